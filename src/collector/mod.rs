@@ -65,6 +65,26 @@ pub trait Collector {
 
     /// Returns true iff the collector requires to compute scores for documents.
     fn requires_scoring(&self) -> bool;
+
+    /// Returns true iff the collector only keeps the top documents
+    /// by score, and reports the score they must exceed through
+    /// `.score_threshold()`.
+    ///
+    /// Queries then use scorers that skip the documents that cannot
+    /// possibly make it to the top documents.
+    fn is_top_k(&self) -> bool {
+        false
+    }
+
+    /// Returns a score threshold, if the collector is not interested
+    /// in documents with a score lower or equal to it.
+    ///
+    /// Scorers may rely on it to skip documents that cannot
+    /// possibly reach this threshold.
+    /// The threshold may change after each call to `.collect(...)`.
+    fn score_threshold(&self) -> Option<Score> {
+        None
+    }
 }
 
 impl<'a, C: Collector> Collector for &'a mut C {
@@ -83,15 +103,24 @@ impl<'a, C: Collector> Collector for &'a mut C {
     fn requires_scoring(&self) -> bool {
         C::requires_scoring(self)
     }
+
+    fn is_top_k(&self) -> bool {
+        C::is_top_k(self)
+    }
+
+    fn score_threshold(&self) -> Option<Score> {
+        C::score_threshold(self)
+    }
 }
 
 #[cfg(test)]
 pub mod tests {
 
     use super::*;
-    use core::SegmentReader;
+    use core::{Searcher, SegmentReader};
     use fastfield::BytesFastFieldReader;
     use fastfield::FastFieldReader;
+    use query::Query;
     use schema::Field;
     use DocId;
     use Score;
@@ -117,6 +146,11 @@ pub mod tests {
         pub fn scores(self) -> Vec<Score> {
             self.scores
         }
+
+        /// Returns the documents along with their scores.
+        pub fn docs_and_scores(self) -> Vec<(DocId, Score)> {
+            self.docs.into_iter().zip(self.scores).collect()
+        }
     }
 
     impl Default for TestCollector {
@@ -147,6 +181,30 @@ pub mod tests {
         }
     }
 
+    fn test_collector_search(searcher: &Searcher, query: &Query) -> TestCollector {
+        let mut test_collector = TestCollector::default();
+        searcher.search(query, &mut test_collector).unwrap();
+        test_collector
+    }
+
+    /// Returns the documents matching the query,
+    /// as collected by a `TestCollector`.
+    pub fn matching_docs(searcher: &Searcher, query: &Query) -> Vec<DocId> {
+        test_collector_search(searcher, query).docs()
+    }
+
+    /// Returns the scores of the documents matching the query,
+    /// as collected by a `TestCollector`.
+    pub fn matching_scores(searcher: &Searcher, query: &Query) -> Vec<Score> {
+        test_collector_search(searcher, query).scores()
+    }
+
+    /// Returns the documents matching the query along with their scores,
+    /// as collected by a `TestCollector`.
+    pub fn matching_docs_and_scores(searcher: &Searcher, query: &Query) -> Vec<(DocId, Score)> {
+        test_collector_search(searcher, query).docs_and_scores()
+    }
+
     /// Collects in order all of the fast fields for all of the
     /// doc in the `DocSet`
     ///
@@ -129,6 +129,18 @@ impl Collector for TopCollector {
     fn requires_scoring(&self) -> bool {
         true
     }
+
+    fn is_top_k(&self) -> bool {
+        true
+    }
+
+    fn score_threshold(&self) -> Option<Score> {
+        if self.at_capacity() {
+            self.heap.peek().map(|limit_doc| limit_doc.score)
+        } else {
+            None
+        }
+    }
 }
 
 #[cfg(test)]
@@ -146,6 +158,7 @@ mod tests {
         top_collector.collect(3, 0.2);
         top_collector.collect(5, 0.3);
         assert!(!top_collector.at_capacity());
+        assert_eq!(top_collector.score_threshold(), None);
         let score_docs: Vec<(Score, DocId)> = top_collector
             .score_docs()
             .into_iter()
@@ -163,6 +176,7 @@ mod tests {
         top_collector.collect(7, 0.9);
         top_collector.collect(9, -0.2);
         assert!(top_collector.at_capacity());
+        assert_eq!(top_collector.score_threshold(), Some(0.2));
         {
             let score_docs: Vec<(Score, DocId)> = top_collector
                 .score_docs()
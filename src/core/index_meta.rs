@@ -46,8 +46,12 @@ impl fmt::Debug for IndexMeta {
 mod tests {
 
     use super::IndexMeta;
+    use core::META_FILEPATH;
+    use directory::{Directory, ManagedDirectory, RAMDirectory};
+    use error::ErrorKind;
     use schema::{SchemaBuilder, TEXT};
     use serde_json;
+    use Index;
 
     #[test]
     fn test_serialize_metas() {
@@ -65,4 +69,32 @@ mod tests {
         let json = serde_json::ser::to_string(&index_metas).expect("serialization failed");
         assert_eq!(json, r#"{"segments":[],"schema":[{"name":"text","type":"text","options":{"indexing":{"record":"position","tokenizer":"default"},"stored":false}}],"opstamp":0}"#);
     }
+
+    #[test]
+    fn test_reject_unversioned_postings() {
+        let mut schema_builder = SchemaBuilder::new();
+        let text_field = schema_builder.add_text_field("text", TEXT);
+        let schema = schema_builder.build();
+        let mut directory = RAMDirectory::create();
+        {
+            let managed_directory = ManagedDirectory::new(directory.clone()).unwrap();
+            let index = Index::from_directory(managed_directory, schema).unwrap();
+            let mut index_writer = index.writer_with_num_threads(1, 40_000_000).unwrap();
+            index_writer.add_document(doc!(text_field => "a"));
+            index_writer.commit().unwrap();
+            index_writer.wait_merging_threads().unwrap();
+        }
+        assert!(Index::open_directory(directory.clone()).is_ok());
+        // segments written before the postings were versioned
+        // have no postings format version.
+        let meta_json = String::from_utf8(directory.atomic_read(&META_FILEPATH).unwrap()).unwrap();
+        let version_json = ",\n      \"postings_format_version\": 1";
+        assert!(meta_json.contains(version_json));
+        let meta_json = meta_json.replace(version_json, "");
+        directory
+            .atomic_write(&META_FILEPATH, meta_json.as_bytes())
+            .unwrap();
+        let err = Index::open_directory(directory).err().unwrap();
+        assert_matches!(err.kind(), &ErrorKind::IncompatibleIndex(_));
+    }
 }
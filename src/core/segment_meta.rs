@@ -1,5 +1,6 @@
 use super::SegmentComponent;
 use core::SegmentId;
+use postings::POSTINGS_FORMAT_VERSION;
 use std::collections::HashSet;
 use std::path::PathBuf;
 
@@ -18,6 +19,10 @@ pub struct SegmentMeta {
     segment_id: SegmentId,
     max_doc: u32,
     deletes: Option<DeleteMeta>,
+    // absent from the segments written before
+    // the postings were versioned.
+    #[serde(default)]
+    postings_format_version: u32,
 }
 
 impl SegmentMeta {
@@ -28,6 +33,7 @@ impl SegmentMeta {
             segment_id,
             max_doc: 0,
             deletes: None,
+            postings_format_version: POSTINGS_FORMAT_VERSION,
         }
     }
 
@@ -36,6 +42,13 @@ impl SegmentMeta {
         self.segment_id
     }
 
+    /// Returns the version of the format of the postings of the segment.
+    ///
+    /// See [`POSTINGS_FORMAT_VERSION`](../postings/constant.POSTINGS_FORMAT_VERSION.html).
+    pub fn postings_format_version(&self) -> u32 {
+        self.postings_format_version
+    }
+
     /// Returns the number of deleted documents.
     pub fn num_deleted_docs(&self) -> u32 {
         self.deletes
@@ -9,9 +9,11 @@ use error::ErrorKind;
 use fastfield::DeleteBitSet;
 use fastfield::FacetReader;
 use fastfield::FastFieldReader;
+use fastfield::GeoPointFastFieldReader;
 use fastfield::{self, FastFieldNotAvailableError};
 use fastfield::{BytesFastFieldReader, FastValue, MultiValueIntFastFieldReader};
 use fieldnorm::FieldNormReader;
+use postings::POSTINGS_FORMAT_VERSION;
 use schema::Cardinality;
 use schema::Document;
 use schema::Field;
@@ -166,6 +168,22 @@ impl SegmentReader {
         Ok(BytesFastFieldReader::open(idx_reader, values))
     }
 
+    /// Accessor to the `GeoPointFastFieldReader` associated to a given `Field`.
+    pub fn geo_point_fast_field_reader(
+        &self,
+        field: Field,
+    ) -> fastfield::Result<GeoPointFastFieldReader> {
+        let field_entry = self.schema.get_field_entry(field);
+        if field_entry.field_type() != &FieldType::GeoPoint {
+            return Err(FastFieldNotAvailableError::new(field_entry));
+        }
+        self.fast_fields_composite
+            .open_read(field)
+            .ok_or_else(|| FastFieldNotAvailableError::new(field_entry))
+            .map(FastFieldReader::open)
+            .map(GeoPointFastFieldReader::open)
+    }
+
     /// Accessor to the `FacetReader` associated to a given `Field`.
     pub fn facet_reader(&self, field: Field) -> Result<FacetReader> {
         let field_entry = self.schema.get_field_entry(field);
@@ -217,7 +235,20 @@ impl SegmentReader {
     }
 
     /// Open a new segment for reading.
+    ///
+    /// Returns an `IncompatibleIndex` error if the postings of the
+    /// segment were written in a format that is not supported anymore.
     pub fn open(segment: &Segment) -> Result<SegmentReader> {
+        let postings_format_version = segment.meta().postings_format_version();
+        if postings_format_version != POSTINGS_FORMAT_VERSION {
+            bail!(ErrorKind::IncompatibleIndex(format!(
+                "the postings of segment {} are in version {} of the format, \
+                 when only version {} is supported. The index needs to be rebuilt.",
+                segment.id().uuid_string(),
+                postings_format_version,
+                POSTINGS_FORMAT_VERSION
+            )));
+        }
         let termdict_source = segment.open_read(SegmentComponent::TERMS)?;
         let termdict_composite = CompositeFile::open(&termdict_source)?;
 
@@ -53,6 +53,18 @@ error_chain!(
             description("the schema is not matching expectations.")
             display("Schema error: '{}'", message)
         }
+        /// A multiterm query (e.g. a prefix or a wildcard query) expanded into
+        /// more terms than allowed.
+        TooManyTerms(max_expansions: usize) {
+            description("the query expanded into too many terms")
+            display("the query expanded into more than {} terms", max_expansions)
+        }
+        /// The index contains segments written in a format
+        /// that is not supported by this version of tantivy.
+        IncompatibleIndex(message: String) {
+            description("the index format is not supported")
+            display("incompatible index: '{}'", message)
+        }
         /// Tried to access a fastfield reader for a field not configured accordingly.
         FastFieldError(err: FastFieldNotAvailableError) {
             description("fast field not available")
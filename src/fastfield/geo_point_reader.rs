@@ -0,0 +1,35 @@
+use super::FastFieldReader;
+use schema::GeoPoint;
+use DocId;
+
+/// Reader for the geo points of a `GeoPoint` field.
+///
+/// Geo points are encoded as `u64` in a regular
+/// single-valued fast field. Documents without any geo point
+/// are associated to the value `0`.
+pub struct GeoPointFastFieldReader {
+    ff_reader: FastFieldReader<u64>,
+}
+
+impl GeoPointFastFieldReader {
+    pub(crate) fn open(ff_reader: FastFieldReader<u64>) -> GeoPointFastFieldReader {
+        GeoPointFastFieldReader { ff_reader }
+    }
+
+    /// Returns the geo point associated to the document,
+    /// or `None` if the document does not have any geo point.
+    ///
+    /// If the document has more than one geo point,
+    /// only the first one is returned.
+    pub fn get(&self, doc: DocId) -> Option<GeoPoint> {
+        GeoPoint::from_u64(self.ff_reader.get(doc))
+    }
+
+    /// Returns the distance, in meters, between the geo point of
+    /// the document and `origin`, computed with the haversine formula.
+    ///
+    /// Returns `None` if the document does not have any geo point.
+    pub fn distance(&self, doc: DocId, origin: &GeoPoint) -> Option<f64> {
+        self.get(doc).map(|geo_point| origin.distance(&geo_point))
+    }
+}
@@ -14,7 +14,8 @@ the `DocSet` : for instance for scoring, grouping, filtering, or faceting.
 
 Fields have to be declared as `FAST` in the  schema.
 Currently only 64-bits integers (signed or unsigned) are
-supported.
+supported, as well as bytes and geo points, which are
+always stored as fast fields.
 
 They are stored in a bit-packed fashion so that their
 memory usage is directly linear with the amplitude of the
@@ -28,6 +29,7 @@ pub use self::delete::write_delete_bitset;
 pub use self::delete::DeleteBitSet;
 pub use self::error::{FastFieldNotAvailableError, Result};
 pub use self::facet_reader::FacetReader;
+pub use self::geo_point_reader::GeoPointFastFieldReader;
 pub use self::multivalued::{MultiValueIntFastFieldReader, MultiValueIntFastFieldWriter};
 pub use self::reader::FastFieldReader;
 pub use self::serializer::FastFieldSerializer;
@@ -41,6 +43,7 @@ mod bytes;
 mod delete;
 mod error;
 mod facet_reader;
+mod geo_point_reader;
 mod multivalued;
 mod reader;
 mod serializer;
@@ -116,7 +119,8 @@ fn value_to_u64(value: &Value) -> u64 {
     match *value {
         Value::U64(ref val) => *val,
         Value::I64(ref val) => common::i64_to_u64(*val),
-        _ => panic!("Expected a u64/i64 field, got {:?} ", value),
+        Value::GeoPoint(ref geo_point) => geo_point.to_u64(),
+        _ => panic!("Expected a u64/i64 or geo point field, got {:?} ", value),
     }
 }
 
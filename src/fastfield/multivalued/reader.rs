@@ -34,6 +34,12 @@ impl<Item: FastValue> MultiValueIntFastFieldReader<Item> {
         (start, stop)
     }
 
+    /// Returns the number of values associated to the given `doc`.
+    pub fn num_vals(&self, doc: DocId) -> usize {
+        let (start, stop) = self.range(doc);
+        (stop - start) as usize
+    }
+
     /// Returns the array of values associated to the given `doc`.
     pub fn get_vals(&self, doc: DocId, vals: &mut Vec<Item>) {
         let (start, stop) = self.range(doc);
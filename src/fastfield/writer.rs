@@ -53,6 +53,11 @@ impl FastFieldsWriter {
                     let fast_field_writer = BytesFastFieldWriter::new(field);
                     bytes_value_writers.push(fast_field_writer);
                 }
+                FieldType::GeoPoint => {
+                    // documents without any geo point get the value 0,
+                    // which is never the encoding of a geo point.
+                    single_value_writers.push(IntFastFieldWriter::new(field));
+                }
                 _ => {}
             }
         }
@@ -145,6 +150,8 @@ impl FastFieldsWriter {
 /// Both u64, and i64 use the same writer.
 /// i64 are just remapped to the `0..2^64 - 1`
 /// using `common::i64_to_u64`.
+///
+/// Geo points are encoded as a u64 as well.
 pub struct IntFastFieldWriter {
     field: Field,
     vals: Vec<u8>,
@@ -1,7 +1,9 @@
 use DocId;
 
 use super::fieldnorm_to_id;
+use super::FieldNormReader;
 use super::FieldNormsSerializer;
+use directory::ReadOnlySource;
 use schema::Field;
 use schema::Schema;
 use std::io;
@@ -74,6 +76,20 @@ impl FieldNormsWriter {
         fieldnorm_buffer[doc as usize] = fieldnorm_to_id(fieldnorm);
     }
 
+    /// Returns a `FieldNormReader` over the fieldnorms recorded so far
+    /// for the given field.
+    ///
+    /// Documents in `0..max_doc` that have not been seen are given
+    /// a fieldnorm of 0.
+    pub(crate) fn fieldnorm_reader(&self, field: Field, max_doc: DocId) -> FieldNormReader {
+        let mut fieldnorm_values: Vec<u8> = self.fieldnorms_buffer
+            .get(field.0 as usize)
+            .cloned()
+            .unwrap_or_else(Vec::new);
+        fieldnorm_values.resize(max_doc as usize, 0u8);
+        FieldNormReader::open(ReadOnlySource::from(fieldnorm_values))
+    }
+
     /// Serialize the seen fieldnorm values to the serializer for all fields.
     pub fn serialize(&self, fieldnorms_serializer: &mut FieldNormsSerializer) -> io::Result<()> {
         for &field in self.fields.iter() {
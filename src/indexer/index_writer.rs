@@ -43,8 +43,19 @@ pub const HEAP_SIZE_LIMIT: u32 = MARGIN_IN_BYTES * 3u32;
 // reaches `PIPELINE_MAX_SIZE_IN_DOCS`
 const PIPELINE_MAX_SIZE_IN_DOCS: usize = 10_000;
 
-type DocumentSender = chan::Sender<AddOperation>;
-type DocumentReceiver = chan::Receiver<AddOperation>;
+/// Maximum number of documents of a block added with
+/// [`IndexWriter::add_document_block`](./struct.IndexWriter.html#method.add_document_block).
+///
+/// As a segment is only closed between blocks, the margins of the memory arena
+/// and of the term hash table must be large enough for a whole block.
+pub const MAX_DOCUMENT_BLOCK_SIZE: usize = 1_000;
+
+// Documents are sent to the indexing workers by batches of operations
+// that must be indexed contiguously in the same segment.
+//
+// A batch contains a single document, unless it was added as a block.
+type DocumentSender = chan::Sender<Vec<AddOperation>>;
+type DocumentReceiver = chan::Receiver<Vec<AddOperation>>;
 
 /// `IndexWriter` is the user entry-point to add document to an index.
 ///
@@ -243,7 +254,7 @@ fn index_documents(
     table_size: usize,
     segment: &Segment,
     generation: usize,
-    document_iterator: &mut Iterator<Item = AddOperation>,
+    document_iterator: &mut Iterator<Item = Vec<AddOperation>>,
     segment_updater: &mut SegmentUpdater,
     mut delete_cursor: DeleteCursor,
 ) -> Result<bool> {
@@ -252,8 +263,12 @@ fn index_documents(
     let segment_id = segment.id();
     let mut segment_writer =
         SegmentWriter::for_segment(heap, table_size, segment.clone(), &schema)?;
-    for doc in document_iterator {
-        segment_writer.add_document(doc, &schema)?;
+    for add_operations in document_iterator {
+        // the segment is only closed after a complete batch,
+        // so that blocks of documents are never split.
+        for add_operation in add_operations {
+            segment_writer.add_document(add_operation, &schema)?;
+        }
         // There is two possible conditions to close the segment.
         // One is the memory arena dedicated to the segment is
         // getting full.
@@ -392,8 +407,9 @@ impl IndexWriter {
                     // this is a valid guarantee as the
                     // peeked document now belongs to
                     // our local iterator.
-                    if let Some(operation) = document_iterator.peek() {
-                        delete_cursor.skip_to(operation.opstamp);
+                    if let Some(operations) = document_iterator.peek() {
+                        // batches are never empty.
+                        delete_cursor.skip_to(operations[0].opstamp);
                     } else {
                         // No more documents.
                         // Happens when there is a commit, or if the `IndexWriter`
@@ -629,9 +645,46 @@ impl IndexWriter {
     pub fn add_document(&mut self, document: Document) -> u64 {
         let opstamp = self.stamper.stamp();
         let add_operation = AddOperation { opstamp, document };
-        self.document_sender.send(add_operation);
+        self.document_sender.send(vec![add_operation]);
         opstamp
     }
+
+    /// Adds a block of documents.
+    ///
+    /// The documents of a block are guaranteed to be indexed
+    /// in the same segment, with contiguous doc ids, in the order of the block.
+    /// Merges preserve this contiguity, as long as the documents of a block
+    /// are deleted all together.
+    ///
+    /// Blocks are typically used to index nested documents, as required by
+    /// [`ToParentBlockJoinQuery`](../query/struct.ToParentBlockJoinQuery.html) and
+    /// [`ToChildBlockJoinQuery`](../query/struct.ToChildBlockJoinQuery.html):
+    /// the child documents come first, followed by their parent document.
+    ///
+    /// The opstamp of the last document of the block is returned.
+    ///
+    /// Returns an `InvalidArgument` error if the block is empty,
+    /// or contains more than [`MAX_DOCUMENT_BLOCK_SIZE`](./constant.MAX_DOCUMENT_BLOCK_SIZE.html)
+    /// documents.
+    pub fn add_document_block(&mut self, documents: Vec<Document>) -> Result<u64> {
+        if documents.is_empty() || documents.len() > MAX_DOCUMENT_BLOCK_SIZE {
+            bail!(ErrorKind::InvalidArgument(format!(
+                "A block must contain between 1 and {} documents, got {}.",
+                MAX_DOCUMENT_BLOCK_SIZE,
+                documents.len()
+            )));
+        }
+        let add_operations: Vec<AddOperation> = documents
+            .into_iter()
+            .map(|document| AddOperation {
+                opstamp: self.stamper.stamp(),
+                document,
+            })
+            .collect();
+        let opstamp = add_operations[add_operations.len() - 1].opstamp;
+        self.document_sender.send(add_operations);
+        Ok(opstamp)
+    }
 }
 
 #[cfg(test)]
@@ -640,6 +693,7 @@ mod tests {
     use env_logger;
     use error::*;
     use indexer::NoMergePolicy;
+    use indexer::MAX_DOCUMENT_BLOCK_SIZE;
     use schema::{self, Document};
     use Index;
     use Term;
@@ -673,6 +727,37 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_add_document_block_size() {
+        let mut schema_builder = schema::SchemaBuilder::default();
+        let text_field = schema_builder.add_text_field("text", schema::TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_with_num_threads(1, 40_000_000).unwrap();
+        let block = |num_docs: usize| -> Vec<Document> {
+            (0..num_docs).map(|_| doc!(text_field => "a")).collect()
+        };
+        assert_matches!(
+            index_writer
+                .add_document_block(block(0))
+                .unwrap_err()
+                .kind(),
+            &ErrorKind::InvalidArgument(_)
+        );
+        assert_matches!(
+            index_writer
+                .add_document_block(block(MAX_DOCUMENT_BLOCK_SIZE + 1))
+                .unwrap_err()
+                .kind(),
+            &ErrorKind::InvalidArgument(_)
+        );
+        assert_eq!(
+            index_writer
+                .add_document_block(block(MAX_DOCUMENT_BLOCK_SIZE))
+                .unwrap(),
+            MAX_DOCUMENT_BLOCK_SIZE as u64 - 1
+        );
+    }
+
     #[test]
     fn test_lockfile_released_on_drop() {
         let schema_builder = schema::SchemaBuilder::default();
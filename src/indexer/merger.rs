@@ -1,6 +1,7 @@
 use core::Segment;
 use core::SegmentReader;
 use core::SerializableSegment;
+use directory::ReadOnlySource;
 use docset::DocSet;
 use error::Result;
 use fastfield::DeleteBitSet;
@@ -159,20 +160,26 @@ impl IndexMerger {
         })
     }
 
+    /// Stacks the fieldnorms of the non-deleted documents of all of the segments,
+    /// following the doc ids of the merged segment.
+    fn merged_fieldnorms(&self, field: Field, fieldnorms_data: &mut Vec<u8>) {
+        fieldnorms_data.clear();
+        for reader in &self.readers {
+            let fieldnorms_reader = reader.get_fieldnorms_reader(field);
+            for doc_id in 0..reader.max_doc() {
+                if !reader.is_deleted(doc_id) {
+                    let fieldnorm_id = fieldnorms_reader.fieldnorm_id(doc_id);
+                    fieldnorms_data.push(fieldnorm_id);
+                }
+            }
+        }
+    }
+
     fn write_fieldnorms(&self, fieldnorms_serializer: &mut FieldNormsSerializer) -> Result<()> {
         let fields = FieldNormsWriter::fields_with_fieldnorm(&self.schema);
         let mut fieldnorms_data = Vec::with_capacity(self.max_doc as usize);
         for field in fields {
-            fieldnorms_data.clear();
-            for reader in &self.readers {
-                let fieldnorms_reader = reader.get_fieldnorms_reader(field);
-                for doc_id in 0..reader.max_doc() {
-                    if !reader.is_deleted(doc_id) {
-                        let fieldnorm_id = fieldnorms_reader.fieldnorm_id(doc_id);
-                        fieldnorms_data.push(fieldnorm_id);
-                    }
-                }
-            }
+            self.merged_fieldnorms(field, &mut fieldnorms_data);
             fieldnorms_serializer.serialize_field(field, &fieldnorms_data[..])?;
         }
         Ok(())
@@ -217,12 +224,15 @@ impl IndexMerger {
                 FieldType::Bytes => {
                     self.write_bytes_fast_field(field, fast_field_serializer)?;
                 }
+                FieldType::GeoPoint => {
+                    self.write_single_fast_field(field, fast_field_serializer)?;
+                }
             }
         }
         Ok(())
     }
 
-    // used both to merge field norms, `u64/i64` single fast fields.
+    // used both to merge field norms, `u64/i64` single fast fields and geo points.
     fn write_single_fast_field(
         &self,
         field: Field,
@@ -233,7 +243,7 @@ impl IndexMerger {
         let mut max_value = u64::min_value();
 
         for reader in &self.readers {
-            let u64_reader: FastFieldReader<u64> = reader.fast_field_reader(field)?;
+            let u64_reader: FastFieldReader<u64> = reader.fast_field_reader_with_idx(field, 0)?;
             if let Some((seg_min_val, seg_max_val)) =
                 compute_min_max_val(&u64_reader, reader.max_doc(), reader.delete_bitset())
             {
@@ -478,6 +488,9 @@ impl IndexMerger {
         let mut max_doc = 0;
 
         // map from segment doc ids to the resulting merged segment doc id.
+        //
+        // Segments are stacked, and the order of the documents is preserved,
+        // so that blocks of documents remain contiguous after merging.
         let mut merged_doc_id_map: Vec<Vec<Option<DocId>>> = Vec::with_capacity(self.readers.len());
 
         for reader in &self.readers {
@@ -508,7 +521,11 @@ impl IndexMerger {
         // - Segment 2's doc ids become  [seg0.max_doc + seg1.max_doc,
         //                                seg0.max_doc + seg1.max_doc + seg2.max_doc]
         // ...
-        let mut field_serializer = serializer.new_field(indexed_field, total_num_tokens)?;
+        let mut fieldnorms_data = Vec::with_capacity(self.max_doc as usize);
+        self.merged_fieldnorms(indexed_field, &mut fieldnorms_data);
+        let fieldnorm_reader = FieldNormReader::open(ReadOnlySource::from(fieldnorms_data));
+        let mut field_serializer =
+            serializer.new_field(indexed_field, total_num_tokens, fieldnorm_reader)?;
 
         let field_entry = self.schema.get_field_entry(indexed_field);
 
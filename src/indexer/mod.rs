@@ -16,7 +16,7 @@ mod segment_writer;
 mod stamper;
 
 pub(crate) use self::directory_lock::DirectoryLock;
-pub use self::index_writer::IndexWriter;
+pub use self::index_writer::{IndexWriter, MAX_DOCUMENT_BLOCK_SIZE};
 pub use self::log_merge_policy::LogMergePolicy;
 pub use self::merge_policy::{MergeCandidate, MergePolicy, NoMergePolicy};
 pub use self::prepared_commit::PreparedCommit;
@@ -89,6 +89,7 @@ impl<'a> SegmentWriter<'a> {
             &self.multifield_postings,
             &self.fast_field_writers,
             &self.fieldnorms_writer,
+            self.max_doc,
             self.segment_serializer,
         )?;
         Ok(self.doc_opstamps)
@@ -201,8 +202,8 @@ impl<'a> SegmentWriter<'a> {
                         }
                     }
                 }
-                FieldType::Bytes => {
-                    // Do nothing. Bytes only supports fast fields.
+                FieldType::Bytes | FieldType::GeoPoint => {
+                    // Do nothing. Bytes and geo points only support fast fields.
                 }
             }
         }
@@ -239,9 +240,14 @@ fn write(
     multifield_postings: &MultiFieldPostingsWriter,
     fast_field_writers: &FastFieldsWriter,
     fieldnorms_writer: &FieldNormsWriter,
+    max_doc: DocId,
     mut serializer: SegmentSerializer,
 ) -> Result<()> {
-    let term_ord_map = multifield_postings.serialize(serializer.get_postings_serializer())?;
+    let term_ord_map = multifield_postings.serialize(
+        serializer.get_postings_serializer(),
+        fieldnorms_writer,
+        max_doc,
+    )?;
     fast_field_writers.serialize(serializer.get_fast_field_serializer(), &term_ord_map)?;
     fieldnorms_writer.serialize(serializer.get_fieldnorms_serializer())?;
     serializer.close()?;
@@ -255,6 +261,7 @@ impl<'a> SerializableSegment for SegmentWriter<'a> {
             &self.multifield_postings,
             &self.fast_field_writers,
             &self.fieldnorms_writer,
+            max_doc,
             serializer,
         )?;
         Ok(max_doc)
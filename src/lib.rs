@@ -135,9 +135,11 @@ extern crate chan;
 extern crate combine;
 extern crate crossbeam;
 extern crate fst;
+extern crate fst_regex;
 extern crate futures;
 extern crate futures_cpupool;
 extern crate itertools;
+extern crate levenshtein_automata;
 extern crate lz4;
 extern crate num_cpus;
 extern crate owning_ref;
@@ -195,6 +197,7 @@ pub mod collector;
 pub mod directory;
 pub mod fastfield;
 pub mod fieldnorm;
+pub mod percolator;
 pub mod postings;
 pub mod query;
 pub mod schema;
@@ -208,7 +211,7 @@ pub use core::SegmentComponent;
 pub use core::{Index, Searcher, Segment, SegmentId, SegmentMeta};
 pub use core::{InvertedIndexReader, SegmentReader};
 pub use directory::Directory;
-pub use indexer::IndexWriter;
+pub use indexer::{IndexWriter, MAX_DOCUMENT_BLOCK_SIZE};
 pub use postings::Postings;
 pub use schema::{Document, Term};
 
@@ -271,7 +274,7 @@ impl DocAddress {
 ///
 /// The id used for the segment is actually an ordinal
 /// in the list of segment hold by a `Searcher`.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct DocAddress(pub SegmentLocalId, pub DocId);
 
 #[cfg(test)]
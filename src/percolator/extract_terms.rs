@@ -0,0 +1,191 @@
+use query::QueryDsl;
+use schema::{Schema, Term};
+use std::collections::BTreeSet;
+
+/// Returns the terms of a query, one of which at least must
+/// be present in a document for the query to match it.
+///
+/// `None` is returned if the query may match a document
+/// without containing any of its terms, as it is the case for
+/// instance for range queries, or for boolean queries combining
+/// an `all` query with `must_not` clauses.
+///
+/// An empty set is returned for queries that cannot match any
+/// document, such as boolean queries with `must_not` clauses only.
+///
+/// For conjunctions, the terms of the clause having the fewest
+/// terms are returned, so that the terms are as selective as possible.
+pub(crate) fn extract_terms(query_dsl: &QueryDsl, schema: &Schema) -> Option<BTreeSet<Term>> {
+    match *query_dsl {
+        QueryDsl::Term { .. }
+        | QueryDsl::TermSet { .. }
+        | QueryDsl::Phrase { .. }
+        | QueryDsl::MultiPhrase { .. }
+        | QueryDsl::PhrasePrefix { .. }
+        | QueryDsl::SpanTerm { .. } => {
+            let query = query_dsl.to_query(schema).ok()?;
+            let mut terms = BTreeSet::new();
+            query.query_terms(&mut terms);
+            Some(terms)
+        }
+        QueryDsl::Bool {
+            ref must,
+            ref should,
+            ref filter,
+            ..
+        } => {
+            if must.is_empty() && filter.is_empty() {
+                extract_disjunction_terms(should, schema)
+            } else {
+                must.iter()
+                    .chain(filter)
+                    .flat_map(|query_dsl| extract_terms(query_dsl, schema))
+                    .min_by_key(|terms| terms.len())
+            }
+        }
+        QueryDsl::Boost { ref query, .. }
+        | QueryDsl::ConstantScore { ref query, .. }
+        | QueryDsl::FunctionScore { ref query, .. }
+        | QueryDsl::SpanFirst { ref query, .. }
+        | QueryDsl::SpanNot {
+            include: ref query, ..
+        } => extract_terms(query, schema),
+        QueryDsl::SpanNear { ref clauses, .. } => clauses
+            .iter()
+            .flat_map(|query_dsl| extract_terms(query_dsl, schema))
+            .min_by_key(|terms| terms.len()),
+        QueryDsl::DisjunctionMax { ref queries, .. } => extract_disjunction_terms(queries, schema),
+        QueryDsl::SpanOr { ref clauses } => extract_disjunction_terms(clauses, schema),
+        _ => None,
+    }
+}
+
+/// Returns the union of the terms of the queries of a disjunction,
+/// or `None` if the terms of one of them cannot be extracted.
+fn extract_disjunction_terms(query_dsls: &[QueryDsl], schema: &Schema) -> Option<BTreeSet<Term>> {
+    let mut terms = BTreeSet::new();
+    for query_dsl in query_dsls {
+        terms.extend(extract_terms(query_dsl, schema)?);
+    }
+    Some(terms)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::extract_terms;
+    use query::QueryDsl;
+    use schema::{SchemaBuilder, Term, INT_INDEXED, TEXT};
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn test_extract_terms() {
+        let mut schema_builder = SchemaBuilder::default();
+        let title = schema_builder.add_text_field("title", TEXT);
+        schema_builder.add_u64_field("year", INT_INDEXED);
+        let schema = schema_builder.build();
+        let extract = |json: &str| {
+            let query_dsl = QueryDsl::from_json(json).unwrap();
+            extract_terms(&query_dsl, &schema).map(|terms| {
+                terms
+                    .into_iter()
+                    .map(|term| term.text().to_string())
+                    .collect::<Vec<String>>()
+            })
+        };
+        assert_eq!(
+            extract(r#"{"term": {"field": "title", "value": "rust"}}"#),
+            Some(vec!["rust".to_string()])
+        );
+        assert_eq!(
+            extract(r#"{"phrase": {"field": "title", "terms": ["rust", "lang"]}}"#),
+            Some(vec!["lang".to_string(), "rust".to_string()])
+        );
+        assert_eq!(
+            extract(
+                r#"{
+                    "bool": {
+                        "must": [
+                            {"phrase": {"field": "title", "terms": ["rust", "lang"]}},
+                            {"range": {"field": "year", "gte": 2000}}
+                        ],
+                        "filter": [{"term": {"field": "title", "value": "fast"}}],
+                        "must_not": [{"term": {"field": "title", "value": "slow"}}]
+                    }
+                }"#
+            ),
+            Some(vec!["fast".to_string()])
+        );
+        assert_eq!(
+            extract(
+                r#"{
+                    "disjunction_max": {
+                        "queries": [
+                            {"term": {"field": "title", "value": "rust"}},
+                            {"boost": {
+                                "query": {"term": {"field": "title", "value": "go"}},
+                                "boost": 2.0
+                            }}
+                        ]
+                    }
+                }"#
+            ),
+            Some(vec!["go".to_string(), "rust".to_string()])
+        );
+        assert_eq!(
+            extract(r#"{"bool": {"must_not": [{"term": {"field": "title", "value": "go"}}]}}"#),
+            Some(vec![])
+        );
+        assert_eq!(
+            extract(r#"{"range": {"field": "year", "gte": 2000}}"#),
+            None
+        );
+        assert_eq!(
+            extract(
+                r#"{
+                    "span_near": {
+                        "clauses": [
+                            {"span_or": {"clauses": [
+                                {"span_term": {"field": "title", "value": "rust"}},
+                                {"span_term": {"field": "title", "value": "go"}}
+                            ]}},
+                            {"span_term": {"field": "title", "value": "lang"}},
+                            {"span_prefix": {"field": "title", "prefix": "comp"}}
+                        ],
+                        "slop": 2
+                    }
+                }"#
+            ),
+            Some(vec!["lang".to_string()])
+        );
+        assert_eq!(
+            extract(
+                r#"{
+                    "bool": {
+                        "should": [
+                            {"term": {"field": "title", "value": "rust"}},
+                            {"prefix": {"field": "title", "prefix": "go"}}
+                        ]
+                    }
+                }"#
+            ),
+            None
+        );
+        assert_eq!(
+            extract(
+                r#"{
+                    "bool": {
+                        "must": [{"all": {}}],
+                        "must_not": [{"term": {"field": "title", "value": "go"}}]
+                    }
+                }"#
+            ),
+            None
+        );
+        let mut expected_terms = BTreeSet::new();
+        expected_terms.insert(Term::from_field_text(title, "rust"));
+        let query_dsl =
+            QueryDsl::from_json(r#"{"term": {"field": "title", "value": "rust"}}"#).unwrap();
+        assert_eq!(extract_terms(&query_dsl, &schema), Some(expected_terms));
+    }
+}
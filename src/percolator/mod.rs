@@ -0,0 +1,13 @@
+/*!
+Percolator, matching documents against a set of registered queries.
+
+Where a search matches a query against the indexed documents, the
+percolator does the opposite: queries are registered beforehand, and
+each incoming document is matched against them.
+See [`Percolator`](./struct.Percolator.html).
+*/
+
+mod extract_terms;
+mod percolator;
+
+pub use self::percolator::{Percolation, Percolator, QueryId};
@@ -0,0 +1,446 @@
+use super::extract_terms::extract_terms;
+use core::{Index, Searcher};
+use query::{Query, QueryDsl, QueryDslError};
+use schema::{Document, Field, Schema, Term};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::result;
+use Error;
+use Result;
+
+// Memory arena of the index writer used to index
+// the percolated documents.
+const PERCOLATOR_HEAP_SIZE_IN_BYTES: usize = 3_000_000;
+
+/// Id of a query registered in the `Percolator`.
+pub type QueryId = u64;
+
+/// Result of the percolation of a document.
+#[derive(Debug, Default)]
+pub struct Percolation {
+    /// Ids of the registered queries matching the document, in increasing order.
+    pub matching_query_ids: Vec<QueryId>,
+    /// Ids of the registered queries whose evaluation failed,
+    /// with their error, in increasing order.
+    ///
+    /// These queries are skipped, and do not prevent the
+    /// other queries from being evaluated.
+    pub failed_queries: Vec<(QueryId, Error)>,
+}
+
+struct PercolatorQuery {
+    query_json: String,
+    query: Box<Query>,
+    // `None` if the query is evaluated against all of the documents.
+    terms: Option<Vec<(Field, Vec<u8>)>>,
+}
+
+/// The `Percolator` matches documents against a set of registered queries.
+///
+/// Queries are registered in their serialized form, as a
+/// [`QueryDsl`](../query/enum.QueryDsl.html) JSON.
+/// A typical use case is alerting: users register queries,
+/// and are notified whenever a new document matches them.
+///
+/// # Implementation
+///
+/// Each percolated document is indexed alone in a RAM index,
+/// against which the candidate queries are evaluated.
+///
+/// In order not to evaluate all of the queries for each document,
+/// the terms that a document must contain for a query to match it
+/// are extracted when the query is registered.
+/// The candidate queries of a document are then the queries
+/// with at least one term in the document.
+/// Queries for which no such terms can be extracted (e.g. range queries)
+/// are evaluated against all of the documents.
+///
+/// # Example
+///
+/// ```rust
+/// #[macro_use]
+/// extern crate tantivy;
+/// use tantivy::percolator::Percolator;
+/// use tantivy::schema::{SchemaBuilder, TEXT};
+/// use tantivy::Result;
+///
+/// # fn main() { example().unwrap(); }
+/// fn example() -> Result<()> {
+///     let mut schema_builder = SchemaBuilder::new();
+///     let title = schema_builder.add_text_field("title", TEXT);
+///     let mut percolator = Percolator::new(schema_builder.build());
+///     percolator
+///         .add_query(1, r#"{"term": {"field": "title", "value": "rust"}}"#)
+///         .unwrap();
+///     percolator
+///         .add_query(2, r#"{"term": {"field": "title", "value": "python"}}"#)
+///         .unwrap();
+///     let percolation = percolator.percolate(&doc!(title => "Rust programming"))?;
+///     assert_eq!(percolation.matching_query_ids, vec![1]);
+///     Ok(())
+/// }
+/// ```
+pub struct Percolator {
+    schema: Schema,
+    queries: BTreeMap<QueryId, PercolatorQuery>,
+    // ids of the queries, for each of their extracted terms.
+    query_ids_per_term: HashMap<Field, HashMap<Vec<u8>, BTreeSet<QueryId>>>,
+    // ids of the queries that are evaluated against all of the documents.
+    unfiltered_query_ids: BTreeSet<QueryId>,
+}
+
+impl Percolator {
+    /// Creates a new `Percolator` with no query, for
+    /// documents of the given schema.
+    pub fn new(schema: Schema) -> Percolator {
+        Percolator {
+            schema,
+            queries: BTreeMap::new(),
+            query_ids_per_term: HashMap::new(),
+            unfiltered_query_ids: BTreeSet::new(),
+        }
+    }
+
+    /// Registers a query, given its JSON `QueryDsl` representation.
+    ///
+    /// If a query was already registered with the same id, it is replaced.
+    ///
+    /// Queries that cannot be evaluated against the documents of the
+    /// schema, for instance term queries on a field that is not indexed,
+    /// or phrase queries on a field without positions, are rejected
+    /// with an `InvalidQuery` error.
+    pub fn add_query(
+        &mut self,
+        query_id: QueryId,
+        query_json: &str,
+    ) -> result::Result<(), QueryDslError> {
+        let query_dsl = QueryDsl::from_json(query_json)?;
+        let query = query_dsl.to_query(&self.schema)?;
+        let extracted_terms = extract_terms(&query_dsl, &self.schema);
+        self.validate_query(&*query, &extracted_terms)?;
+        self.remove_query(query_id);
+        let terms = extracted_terms.map(|terms| {
+            terms
+                .into_iter()
+                .map(|term| (term.field(), term.value_bytes().to_owned()))
+                .collect::<Vec<_>>()
+        });
+        if let Some(ref terms) = terms {
+            for &(field, ref value_bytes) in terms {
+                self.query_ids_per_term
+                    .entry(field)
+                    .or_insert_with(HashMap::new)
+                    .entry(value_bytes.clone())
+                    .or_insert_with(BTreeSet::new)
+                    .insert(query_id);
+            }
+        } else {
+            self.unfiltered_query_ids.insert(query_id);
+        }
+        self.queries.insert(
+            query_id,
+            PercolatorQuery {
+                query_json: query_dsl.to_json(),
+                query,
+                terms,
+            },
+        );
+        Ok(())
+    }
+
+    /// Checks that the terms of the query belong to indexed fields, and
+    /// that the weight of the query can be built, so that the query
+    /// does not fail on the percolation of every document.
+    fn validate_query(
+        &self,
+        query: &Query,
+        extracted_terms: &Option<BTreeSet<Term>>,
+    ) -> result::Result<(), QueryDslError> {
+        let invalid_query = |message: String| QueryDslError::InvalidQuery {
+            path: "$".to_string(),
+            message,
+        };
+        let mut terms = BTreeSet::new();
+        query.query_terms(&mut terms);
+        if let Some(ref extracted_terms) = *extracted_terms {
+            terms.extend(extracted_terms.iter().cloned());
+        }
+        for term in &terms {
+            let field_entry = self.schema.get_field_entry(term.field());
+            if !field_entry.is_indexed() {
+                return Err(invalid_query(format!(
+                    "Field {:?} is not indexed",
+                    field_entry.name()
+                )));
+            }
+        }
+        let index = Index::create_in_ram(self.schema.clone());
+        index
+            .load_searchers()
+            .and_then(|_| query.weight(&*index.searcher(), true))
+            .map_err(|err| invalid_query(err.to_string()))?;
+        Ok(())
+    }
+
+    /// Unregisters a query.
+    ///
+    /// Returns `false` if there was no query with the given id.
+    pub fn remove_query(&mut self, query_id: QueryId) -> bool {
+        let percolator_query = match self.queries.remove(&query_id) {
+            Some(percolator_query) => percolator_query,
+            None => {
+                return false;
+            }
+        };
+        if let Some(terms) = percolator_query.terms {
+            for (field, value_bytes) in terms {
+                let is_field_empty = {
+                    let query_ids_per_value = self.query_ids_per_term.get_mut(&field).unwrap();
+                    let is_value_empty = {
+                        let query_ids = query_ids_per_value.get_mut(&value_bytes).unwrap();
+                        query_ids.remove(&query_id);
+                        query_ids.is_empty()
+                    };
+                    if is_value_empty {
+                        query_ids_per_value.remove(&value_bytes);
+                    }
+                    query_ids_per_value.is_empty()
+                };
+                if is_field_empty {
+                    self.query_ids_per_term.remove(&field);
+                }
+            }
+        } else {
+            self.unfiltered_query_ids.remove(&query_id);
+        }
+        true
+    }
+
+    /// Returns the JSON `QueryDsl` representation of a registered query.
+    pub fn query_json(&self, query_id: QueryId) -> Option<&str> {
+        self.queries
+            .get(&query_id)
+            .map(|percolator_query| percolator_query.query_json.as_str())
+    }
+
+    /// Returns the number of registered queries.
+    pub fn num_queries(&self) -> usize {
+        self.queries.len()
+    }
+
+    /// Evaluates the registered queries against the document.
+    ///
+    /// The queries whose evaluation fails are reported
+    /// in the returned `Percolation`. An error is only returned
+    /// if the document itself cannot be indexed.
+    pub fn percolate(&self, doc: &Document) -> Result<Percolation> {
+        let index = Index::create_in_ram(self.schema.clone());
+        {
+            let mut index_writer =
+                index.writer_with_num_threads(1, PERCOLATOR_HEAP_SIZE_IN_BYTES)?;
+            index_writer.add_document(doc.clone());
+            index_writer.commit()?;
+        }
+        index.load_searchers()?;
+        let searcher = index.searcher();
+        let mut percolation = Percolation::default();
+        for query_id in self.candidate_query_ids(&*searcher) {
+            match self.queries[&query_id].query.count(&*searcher) {
+                Ok(count) => {
+                    if count > 0 {
+                        percolation.matching_query_ids.push(query_id);
+                    }
+                }
+                Err(err) => percolation.failed_queries.push((query_id, err)),
+            }
+        }
+        Ok(percolation)
+    }
+
+    /// Returns the ids of the queries that may match the document
+    /// indexed in the searcher.
+    fn candidate_query_ids(&self, searcher: &Searcher) -> BTreeSet<QueryId> {
+        let mut candidate_query_ids = self.unfiltered_query_ids.clone();
+        for segment_reader in searcher.segment_readers() {
+            for (&field, query_ids_per_value) in &self.query_ids_per_term {
+                let inverted_index = segment_reader.inverted_index(field);
+                let mut term_stream = inverted_index.terms().stream();
+                while term_stream.advance() {
+                    if let Some(query_ids) = query_ids_per_value.get(term_stream.key()) {
+                        candidate_query_ids.extend(query_ids);
+                    }
+                }
+            }
+        }
+        candidate_query_ids
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::Percolator;
+    use error::ErrorKind;
+    use query::QueryDslError;
+    use schema::{SchemaBuilder, INT_INDEXED, STORED, STRING, TEXT};
+    use Index;
+
+    #[test]
+    fn test_percolator() {
+        let mut schema_builder = SchemaBuilder::default();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let year = schema_builder.add_u64_field("year", INT_INDEXED);
+        let mut percolator = Percolator::new(schema_builder.build());
+        for &(query_id, query_json) in &[
+            (1, r#"{"term": {"field": "title", "value": "rust"}}"#),
+            (
+                2,
+                r#"{"phrase": {"field": "title", "terms": ["rust", "programming"]}}"#,
+            ),
+            (
+                3,
+                r#"{"bool": {"must": [{"term": {"field": "title", "value": "python"}}]}}"#,
+            ),
+            (4, r#"{"range": {"field": "year", "gte": 2000}}"#),
+            (
+                5,
+                r#"{
+                    "bool": {
+                        "must": [{"all": {}}],
+                        "must_not": [{"term": {"field": "title", "value": "rust"}}]
+                    }
+                }"#,
+            ),
+            (
+                6,
+                r#"{
+                    "bool": {
+                        "should": [
+                            {"term": {"field": "title", "value": "rust"}},
+                            {"term": {"field": "title", "value": "python"}}
+                        ]
+                    }
+                }"#,
+            ),
+        ] {
+            percolator.add_query(query_id, query_json).unwrap();
+        }
+        assert_eq!(percolator.num_queries(), 6);
+        assert_eq!(
+            percolator
+                .percolate(&doc!(title => "Rust programming", year => 2010u64))
+                .unwrap()
+                .matching_query_ids,
+            vec![1, 2, 4, 6]
+        );
+        assert_eq!(
+            percolator
+                .percolate(&doc!(title => "A python tutorial", year => 1990u64))
+                .unwrap()
+                .matching_query_ids,
+            vec![3, 5, 6]
+        );
+        assert_eq!(
+            percolator
+                .percolate(&doc!(title => "programming rust"))
+                .unwrap()
+                .matching_query_ids,
+            vec![1, 6]
+        );
+
+        assert!(percolator.remove_query(6));
+        assert!(!percolator.remove_query(6));
+        percolator
+            .add_query(1, r#"{"term": {"field": "title", "value": "python"}}"#)
+            .unwrap();
+        assert_eq!(
+            percolator.query_json(1),
+            Some(r#"{"term":{"field":"title","value":"python"}}"#)
+        );
+        assert_eq!(percolator.num_queries(), 5);
+        assert_eq!(
+            percolator
+                .percolate(&doc!(title => "Rust and Python"))
+                .unwrap()
+                .matching_query_ids,
+            vec![1, 3]
+        );
+        assert!(percolator
+            .add_query(7, r#"{"term": {"field": "author", "value": "x"}}"#)
+            .is_err());
+        assert_eq!(percolator.num_queries(), 5);
+    }
+
+    #[test]
+    fn test_percolator_invalid_queries() {
+        let mut schema_builder = SchemaBuilder::default();
+        let title = schema_builder.add_text_field("title", TEXT);
+        schema_builder.add_text_field("author", STORED);
+        schema_builder.add_text_field("tag", STRING);
+        let mut percolator = Percolator::new(schema_builder.build());
+        for query_json in &[
+            r#"{"term": {"field": "author", "value": "x"}}"#,
+            r#"{"bool": {"should": [{"term": {"field": "author", "value": "x"}}]}}"#,
+            r#"{"phrase": {"field": "tag", "terms": ["a", "b"]}}"#,
+        ] {
+            assert_matches!(
+                percolator.add_query(1, query_json),
+                Err(QueryDslError::InvalidQuery { .. })
+            );
+        }
+        assert_eq!(percolator.num_queries(), 0);
+
+        percolator
+            .add_query(1, r#"{"term": {"field": "title", "value": "rust"}}"#)
+            .unwrap();
+        percolator
+            .add_query(
+                2,
+                r#"{"prefix": {"field": "title", "prefix": "ru", "max_expansions": 1}}"#,
+            )
+            .unwrap();
+        let percolation = percolator.percolate(&doc!(title => "rust rules")).unwrap();
+        assert_eq!(percolation.matching_query_ids, vec![1]);
+        assert_eq!(percolation.failed_queries.len(), 1);
+        let (failed_query_id, ref err) = percolation.failed_queries[0];
+        assert_eq!(failed_query_id, 2);
+        assert_matches!(err.kind(), &ErrorKind::TooManyTerms(1));
+    }
+
+    #[test]
+    fn test_percolator_candidate_queries() {
+        let mut schema_builder = SchemaBuilder::default();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let schema = schema_builder.build();
+        let mut percolator = Percolator::new(schema.clone());
+        for &(query_id, text) in &[(1, "rust"), (2, "python"), (3, "go"), (4, "rust")] {
+            let query_json = format!(r#"{{"term": {{"field": "title", "value": "{}"}}}}"#, text);
+            percolator.add_query(query_id, &query_json).unwrap();
+        }
+        percolator
+            .add_query(5, r#"{"prefix": {"field": "title", "prefix": "ru"}}"#)
+            .unwrap();
+        let index = Index::create_in_ram(schema);
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 40_000_000).unwrap();
+            index_writer.add_document(doc!(title => "rust and go"));
+            index_writer.commit().unwrap();
+        }
+        index.load_searchers().unwrap();
+        let candidate_query_ids = |percolator: &Percolator| {
+            percolator
+                .candidate_query_ids(&*index.searcher())
+                .into_iter()
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(candidate_query_ids(&percolator), vec![1, 3, 4, 5]);
+        percolator.remove_query(1);
+        percolator.remove_query(5);
+        assert_eq!(candidate_query_ids(&percolator), vec![3, 4]);
+        percolator.remove_query(3);
+        percolator.remove_query(4);
+        assert!(candidate_query_ids(&percolator).is_empty());
+        percolator.remove_query(2);
+        assert!(percolator.query_ids_per_term.is_empty());
+        assert!(percolator.unfiltered_query_ids.is_empty());
+    }
+}
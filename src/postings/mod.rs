@@ -14,7 +14,7 @@ mod serializer;
 mod term_info;
 
 pub(crate) use self::postings_writer::MultiFieldPostingsWriter;
-pub use self::serializer::{FieldSerializer, InvertedIndexSerializer};
+pub use self::serializer::{FieldSerializer, InvertedIndexSerializer, POSTINGS_FORMAT_VERSION};
 
 pub use self::postings::Postings;
 pub use self::term_info::TermInfo;
@@ -62,7 +62,10 @@ pub mod tests {
         let mut segment = index.new_segment();
         let mut posting_serializer = InvertedIndexSerializer::open(&mut segment).unwrap();
         {
-            let mut field_serializer = posting_serializer.new_field(text_field, 120 * 4).unwrap();
+            let fieldnorm_reader = FieldNormReader::from(vec![4u32; 120]);
+            let mut field_serializer = posting_serializer
+                .new_field(text_field, 120 * 4, fieldnorm_reader)
+                .unwrap();
             field_serializer.new_term("abc".as_bytes()).unwrap();
             for doc_id in 0u32..120u32 {
                 let delta_positions = vec![1, 2, 3, 2];
@@ -1,4 +1,5 @@
 use datastruct::stacker::{Heap, TermHashMap};
+use fieldnorm::FieldNormsWriter;
 use postings::recorder::{NothingRecorder, Recorder, TFAndPositionRecorder, TermFrequencyRecorder};
 use postings::UnorderedTermId;
 use postings::{FieldSerializer, InvertedIndexSerializer};
@@ -36,8 +37,8 @@ fn posting_from_field_entry<'a>(
         FieldType::U64(_) | FieldType::I64(_) | FieldType::HierarchicalFacet => {
             SpecializedPostingsWriter::<NothingRecorder>::new_boxed(heap)
         }
-        FieldType::Bytes => {
-            // FieldType::Bytes cannot actually be indexed.
+        FieldType::Bytes | FieldType::GeoPoint => {
+            // FieldType::Bytes and FieldType::GeoPoint cannot actually be indexed.
             // TODO fix during the indexer refactoring described in #276
             SpecializedPostingsWriter::<NothingRecorder>::new_boxed(heap)
         }
@@ -82,10 +83,15 @@ impl<'a> MultiFieldPostingsWriter<'a> {
     /// Serialize the inverted index.
     /// It pushes all term, one field at a time, towards the
     /// postings serializer.
+    ///
+    /// The fieldnorms of the `max_doc` documents of the segment are
+    /// required to compute the block infos of the postings.
     #[allow(needless_range_loop)]
     pub fn serialize(
         &self,
         serializer: &mut InvertedIndexSerializer,
+        fieldnorms_writer: &FieldNormsWriter,
+        max_doc: DocId,
     ) -> Result<HashMap<Field, HashMap<UnorderedTermId, TermOrdinal>>> {
         let mut term_offsets: Vec<(&[u8], u32, UnorderedTermId)> = self.term_index.iter().collect();
         term_offsets.sort_by_key(|&(k, _, _)| k);
@@ -133,12 +139,16 @@ impl<'a> MultiFieldPostingsWriter<'a> {
                     unordered_term_mappings.insert(field, mapping);
                 }
                 &FieldType::U64(_) | &FieldType::I64(_) => {}
-                &FieldType::Bytes => {}
+                &FieldType::Bytes | &FieldType::GeoPoint => {}
             }
 
             let postings_writer = &self.per_field_postings_writers[field.0 as usize];
-            let mut field_serializer =
-                serializer.new_field(field, postings_writer.total_num_tokens())?;
+            let fieldnorm_reader = fieldnorms_writer.fieldnorm_reader(field, max_doc);
+            let mut field_serializer = serializer.new_field(
+                field,
+                postings_writer.total_num_tokens(),
+                fieldnorm_reader,
+            )?;
             postings_writer.serialize(
                 &term_offsets[start..stop],
                 &mut field_serializer,
@@ -4,6 +4,7 @@ use DocId;
 use common::BitSet;
 use common::CountingWriter;
 use common::HasLen;
+use common::VInt;
 use compression::compressed_block_size;
 use directory::{ReadOnlySource, SourceRead};
 use docset::{DocSet, SkipResult};
@@ -80,7 +81,7 @@ impl SegmentPostings {
     pub fn create_from_docs(docs: &[u32]) -> SegmentPostings {
         let mut counting_writer = CountingWriter::wrap(Vec::new());
         {
-            let mut postings_serializer = PostingsSerializer::new(&mut counting_writer, false);
+            let mut postings_serializer = PostingsSerializer::new(&mut counting_writer, false, None);
             for &doc in docs {
                 postings_serializer.write_doc(doc, 1u32).unwrap();
             }
@@ -102,6 +103,21 @@ impl SegmentPostings {
 }
 
 impl SegmentPostings {
+    /// Returns the last doc of the current block.
+    pub(crate) fn block_last_doc(&self) -> DocId {
+        self.block_cursor.block_last_doc()
+    }
+
+    /// Returns the maximum term frequency within the current block.
+    pub(crate) fn block_max_term_freq(&self) -> u32 {
+        self.block_cursor.block_max_term_freq()
+    }
+
+    /// Returns the minimum fieldnorm id within the current block.
+    pub(crate) fn block_min_fieldnorm_id(&self) -> u8 {
+        self.block_cursor.block_min_fieldnorm_id()
+    }
+
     /// Reads a Segment postings from an &[u8]
     ///
     /// * `len` - number of document in the posting lists.
@@ -170,8 +186,12 @@ impl DocSet for SegmentPostings {
                         .as_mut()
                         .unwrap()
                         .add_skip(sum_freq as usize);
-                }
-                if !self.block_cursor.advance() {
+                    if !self.block_cursor.advance() {
+                        return SkipResult::End;
+                    }
+                } else if !self.block_cursor.skip_to(target) {
+                    // without positions, the blocks that cannot
+                    // contain the target do not even need to be decoded.
                     return SkipResult::End;
                 }
                 self.cur = 0;
@@ -307,6 +327,10 @@ pub struct BlockSegmentPostings {
     num_bitpacked_blocks: usize,
     num_vint_docs: usize,
     remaining_data: SourceRead,
+
+    block_last_doc: DocId,
+    block_max_term_freq: u32,
+    block_min_fieldnorm_id: u8,
 }
 
 impl BlockSegmentPostings {
@@ -326,6 +350,9 @@ impl BlockSegmentPostings {
             remaining_data: data,
             doc_offset: 0,
             doc_freq,
+            block_last_doc: 0,
+            block_max_term_freq: 0,
+            block_min_fieldnorm_id: 0,
         }
     }
 
@@ -347,6 +374,9 @@ impl BlockSegmentPostings {
         self.remaining_data = postings_data;
         self.doc_offset = 0;
         self.doc_freq = doc_freq;
+        self.block_last_doc = 0;
+        self.block_max_term_freq = 0;
+        self.block_min_fieldnorm_id = 0;
     }
 
     /// Returns the document frequency associated to this block postings.
@@ -394,10 +424,100 @@ impl BlockSegmentPostings {
         self.doc_decoder.output_len
     }
 
+    /// Returns the last doc of the current block.
+    pub(crate) fn block_last_doc(&self) -> DocId {
+        self.block_last_doc
+    }
+
+    /// Returns the maximum term frequency within the current block.
+    ///
+    /// If term frequencies were not indexed, it is `1`.
+    pub(crate) fn block_max_term_freq(&self) -> u32 {
+        self.block_max_term_freq
+    }
+
+    /// Returns the minimum fieldnorm id within the current block.
+    pub(crate) fn block_min_fieldnorm_id(&self) -> u8 {
+        self.block_min_fieldnorm_id
+    }
+
+    /// Reads the block info preceding the next block.
+    ///
+    /// Returns false iff there was no remaining blocks.
+    fn read_block_info(&mut self) -> bool {
+        if self.num_bitpacked_blocks == 0 && self.num_vint_docs == 0 {
+            return false;
+        }
+        let (last_doc_delta, max_term_freq, num_consumed_bytes) = {
+            let data: &[u8] = self.remaining_data.as_ref();
+            let mut cursor = data;
+            let last_doc_delta = VInt::deserialize_u64(&mut cursor)
+                .expect("Corrupted postings: failed to read the block info.");
+            let max_term_freq = VInt::deserialize_u64(&mut cursor)
+                .expect("Corrupted postings: failed to read the block info.");
+            self.block_min_fieldnorm_id = cursor[0];
+            (last_doc_delta, max_term_freq, data.len() - cursor.len() + 1)
+        };
+        self.remaining_data.advance(num_consumed_bytes);
+        self.block_last_doc = self.doc_offset + last_doc_delta as DocId;
+        self.block_max_term_freq = max_term_freq as u32;
+        true
+    }
+
+    /// Skips the next block without decoding it.
+    ///
+    /// Its block info must have been read.
+    fn skip_block(&mut self) {
+        if self.num_bitpacked_blocks > 0 {
+            let num_doc_bytes = compressed_block_size(self.remaining_data.as_ref()[0]);
+            self.remaining_data.advance(num_doc_bytes);
+            match self.freq_reading_option {
+                FreqReadingOption::NoFreq => {}
+                FreqReadingOption::SkipFreq | FreqReadingOption::ReadFreq => {
+                    let num_freq_bytes = compressed_block_size(self.remaining_data.as_ref()[0]);
+                    self.remaining_data.advance(num_freq_bytes);
+                }
+            }
+            self.num_bitpacked_blocks -= 1;
+        } else {
+            // the vint block is necessarily the last one.
+            self.num_vint_docs = 0;
+        }
+        self.doc_offset = self.block_last_doc;
+    }
+
+    /// Advance to the first block whose last doc is greater
+    /// or equal to `target`, without decoding the blocks
+    /// in between.
+    ///
+    /// Returns false iff there was no such block.
+    pub(crate) fn skip_to(&mut self, target: DocId) -> bool {
+        while self.read_block_info() {
+            if self.block_last_doc >= target {
+                self.decode_block();
+                return true;
+            }
+            self.skip_block();
+        }
+        false
+    }
+
     /// Advance to the next block.
     ///
     /// Returns false iff there was no remaining blocks.
     pub fn advance(&mut self) -> bool {
+        if self.read_block_info() {
+            self.decode_block();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Decodes the next block.
+    ///
+    /// Its block info must have been read.
+    fn decode_block(&mut self) {
         if self.num_bitpacked_blocks > 0 {
             let num_consumed_bytes = self.doc_decoder
                 .uncompress_block_sorted(self.remaining_data.as_ref(), self.doc_offset);
@@ -417,8 +537,7 @@ impl BlockSegmentPostings {
             // it will be used as the next offset.
             self.doc_offset = self.doc_decoder.output(COMPRESSION_BLOCK_SIZE - 1);
             self.num_bitpacked_blocks -= 1;
-            true
-        } else if self.num_vint_docs > 0 {
+        } else {
             let num_compressed_bytes = self.doc_decoder.uncompress_vint_sorted(
                 self.remaining_data.as_ref(),
                 self.doc_offset,
@@ -433,9 +552,6 @@ impl BlockSegmentPostings {
                 }
             }
             self.num_vint_docs = 0;
-            true
-        } else {
-            false
         }
     }
 
@@ -452,6 +568,9 @@ impl BlockSegmentPostings {
             remaining_data: From::from(ReadOnlySource::empty()),
             doc_offset: 0,
             doc_freq: 0,
+            block_last_doc: 0,
+            block_max_term_freq: 0,
+            block_min_fieldnorm_id: 0,
         }
     }
 }
@@ -476,11 +595,14 @@ mod tests {
     use common::HasLen;
     use core::Index;
     use docset::DocSet;
+    use docset::SkipResult;
+    use fieldnorm::FieldNormReader;
     use fst::Streamer;
     use schema::IndexRecordOption;
     use schema::SchemaBuilder;
     use schema::Term;
     use schema::INT_INDEXED;
+    use schema::TEXT;
 
     #[test]
     fn test_empty_segment_postings() {
@@ -567,4 +689,49 @@ mod tests {
         assert!(block_segments.advance());
         assert_eq!(block_segments.docs(), &[1, 3, 5]);
     }
+
+    #[test]
+    fn test_block_info() {
+        let mut schema_builder = SchemaBuilder::default();
+        let text_field = schema_builder.add_text_field("text", TEXT);
+        let schema = schema_builder.build();
+        let index = Index::create_in_ram(schema);
+        let mut index_writer = index.writer_with_num_threads(1, 40_000_000).unwrap();
+        // `a` appears in all of the 1,000 documents, up to
+        // 3 times in the first blocks, and once in the last ones.
+        for doc in 0..1_000 {
+            let text = if doc < 500 {
+                ["a", "a a b", "a a a"][doc % 3]
+            } else {
+                "a b"
+            };
+            index_writer.add_document(doc!(text_field => text));
+        }
+        index_writer.commit().unwrap();
+        index.load_searchers().unwrap();
+        let searcher = index.searcher();
+        let inverted_index = searcher.segment_reader(0).inverted_index(text_field);
+        let term = Term::from_field_text(text_field, "a");
+        let mut postings = inverted_index
+            .read_postings(&term, IndexRecordOption::WithFreqs)
+            .unwrap();
+        assert!(postings.advance());
+        assert_eq!(postings.block_last_doc(), 127);
+        assert_eq!(postings.block_max_term_freq(), 3);
+        assert_eq!(
+            postings.block_min_fieldnorm_id(),
+            FieldNormReader::fieldnorm_to_id(1)
+        );
+        assert_eq!(postings.skip_next(700), SkipResult::Reached);
+        assert_eq!(postings.doc(), 700);
+        assert_eq!(postings.block_last_doc(), 767);
+        assert_eq!(postings.block_max_term_freq(), 1);
+        assert_eq!(
+            postings.block_min_fieldnorm_id(),
+            FieldNormReader::fieldnorm_to_id(2)
+        );
+        assert_eq!(postings.skip_next(999), SkipResult::Reached);
+        assert_eq!(postings.block_last_doc(), 999);
+        assert!(!postings.advance());
+    }
 }
@@ -1,10 +1,12 @@
 use super::TermInfo;
 use common::BinarySerializable;
+use common::VInt;
 use common::{CompositeWrite, CountingWriter};
 use compression::VIntEncoder;
 use compression::{BlockEncoder, COMPRESSION_BLOCK_SIZE};
 use core::Segment;
 use directory::WritePtr;
+use fieldnorm::FieldNormReader;
 use schema::Schema;
 use schema::{Field, FieldEntry, FieldType};
 use std::io::{self, Write};
@@ -81,10 +83,15 @@ impl InvertedIndexSerializer {
     /// a given field.
     ///
     /// Loads the indexing options for the given field.
+    ///
+    /// The `fieldnorm_reader` gives the fieldnorms of the documents
+    /// of the segment being written, for this field. It is used to
+    /// record the minimum fieldnorm of each block of the postings.
     pub fn new_field(
         &mut self,
         field: Field,
         total_num_tokens: u64,
+        fieldnorm_reader: FieldNormReader,
     ) -> io::Result<FieldSerializer> {
         let field_entry: &FieldEntry = self.schema.get_field_entry(field);
         let term_dictionary_write = self.terms_write.for_field(field);
@@ -96,6 +103,7 @@ impl InvertedIndexSerializer {
             term_dictionary_write,
             postings_write,
             positions_write,
+            fieldnorm_reader,
         )
     }
 
@@ -125,6 +133,7 @@ impl<'a> FieldSerializer<'a> {
         term_dictionary_write: &'a mut CountingWriter<WritePtr>,
         postings_write: &'a mut CountingWriter<WritePtr>,
         positions_write: &'a mut CountingWriter<WritePtr>,
+        fieldnorm_reader: FieldNormReader,
     ) -> io::Result<FieldSerializer<'a>> {
         let (term_freq_enabled, position_enabled): (bool, bool) = match field_type {
             FieldType::Str(ref text_options) => {
@@ -142,7 +151,8 @@ impl<'a> FieldSerializer<'a> {
         };
         let term_dictionary_builder =
             TermDictionaryBuilder::new(term_dictionary_write, field_type)?;
-        let postings_serializer = PostingsSerializer::new(postings_write, term_freq_enabled);
+        let postings_serializer =
+            PostingsSerializer::new(postings_write, term_freq_enabled, Some(fieldnorm_reader));
         let positions_serializer_opt = if position_enabled {
             Some(PositionSerializer::new(positions_write))
         } else {
@@ -239,6 +249,23 @@ impl<'a> FieldSerializer<'a> {
     }
 }
 
+/// Version of the format of the postings written by the `PostingsSerializer`.
+///
+/// It is recorded in the `SegmentMeta` of the segments.
+/// Version `0` designates the segments written before the postings
+/// were versioned, with no block infos.
+pub const POSTINGS_FORMAT_VERSION: u32 = 1;
+
+/// Each block of the postings is preceded by a block info
+/// made of
+/// * the last doc of the block, delta-encoded with the last doc
+///   of the previous block (`VInt`)
+/// * the maximum term frequency within the block (`VInt`)
+/// * the minimum fieldnorm id within the block (`u8`)
+///
+/// The block info makes it possible to skip a block without
+/// decoding it, and to compute an upper bound of the score of its
+/// documents.
 pub struct PostingsSerializer<W: Write> {
     postings_write: CountingWriter<W>,
     last_doc_id_encoded: u32,
@@ -248,10 +275,19 @@ pub struct PostingsSerializer<W: Write> {
     term_freqs: Vec<u32>,
 
     termfreq_enabled: bool,
+    fieldnorm_reader_opt: Option<FieldNormReader>,
 }
 
 impl<W: Write> PostingsSerializer<W> {
-    pub fn new(write: W, termfreq_enabled: bool) -> PostingsSerializer<W> {
+    /// Creates a new `PostingsSerializer`.
+    ///
+    /// If no `fieldnorm_reader` is given, the minimum fieldnorm id
+    /// of all blocks is recorded as `0`.
+    pub fn new(
+        write: W,
+        termfreq_enabled: bool,
+        fieldnorm_reader_opt: Option<FieldNormReader>,
+    ) -> PostingsSerializer<W> {
         PostingsSerializer {
             postings_write: CountingWriter::wrap(write),
 
@@ -261,15 +297,34 @@ impl<W: Write> PostingsSerializer<W> {
 
             last_doc_id_encoded: 0u32,
             termfreq_enabled,
+            fieldnorm_reader_opt,
         }
     }
 
+    fn write_block_info(&mut self) -> io::Result<()> {
+        let last_doc = self.doc_ids[self.doc_ids.len() - 1];
+        VInt(u64::from(last_doc - self.last_doc_id_encoded)).serialize(&mut self.postings_write)?;
+        let max_term_freq = self.term_freqs.iter().cloned().max().unwrap_or(1u32);
+        VInt(u64::from(max_term_freq)).serialize(&mut self.postings_write)?;
+        let min_fieldnorm_id = self.fieldnorm_reader_opt
+            .as_ref()
+            .and_then(|fieldnorm_reader| {
+                self.doc_ids
+                    .iter()
+                    .map(|&doc| fieldnorm_reader.fieldnorm_id(doc))
+                    .min()
+            })
+            .unwrap_or(0u8);
+        self.postings_write.write_all(&[min_fieldnorm_id])
+    }
+
     pub fn write_doc(&mut self, doc_id: DocId, term_freq: u32) -> io::Result<()> {
         self.doc_ids.push(doc_id);
         if self.termfreq_enabled {
             self.term_freqs.push(term_freq as u32);
         }
         if self.doc_ids.len() == COMPRESSION_BLOCK_SIZE {
+            self.write_block_info()?;
             {
                 // encode the doc ids
                 let block_encoded: &[u8] = self.block_encoder
@@ -297,6 +352,7 @@ impl<W: Write> PostingsSerializer<W> {
             //
             // In that case, the remaining part is encoded
             // using variable int encoding.
+            self.write_block_info()?;
             {
                 let block_encoded = self.block_encoder
                     .compress_vint_sorted(&self.doc_ids, self.last_doc_id_encoded);
@@ -1,7 +1,8 @@
 use core::Searcher;
 use core::SegmentReader;
 use docset::DocSet;
-use query::{Query, Scorer, Weight};
+use query::{Query, QueryDsl, Scorer, Weight};
+use schema::Schema;
 use DocId;
 use Result;
 use Score;
@@ -16,6 +17,10 @@ impl Query for AllQuery {
     fn weight(&self, _: &Searcher, _: bool) -> Result<Box<Weight>> {
         Ok(Box::new(AllWeight))
     }
+
+    fn to_dsl(&self, _schema: &Schema) -> Result<QueryDsl> {
+        Ok(QueryDsl::All {})
+    }
 }
 
 /// Weight associated to the `AllQuery` query.
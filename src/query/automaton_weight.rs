@@ -0,0 +1,153 @@
+use common::BitSet;
+use core::InvertedIndexReader;
+use core::Searcher;
+use core::SegmentReader;
+use error::ErrorKind;
+use fst::Automaton;
+use query::BitSetDocSet;
+use query::ConstScorer;
+use query::TermSetQuery;
+use query::{Scorer, Weight};
+use schema::{Field, IndexRecordOption, Term};
+use std::collections::BTreeSet;
+use termdict::{TermDictionary, TermStreamer};
+use DocId;
+use Result;
+
+/// Default maximum number of terms a prefix or wildcard query
+/// may expand to within a segment.
+pub(crate) const DEFAULT_MAX_EXPANSIONS: usize = 1_024;
+
+/// Appends the documents of all of the terms of `term_stream`
+/// into a `BitSet`.
+///
+/// Returns a `TooManyTerms` error if the stream contains more
+/// than `max_expansions` terms.
+pub(crate) fn term_stream_to_bitset<A: Automaton>(
+    inverted_index: &InvertedIndexReader,
+    mut term_stream: TermStreamer<A>,
+    max_doc: DocId,
+    max_expansions: Option<usize>,
+) -> Result<BitSet> {
+    let mut doc_bitset = BitSet::with_max_value(max_doc);
+    let mut num_terms = 0usize;
+    while term_stream.advance() {
+        num_terms += 1;
+        if let Some(max_expansions) = max_expansions {
+            if num_terms > max_expansions {
+                bail!(ErrorKind::TooManyTerms(max_expansions));
+            }
+        }
+        let term_info = term_stream.value();
+        let mut block_segment_postings =
+            inverted_index.read_block_postings_from_terminfo(term_info, IndexRecordOption::Basic);
+        while block_segment_postings.advance() {
+            for &doc in block_segment_postings.docs() {
+                doc_bitset.insert(doc);
+            }
+        }
+    }
+    Ok(doc_bitset)
+}
+
+/// Appends all of the terms of `term_stream` into `terms`.
+///
+/// Returns a `TooManyTerms` error if the stream contains more
+/// than `max_expansions` terms.
+pub(crate) fn term_stream_to_terms<A: Automaton>(
+    field: Field,
+    mut term_stream: TermStreamer<A>,
+    max_expansions: Option<usize>,
+    terms: &mut BTreeSet<Term>,
+) -> Result<()> {
+    let mut num_terms = 0usize;
+    while term_stream.advance() {
+        num_terms += 1;
+        if let Some(max_expansions) = max_expansions {
+            if num_terms > max_expansions {
+                bail!(ErrorKind::TooManyTerms(max_expansions));
+            }
+        }
+        let mut term = Term::for_field(field);
+        term.set_bytes(term_stream.key());
+        terms.insert(term);
+    }
+    Ok(())
+}
+
+/// `Weight` matching all of the documents containing at least
+/// one term accepted by an `Automaton`.
+///
+/// The terms of the dictionary accepted by the automaton
+/// are streamed, and the documents of their posting lists
+/// are appended into a `BitSet`.
+///
+/// Matched documents all get a constant `Score` of one.
+///
+/// Optionally, the number of terms that may be expanded
+/// within a segment can be capped.
+/// See [`.set_max_expansions(...)`](#method.set_max_expansions).
+pub struct AutomatonWeight<A>
+where
+    A: Automaton,
+{
+    field: Field,
+    automaton: A,
+    max_expansions: Option<usize>,
+}
+
+impl<A> AutomatonWeight<A>
+where
+    A: Automaton,
+{
+    /// Creates a new `AutomatonWeight`.
+    pub fn new(field: Field, automaton: A) -> AutomatonWeight<A> {
+        AutomatonWeight {
+            field,
+            automaton,
+            max_expansions: None,
+        }
+    }
+
+    /// Caps the number of terms matching the automaton within a segment.
+    ///
+    /// If a segment contains more matching terms, `.scorer(...)`
+    /// returns a `TooManyTerms` error.
+    pub fn set_max_expansions(&mut self, max_expansions: usize) {
+        self.max_expansions = Some(max_expansions);
+    }
+
+    /// Returns the `TermSetQuery` over all of the terms accepted by
+    /// the automaton, in any of the segments of the searcher.
+    pub(crate) fn term_set_query(&self, searcher: &Searcher) -> Result<TermSetQuery> {
+        let mut terms = BTreeSet::new();
+        for reader in searcher.segment_readers() {
+            let inverted_index = reader.inverted_index(self.field);
+            let term_stream = self.automaton_stream(inverted_index.terms());
+            term_stream_to_terms(self.field, term_stream, self.max_expansions, &mut terms)?;
+        }
+        Ok(TermSetQuery::new(terms.into_iter().collect()))
+    }
+
+    fn automaton_stream<'a>(&'a self, term_dict: &'a TermDictionary) -> TermStreamer<'a, &'a A> {
+        let term_stream_builder = term_dict.search(&self.automaton);
+        term_stream_builder.into_stream()
+    }
+}
+
+impl<A> Weight for AutomatonWeight<A>
+where
+    A: Automaton,
+{
+    fn scorer(&self, reader: &SegmentReader) -> Result<Box<Scorer>> {
+        let inverted_index = reader.inverted_index(self.field);
+        let term_stream = self.automaton_stream(inverted_index.terms());
+        let doc_bitset = term_stream_to_bitset(
+            &inverted_index,
+            term_stream,
+            reader.max_doc(),
+            self.max_expansions,
+        )?;
+        Ok(Box::new(ConstScorer::new(BitSetDocSet::from(doc_bitset))))
+    }
+}
@@ -0,0 +1,334 @@
+/*!
+Block join queries, matching parent documents from their
+child documents, and vice versa.
+
+Nested documents are indexed as a block of documents, using
+[`IndexWriter::add_document_block`](../../struct.IndexWriter.html#method.add_document_block):
+the child documents come first, followed by their parent document.
+The parent documents are then identified by a parent filter query,
+typically a `TermQuery` on a field only present in the parent documents.
+*/
+
+mod to_child_block_join_query;
+mod to_parent_block_join_query;
+
+pub use self::to_child_block_join_query::ToChildBlockJoinQuery;
+pub use self::to_parent_block_join_query::ToParentBlockJoinQuery;
+
+use common::BitSet;
+use core::SegmentReader;
+use docset::DocSet;
+use query::Weight;
+use Result;
+
+/// Defines how the score of a parent document is computed
+/// from the scores of its matching child documents.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScoreMode {
+    /// The score is the maximum score of the child documents.
+    Max,
+    /// The score is the average score of the child documents.
+    Avg,
+    /// The score is the sum of the scores of the child documents.
+    Sum,
+}
+
+/// Returns the bitset of the parent documents of the segment,
+/// as matched by the parent filter.
+fn parent_bitset(parent_filter_weight: &Weight, reader: &SegmentReader) -> Result<BitSet> {
+    let mut parents = BitSet::with_max_value(reader.max_doc());
+    let mut parent_scorer = parent_filter_weight.scorer(reader)?;
+    while parent_scorer.advance() {
+        parents.insert(parent_scorer.doc());
+    }
+    Ok(parents)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{ScoreMode, ToChildBlockJoinQuery, ToParentBlockJoinQuery};
+    use collector::tests::{matching_docs, matching_docs_and_scores};
+    use futures::Future;
+    use query::{BooleanQuery, ConstantScoreQuery, Occur, Query, TermQuery};
+    use schema::{Document, Field, IndexRecordOption, SchemaBuilder, Term, STRING};
+    use DocAddress;
+    use Index;
+    use IndexWriter;
+
+    struct Fields {
+        block: Field,
+        kind: Field,
+        color: Field,
+        size: Field,
+    }
+
+    fn add_product(
+        index_writer: &mut IndexWriter,
+        fields: &Fields,
+        block: &str,
+        variants: &[(&str, &str)],
+    ) {
+        let mut documents: Vec<Document> = variants
+            .iter()
+            .map(|&(color, size)| {
+                doc!(
+                    fields.block => block,
+                    fields.color => color,
+                    fields.size => size
+                )
+            })
+            .collect();
+        documents.push(doc!(fields.block => block, fields.kind => "product"));
+        index_writer.add_document_block(documents).unwrap();
+    }
+
+    fn term_query(field: Field, text: &str) -> Box<Query> {
+        Box::new(TermQuery::new(
+            Term::from_field_text(field, text),
+            IndexRecordOption::Basic,
+        ))
+    }
+
+    fn create_index() -> (Index, Fields) {
+        let mut schema_builder = SchemaBuilder::default();
+        let fields = Fields {
+            block: schema_builder.add_text_field("block", STRING),
+            kind: schema_builder.add_text_field("kind", STRING),
+            color: schema_builder.add_text_field("color", STRING),
+            size: schema_builder.add_text_field("size", STRING),
+        };
+        let index = Index::create_in_ram(schema_builder.build());
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 40_000_000).unwrap();
+            // docs 0 to 2
+            add_product(
+                &mut index_writer,
+                &fields,
+                "1",
+                &[("red", "M"), ("blue", "L")],
+            );
+            // docs 3 to 5
+            add_product(
+                &mut index_writer,
+                &fields,
+                "2",
+                &[("red", "L"), ("green", "M")],
+            );
+            // doc 6
+            add_product(&mut index_writer, &fields, "3", &[]);
+            // docs 7 to 9
+            add_product(
+                &mut index_writer,
+                &fields,
+                "4",
+                &[("red", "M"), ("blue", "M")],
+            );
+            index_writer.commit().unwrap();
+        }
+        index.load_searchers().unwrap();
+        (index, fields)
+    }
+
+    // scores 1 for red variants, 2 for M variants, and 3 for red M variants.
+    fn child_query(fields: &Fields) -> Box<Query> {
+        Box::new(BooleanQuery::from(vec![
+            (
+                Occur::Should,
+                Box::new(ConstantScoreQuery::new(
+                    term_query(fields.color, "red"),
+                    1f32,
+                )) as Box<Query>,
+            ),
+            (
+                Occur::Should,
+                Box::new(ConstantScoreQuery::new(term_query(fields.size, "M"), 2f32)),
+            ),
+        ]))
+    }
+
+    #[test]
+    fn test_to_parent_block_join_query() {
+        let (index, fields) = create_index();
+        {
+            let red_m_variant = BooleanQuery::from(vec![
+                (Occur::Must, term_query(fields.color, "red")),
+                (Occur::Must, term_query(fields.size, "M")),
+            ]);
+            let query = ToParentBlockJoinQuery::new(
+                Box::new(red_m_variant),
+                term_query(fields.kind, "product"),
+                ScoreMode::Max,
+            );
+            let docs = matching_docs(&index.searcher(), &query);
+            assert_eq!(docs, vec![2, 9]);
+        }
+        let scores = |score_mode: ScoreMode| {
+            let query = ToParentBlockJoinQuery::new(
+                child_query(&fields),
+                term_query(fields.kind, "product"),
+                score_mode,
+            );
+            matching_docs_and_scores(&index.searcher(), &query)
+        };
+        assert_eq!(
+            scores(ScoreMode::Max),
+            vec![(2, 3f32), (5, 2f32), (9, 3f32)]
+        );
+        assert_eq!(
+            scores(ScoreMode::Avg),
+            vec![(2, 3f32), (5, 1.5f32), (9, 2.5f32)]
+        );
+        assert_eq!(
+            scores(ScoreMode::Sum),
+            vec![(2, 3f32), (5, 3f32), (9, 5f32)]
+        );
+    }
+
+    #[test]
+    fn test_to_parent_block_join_query_with_deletes() {
+        let (index, fields) = create_index();
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 40_000_000).unwrap();
+            index_writer.delete_term(Term::from_field_text(fields.color, "green"));
+            index_writer.commit().unwrap();
+        }
+        index.load_searchers().unwrap();
+        let searcher = index.searcher();
+        // the only M variant of the block 2 was deleted.
+        let query = ToParentBlockJoinQuery::new(
+            term_query(fields.size, "M"),
+            term_query(fields.kind, "product"),
+            ScoreMode::Max,
+        );
+        assert_eq!(matching_docs(&searcher, &query), vec![2, 9]);
+        let query = ToParentBlockJoinQuery::new(
+            child_query(&fields),
+            term_query(fields.kind, "product"),
+            ScoreMode::Sum,
+        );
+        assert_eq!(
+            matching_docs_and_scores(&searcher, &query),
+            vec![(2, 3f32), (5, 1f32), (9, 5f32)]
+        );
+        let explanation = query.explain(&*searcher, DocAddress(0, 5)).unwrap();
+        assert_eq!(explanation.value(), 1f32);
+        assert_eq!(explanation.details().len(), 1);
+    }
+
+    #[test]
+    fn test_to_child_block_join_query() {
+        let (index, fields) = create_index();
+        let parent_query = BooleanQuery::from(vec![
+            (Occur::Should, term_query(fields.block, "2")),
+            (Occur::Should, term_query(fields.block, "3")),
+            (Occur::Should, term_query(fields.block, "4")),
+        ]);
+        let query = ToChildBlockJoinQuery::new(
+            Box::new(ConstantScoreQuery::new(Box::new(parent_query), 1f32)),
+            term_query(fields.kind, "product"),
+        );
+        assert_eq!(
+            matching_docs_and_scores(&index.searcher(), &query),
+            vec![(3, 1f32), (4, 1f32), (7, 1f32), (8, 1f32)]
+        );
+    }
+
+    #[test]
+    fn test_to_child_block_join_query_with_deletes() {
+        let (index, fields) = create_index();
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 40_000_000).unwrap();
+            index_writer.delete_term(Term::from_field_text(fields.block, "2"));
+            index_writer.delete_term(Term::from_field_text(fields.color, "blue"));
+            index_writer.commit().unwrap();
+        }
+        index.load_searchers().unwrap();
+        let parent_query = BooleanQuery::from(vec![
+            (Occur::Should, term_query(fields.block, "2")),
+            (Occur::Should, term_query(fields.block, "3")),
+            (Occur::Should, term_query(fields.block, "4")),
+        ]);
+        let query =
+            ToChildBlockJoinQuery::new(Box::new(parent_query), term_query(fields.kind, "product"));
+        let docs = matching_docs(&index.searcher(), &query);
+        assert_eq!(docs, vec![7]);
+        assert_eq!(query.count(&*index.searcher()).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_block_join_explain() {
+        let (index, fields) = create_index();
+        let searcher = index.searcher();
+        let query = ToParentBlockJoinQuery::new(
+            child_query(&fields),
+            term_query(fields.kind, "product"),
+            ScoreMode::Sum,
+        );
+        let explanation = query.explain(&*searcher, DocAddress(0, 9)).unwrap();
+        assert_eq!(
+            explanation.description(),
+            "ToParentBlockJoinQuery, sum of the scores of the matching children:"
+        );
+        assert_eq!(explanation.value(), 5f32);
+        let child_scores: Vec<f32> = explanation
+            .details()
+            .iter()
+            .map(|detail| detail.value())
+            .collect();
+        assert_eq!(child_scores, vec![3f32, 2f32]);
+        assert!(query.explain(&*searcher, DocAddress(0, 6)).is_err());
+
+        let query = ToChildBlockJoinQuery::new(
+            Box::new(ConstantScoreQuery::new(term_query(fields.block, "4"), 2f32)),
+            term_query(fields.kind, "product"),
+        );
+        let explanation = query.explain(&*searcher, DocAddress(0, 8)).unwrap();
+        assert_eq!(
+            explanation.description(),
+            "ToChildBlockJoinQuery, score of the parent document #9:"
+        );
+        assert_eq!(explanation.value(), 2f32);
+        assert_eq!(explanation.details()[0].value(), 2f32);
+        assert!(query.explain(&*searcher, DocAddress(0, 9)).is_err());
+    }
+
+    #[test]
+    fn test_block_join_after_merge() {
+        let (index, fields) = create_index();
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 40_000_000).unwrap();
+            add_product(
+                &mut index_writer,
+                &fields,
+                "5",
+                &[("green", "S"), ("red", "M")],
+            );
+            index_writer.delete_term(Term::from_field_text(fields.block, "2"));
+            index_writer.commit().unwrap();
+            let segment_ids = index
+                .searchable_segment_ids()
+                .expect("Searchable segments failed.");
+            assert_eq!(segment_ids.len(), 2);
+            index_writer
+                .merge(&segment_ids)
+                .wait()
+                .expect("Merging failed");
+            index_writer.wait_merging_threads().unwrap();
+        }
+        index.load_searchers().unwrap();
+        assert_eq!(index.searcher().segment_readers().len(), 1);
+        let query = ToParentBlockJoinQuery::new(
+            child_query(&fields),
+            term_query(fields.kind, "product"),
+            ScoreMode::Sum,
+        );
+        // block 2 was deleted, so that blocks 1, 3, 4 and 5
+        // respectively span the docs 0 to 2, 3, 4 to 6 and 7 to 9.
+        assert_eq!(
+            matching_docs_and_scores(&index.searcher(), &query),
+            vec![(2, 3f32), (6, 5f32), (9, 3f32)]
+        );
+    }
+}
@@ -0,0 +1,186 @@
+use super::parent_bitset;
+use common::BitSet;
+use core::Searcher;
+use core::SegmentReader;
+use docset::{DocSet, SkipResult};
+use fastfield::DeleteBitSet;
+use query::explanation::does_not_match;
+use query::{Explanation, Query, QueryDsl, Scorer, Weight};
+use schema::{Schema, Term};
+use std::collections::BTreeSet;
+use DocId;
+use Result;
+use Score;
+
+/// `ToChildBlockJoinQuery` matches the child documents
+/// of the parent documents matching the parent query.
+///
+/// Parent and child documents must have been added as a block
+/// with [`IndexWriter::add_document_block`](../struct.IndexWriter.html#method.add_document_block),
+/// the parent document being the last document of its block.
+/// The parent documents are identified by the `parent_filter` query.
+///
+/// Child documents are given the score of their parent document.
+#[derive(Debug)]
+pub struct ToChildBlockJoinQuery {
+    parent_query: Box<Query>,
+    parent_filter: Box<Query>,
+}
+
+impl ToChildBlockJoinQuery {
+    /// Creates a new `ToChildBlockJoinQuery`.
+    ///
+    /// The documents matched by the parent query that are not
+    /// parent documents are ignored.
+    pub fn new(parent_query: Box<Query>, parent_filter: Box<Query>) -> ToChildBlockJoinQuery {
+        ToChildBlockJoinQuery {
+            parent_query,
+            parent_filter,
+        }
+    }
+}
+
+impl Query for ToChildBlockJoinQuery {
+    fn weight(&self, searcher: &Searcher, scoring_enabled: bool) -> Result<Box<Weight>> {
+        Ok(Box::new(ToChildBlockJoinWeight {
+            parent_weight: self.parent_query.weight(searcher, scoring_enabled)?,
+            parent_filter_weight: self.parent_filter.weight(searcher, false)?,
+        }))
+    }
+
+    fn rewrite(self: Box<Self>, searcher: &Searcher) -> Result<Box<Query>> {
+        let ToChildBlockJoinQuery {
+            parent_query,
+            parent_filter,
+        } = *self;
+        Ok(Box::new(ToChildBlockJoinQuery::new(
+            parent_query.rewrite(searcher)?,
+            parent_filter.rewrite(searcher)?,
+        )))
+    }
+
+    fn query_terms(&self, term_set: &mut BTreeSet<Term>) {
+        self.parent_query.query_terms(term_set);
+        self.parent_filter.query_terms(term_set);
+    }
+
+    fn to_dsl(&self, schema: &Schema) -> Result<QueryDsl> {
+        Ok(QueryDsl::ToChildBlockJoin {
+            query: Box::new(self.parent_query.to_dsl(schema)?),
+            parent_filter: Box::new(self.parent_filter.to_dsl(schema)?),
+        })
+    }
+}
+
+struct ToChildBlockJoinWeight {
+    parent_weight: Box<Weight>,
+    parent_filter_weight: Box<Weight>,
+}
+
+impl Weight for ToChildBlockJoinWeight {
+    fn scorer(&self, reader: &SegmentReader) -> Result<Box<Scorer>> {
+        Ok(Box::new(ToChildBlockJoinScorer {
+            parent_scorer: self.parent_weight.scorer(reader)?,
+            parents: parent_bitset(&*self.parent_filter_weight, reader)?,
+            delete_bitset_opt: reader.delete_bitset().cloned(),
+            doc: 0,
+            parent_doc: 0,
+            score: 0f32,
+        }))
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> Result<Explanation> {
+        let mut scorer = self.scorer(reader)?;
+        if scorer.skip_next(doc) != SkipResult::Reached {
+            return Err(does_not_match(doc));
+        }
+        let parents = parent_bitset(&*self.parent_filter_weight, reader)?;
+        let mut parent_doc = doc + 1;
+        while !parents.contains(parent_doc) {
+            parent_doc += 1;
+        }
+        let mut explanation = Explanation::new(
+            format!(
+                "ToChildBlockJoinQuery, score of the parent document #{}:",
+                parent_doc
+            ),
+            scorer.score(),
+        );
+        explanation.add_detail(self.parent_weight.explain(reader, parent_doc)?);
+        Ok(explanation)
+    }
+}
+
+struct ToChildBlockJoinScorer {
+    parent_scorer: Box<Scorer>,
+    parents: BitSet,
+    delete_bitset_opt: Option<DeleteBitSet>,
+    doc: DocId,
+    // parent of the current child document.
+    parent_doc: DocId,
+    score: Score,
+}
+
+impl ToChildBlockJoinScorer {
+    fn is_deleted(&self, doc: DocId) -> bool {
+        self.delete_bitset_opt
+            .as_ref()
+            .map(|delete_bitset| delete_bitset.is_deleted(doc))
+            .unwrap_or(false)
+    }
+
+    /// Advances to the first child of the next matching parent.
+    fn advance_parent(&mut self) -> bool {
+        while self.parent_scorer.advance() {
+            let parent_doc = self.parent_scorer.doc();
+            if !self.parents.contains(parent_doc) {
+                continue;
+            }
+            let mut first_child = parent_doc;
+            while first_child > 0 && !self.parents.contains(first_child - 1) {
+                first_child -= 1;
+            }
+            if first_child == parent_doc {
+                // this parent does not have any children.
+                continue;
+            }
+            self.doc = first_child;
+            self.parent_doc = parent_doc;
+            self.score = self.parent_scorer.score();
+            return true;
+        }
+        false
+    }
+}
+
+impl DocSet for ToChildBlockJoinScorer {
+    fn advance(&mut self) -> bool {
+        loop {
+            if self.doc + 1 < self.parent_doc {
+                self.doc += 1;
+            } else if !self.advance_parent() {
+                return false;
+            }
+            // deleted documents are skipped, including the children
+            // of a deleted parent, which may otherwise be mistaken
+            // for the children of the next parent.
+            if !self.is_deleted(self.doc) {
+                return true;
+            }
+        }
+    }
+
+    fn doc(&self) -> DocId {
+        self.doc
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.parent_scorer.size_hint()
+    }
+}
+
+impl Scorer for ToChildBlockJoinScorer {
+    fn score(&mut self) -> Score {
+        self.score
+    }
+}
@@ -0,0 +1,227 @@
+use super::{parent_bitset, ScoreMode};
+use common::BitSet;
+use core::Searcher;
+use core::SegmentReader;
+use docset::{DocSet, SkipResult};
+use fastfield::DeleteBitSet;
+use query::explanation::does_not_match;
+use query::{Explanation, Query, QueryDsl, Scorer, Weight};
+use schema::{Schema, Term};
+use std::collections::BTreeSet;
+use std::f32;
+use DocId;
+use Result;
+use Score;
+
+/// `ToParentBlockJoinQuery` matches the parent documents
+/// having at least one child document matching the child query.
+///
+/// Parent and child documents must have been added as a block
+/// with [`IndexWriter::add_document_block`](../struct.IndexWriter.html#method.add_document_block),
+/// the parent document being the last document of its block.
+/// The parent documents are identified by the `parent_filter` query.
+///
+/// The score of a parent document is computed from the scores of
+/// its matching child documents, according to the `ScoreMode`.
+#[derive(Debug)]
+pub struct ToParentBlockJoinQuery {
+    child_query: Box<Query>,
+    parent_filter: Box<Query>,
+    score_mode: ScoreMode,
+}
+
+impl ToParentBlockJoinQuery {
+    /// Creates a new `ToParentBlockJoinQuery`.
+    ///
+    /// The child query must not match any parent document.
+    pub fn new(
+        child_query: Box<Query>,
+        parent_filter: Box<Query>,
+        score_mode: ScoreMode,
+    ) -> ToParentBlockJoinQuery {
+        ToParentBlockJoinQuery {
+            child_query,
+            parent_filter,
+            score_mode,
+        }
+    }
+}
+
+impl Query for ToParentBlockJoinQuery {
+    fn weight(&self, searcher: &Searcher, scoring_enabled: bool) -> Result<Box<Weight>> {
+        Ok(Box::new(ToParentBlockJoinWeight {
+            child_weight: self.child_query.weight(searcher, scoring_enabled)?,
+            parent_filter_weight: self.parent_filter.weight(searcher, false)?,
+            score_mode: self.score_mode,
+        }))
+    }
+
+    fn rewrite(self: Box<Self>, searcher: &Searcher) -> Result<Box<Query>> {
+        let ToParentBlockJoinQuery {
+            child_query,
+            parent_filter,
+            score_mode,
+        } = *self;
+        Ok(Box::new(ToParentBlockJoinQuery::new(
+            child_query.rewrite(searcher)?,
+            parent_filter.rewrite(searcher)?,
+            score_mode,
+        )))
+    }
+
+    fn query_terms(&self, term_set: &mut BTreeSet<Term>) {
+        self.child_query.query_terms(term_set);
+        self.parent_filter.query_terms(term_set);
+    }
+
+    fn to_dsl(&self, schema: &Schema) -> Result<QueryDsl> {
+        Ok(QueryDsl::ToParentBlockJoin {
+            query: Box::new(self.child_query.to_dsl(schema)?),
+            parent_filter: Box::new(self.parent_filter.to_dsl(schema)?),
+            score_mode: self.score_mode,
+        })
+    }
+}
+
+struct ToParentBlockJoinWeight {
+    child_weight: Box<Weight>,
+    parent_filter_weight: Box<Weight>,
+    score_mode: ScoreMode,
+}
+
+impl Weight for ToParentBlockJoinWeight {
+    fn scorer(&self, reader: &SegmentReader) -> Result<Box<Scorer>> {
+        Ok(Box::new(ToParentBlockJoinScorer {
+            child_scorer: self.child_weight.scorer(reader)?,
+            parents: parent_bitset(&*self.parent_filter_weight, reader)?,
+            max_doc: reader.max_doc(),
+            delete_bitset_opt: reader.delete_bitset().cloned(),
+            score_mode: self.score_mode,
+            started: false,
+            has_child: false,
+            doc: 0,
+            score: 0f32,
+        }))
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> Result<Explanation> {
+        let mut scorer = self.scorer(reader)?;
+        if scorer.skip_next(doc) != SkipResult::Reached {
+            return Err(does_not_match(doc));
+        }
+        let mut explanation = Explanation::new(
+            format!(
+                "ToParentBlockJoinQuery, {} of the scores of the matching children:",
+                format!("{:?}", self.score_mode).to_lowercase()
+            ),
+            scorer.score(),
+        );
+        let parents = parent_bitset(&*self.parent_filter_weight, reader)?;
+        let mut first_child = doc;
+        while first_child > 0 && !parents.contains(first_child - 1) {
+            first_child -= 1;
+        }
+        let is_deleted = |child_doc: DocId| {
+            reader
+                .delete_bitset()
+                .map(|delete_bitset| delete_bitset.is_deleted(child_doc))
+                .unwrap_or(false)
+        };
+        let mut child_scorer = self.child_weight.scorer(reader)?;
+        let mut has_child = child_scorer.skip_next(first_child) != SkipResult::End;
+        while has_child && child_scorer.doc() < doc {
+            if !is_deleted(child_scorer.doc()) {
+                explanation.add_detail(self.child_weight.explain(reader, child_scorer.doc())?);
+            }
+            has_child = child_scorer.advance();
+        }
+        Ok(explanation)
+    }
+}
+
+struct ToParentBlockJoinScorer {
+    child_scorer: Box<Scorer>,
+    parents: BitSet,
+    max_doc: DocId,
+    delete_bitset_opt: Option<DeleteBitSet>,
+    score_mode: ScoreMode,
+    started: bool,
+    // true iff the child scorer is positioned on a
+    // child document that has not been consumed yet.
+    has_child: bool,
+    doc: DocId,
+    score: Score,
+}
+
+impl ToParentBlockJoinScorer {
+    fn is_deleted(&self, doc: DocId) -> bool {
+        self.delete_bitset_opt
+            .as_ref()
+            .map(|delete_bitset| delete_bitset.is_deleted(doc))
+            .unwrap_or(false)
+    }
+}
+
+impl DocSet for ToParentBlockJoinScorer {
+    fn advance(&mut self) -> bool {
+        if !self.started {
+            self.started = true;
+            self.has_child = self.child_scorer.advance();
+        }
+        while self.has_child {
+            let child_doc = self.child_scorer.doc();
+            if self.parents.contains(child_doc) {
+                // parent documents cannot be children.
+                self.has_child = self.child_scorer.advance();
+                continue;
+            }
+            let mut parent_doc = child_doc + 1;
+            while parent_doc < self.max_doc && !self.parents.contains(parent_doc) {
+                parent_doc += 1;
+            }
+            if parent_doc == self.max_doc {
+                // the remaining children do not have any parent.
+                self.has_child = false;
+                return false;
+            }
+            let mut num_children = 0u32;
+            let mut max_score = f32::NEG_INFINITY;
+            let mut sum_score = 0f32;
+            while self.has_child && self.child_scorer.doc() < parent_doc {
+                // deleted children neither match nor contribute to the score.
+                if !self.is_deleted(self.child_scorer.doc()) {
+                    let child_score = self.child_scorer.score();
+                    num_children += 1;
+                    max_score = max_score.max(child_score);
+                    sum_score += child_score;
+                }
+                self.has_child = self.child_scorer.advance();
+            }
+            if num_children == 0 {
+                continue;
+            }
+            self.doc = parent_doc;
+            self.score = match self.score_mode {
+                ScoreMode::Max => max_score,
+                ScoreMode::Avg => sum_score / num_children as Score,
+                ScoreMode::Sum => sum_score,
+            };
+            return true;
+        }
+        false
+    }
+
+    fn doc(&self) -> DocId {
+        self.doc
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.child_scorer.size_hint()
+    }
+}
+
+impl Scorer for ToParentBlockJoinScorer {
+    fn score(&mut self) -> Score {
+        self.score
+    }
+}
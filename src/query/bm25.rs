@@ -1,4 +1,5 @@
 use fieldnorm::FieldNormReader;
+use query::Explanation;
 use Score;
 use Searcher;
 use Term;
@@ -6,7 +7,7 @@ use Term;
 const K1: f32 = 1.2;
 const B: f32 = 0.75;
 
-fn idf(doc_freq: u64, doc_count: u64) -> f32 {
+pub fn idf(doc_freq: u64, doc_count: u64) -> f32 {
     let x = ((doc_count - doc_freq) as f32 + 0.5) / (doc_freq as f32 + 0.5);
     (1f32 + x).ln()
 }
@@ -26,22 +27,39 @@ fn compute_tf_cache(average_fieldnorm: f32) -> [f32; 256] {
 
 #[derive(Clone)]
 pub struct BM25Weight {
+    idf_explanation: Explanation,
     weight: f32,
     cache: [f32; 256],
+    average_fieldnorm: f32,
 }
 
 impl BM25Weight {
     pub fn null() -> BM25Weight {
         BM25Weight {
+            idf_explanation: Explanation::new("idf", 0f32),
             weight: 0f32,
             cache: [1f32; 256],
+            average_fieldnorm: 0f32,
         }
     }
 
     pub fn for_terms(searcher: &Searcher, terms: &[Term]) -> BM25Weight {
-        assert!(!terms.is_empty(), "BM25 requires at least one term");
-        let field = terms[0].field();
-        for term in &terms[1..] {
+        let term_groups: Vec<Vec<Term>> = terms.iter().map(|term| vec![term.clone()]).collect();
+        BM25Weight::for_term_groups(searcher, &term_groups)
+    }
+
+    /// Same as `.for_terms(...)`, but each element is a group of alternative
+    /// terms, as in a `MultiPhraseQuery`.
+    ///
+    /// The document frequency of a group is approximated as the sum of
+    /// the document frequencies of its terms.
+    pub fn for_term_groups(searcher: &Searcher, term_groups: &[Vec<Term>]) -> BM25Weight {
+        assert!(
+            term_groups.iter().any(|terms| !terms.is_empty()),
+            "BM25 requires at least one term"
+        );
+        let field = term_groups.iter().flat_map(|terms| terms).next().unwrap().field();
+        for term in term_groups.iter().flat_map(|terms| terms) {
             assert_eq!(
                 term.field(),
                 field,
@@ -58,31 +76,107 @@ impl BM25Weight {
         }
         let average_fieldnorm = total_num_tokens as f32 / total_num_docs as f32;
 
-        let idf = terms
+        let mut idf_explanations: Vec<Explanation> = term_groups
             .iter()
-            .map(|term| {
-                let term_doc_freq = searcher.doc_freq(term);
-                idf(term_doc_freq, total_num_docs)
+            .map(|terms| {
+                let group_doc_freq = terms
+                    .iter()
+                    .map(|term| searcher.doc_freq(term))
+                    .sum::<u64>()
+                    .min(total_num_docs);
+                idf_explanation(group_doc_freq, total_num_docs)
             })
-            .sum::<f32>();
-        BM25Weight::new(idf, average_fieldnorm)
+            .collect();
+        let idf_explanation = if idf_explanations.len() == 1 {
+            idf_explanations.pop().unwrap()
+        } else {
+            let idf = idf_explanations
+                .iter()
+                .map(|explanation| explanation.value())
+                .sum::<f32>();
+            let mut idf_explanation = Explanation::new("idf, sum of:", idf);
+            for explanation in idf_explanations {
+                idf_explanation.add_detail(explanation);
+            }
+            idf_explanation
+        };
+        BM25Weight::new(idf_explanation, average_fieldnorm)
     }
 
-    fn new(idf: f32, average_fieldnorm: f32) -> BM25Weight {
+    fn new(idf_explanation: Explanation, average_fieldnorm: f32) -> BM25Weight {
+        let weight = idf_explanation.value() * (1f32 + K1);
         BM25Weight {
-            weight: idf * (1f32 + K1),
+            idf_explanation,
+            weight,
             cache: compute_tf_cache(average_fieldnorm),
+            average_fieldnorm,
         }
     }
 
+    /// Returns an upper bound of the scores that can be returned
+    /// by `.score(...)`, whatever the fieldnorm and the term frequency.
+    pub fn max_score(&self) -> Score {
+        self.weight
+    }
+
     #[inline(always)]
     pub fn score(&self, fieldnorm_id: u8, term_freq: u32) -> Score {
+        self.score_freq(fieldnorm_id, term_freq as f32)
+    }
+
+    /// Same as `.score(...)`, but accepts a fractional frequency,
+    /// as computed for sloppy phrases.
+    #[inline(always)]
+    pub fn score_freq(&self, fieldnorm_id: u8, freq: f32) -> Score {
+        let norm = self.cache[fieldnorm_id as usize];
+        self.weight * freq / (freq + norm)
+    }
+
+    /// Explains the result of `.score_freq(...)`.
+    pub fn explain(&self, fieldnorm_id: u8, freq: f32) -> Explanation {
+        let score = self.score_freq(fieldnorm_id, freq);
+        let mut explanation =
+            Explanation::new("BM25 score, computed as (k1 + 1) * idf * tf from:", score);
+        explanation.add_detail(Explanation::new("k1, term saturation parameter", K1));
+        explanation.add_detail(self.idf_explanation.clone());
         let norm = self.cache[fieldnorm_id as usize];
-        let term_freq = term_freq as f32;
-        self.weight * term_freq / (term_freq + norm)
+        let mut tf_explanation = Explanation::new(
+            "tf, computed as freq / (freq + k1 * (1 - b + b * fieldnorm / average fieldnorm)) from:",
+            freq / (freq + norm),
+        );
+        tf_explanation.add_detail(Explanation::new("freq, occurrences within the document", freq));
+        tf_explanation.add_detail(Explanation::new("k1, term saturation parameter", K1));
+        tf_explanation.add_detail(Explanation::new("b, length normalization parameter", B));
+        let fieldnorm = FieldNormReader::id_to_fieldnorm(fieldnorm_id);
+        tf_explanation.add_detail(Explanation::new(
+            "fieldnorm, length of the field within the document",
+            fieldnorm as f32,
+        ));
+        tf_explanation.add_detail(Explanation::new(
+            "average fieldnorm, average length of the field",
+            self.average_fieldnorm,
+        ));
+        explanation.add_detail(tf_explanation);
+        explanation
     }
 }
 
+fn idf_explanation(doc_freq: u64, doc_count: u64) -> Explanation {
+    let mut explanation = Explanation::new(
+        "idf, computed as log(1 + (N - n + 0.5) / (n + 0.5)) from:",
+        idf(doc_freq, doc_count),
+    );
+    explanation.add_detail(Explanation::new(
+        "n, number of documents containing the term",
+        doc_freq as f32,
+    ));
+    explanation.add_detail(Explanation::new(
+        "N, total number of documents",
+        doc_count as f32,
+    ));
+    explanation
+}
+
 #[cfg(test)]
 mod tests {
 
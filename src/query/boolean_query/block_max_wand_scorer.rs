@@ -0,0 +1,171 @@
+use collector::Collector;
+use docset::{DocSet, SkipResult};
+use fastfield::DeleteBitSet;
+use query::term_query::TermScorer;
+use query::Scorer;
+use std::f32;
+use DocId;
+use Score;
+
+/// Disjunction of `TermScorer`s implementing Block-Max WAND.
+///
+/// Documents are scored as the sum of the score of the
+/// terms they contain, as in a regular `Union`.
+///
+/// When collecting, the scorer asks the collector for its
+/// `score_threshold`. Thanks to the upper bound of the score of each term,
+/// and of each of its blocks of postings, the documents and the blocks
+/// that cannot possibly reach the threshold are skipped without being scored.
+pub struct BlockMaxWandScorer {
+    // scorers that are not exhausted yet.
+    scorers: Vec<TermScorer>,
+    started: bool,
+    doc: DocId,
+    score: Score,
+}
+
+impl BlockMaxWandScorer {
+    /// Creates a new `BlockMaxWandScorer`.
+    pub fn new(scorers: Vec<TermScorer>) -> BlockMaxWandScorer {
+        BlockMaxWandScorer {
+            scorers,
+            started: false,
+            doc: 0,
+            score: 0f32,
+        }
+    }
+
+    /// Skips the first `num_scorers` scorers to `target`, if they are
+    /// positioned before it, and removes the exhausted ones.
+    fn skip_scorers(&mut self, num_scorers: usize, target: DocId) {
+        for ord in (0..num_scorers).rev() {
+            let is_exhausted = {
+                let scorer = &mut self.scorers[ord];
+                scorer.doc() < target && scorer.skip_next(target) == SkipResult::End
+            };
+            if is_exhausted {
+                self.scorers.swap_remove(ord);
+            }
+        }
+    }
+
+    /// Advances to the next document whose score is strictly
+    /// greater than `threshold`.
+    ///
+    /// Returns false iff there is no such document.
+    fn advance_with_threshold(&mut self, threshold: Score) -> bool {
+        if self.started {
+            let (num_scorers, target) = (self.scorers.len(), self.doc + 1);
+            self.skip_scorers(num_scorers, target);
+        } else {
+            self.started = true;
+            for ord in (0..self.scorers.len()).rev() {
+                if !self.scorers[ord].advance() {
+                    self.scorers.swap_remove(ord);
+                }
+            }
+        }
+        loop {
+            self.scorers.sort_by_key(|scorer| scorer.doc());
+
+            // The pivot is the first scorer such that the documents before
+            // its current doc cannot reach the threshold.
+            let mut upper_bound = 0f32;
+            let mut pivot_ord_opt = None;
+            for (ord, scorer) in self.scorers.iter().enumerate() {
+                upper_bound += scorer.max_score();
+                if upper_bound > threshold {
+                    pivot_ord_opt = Some(ord);
+                    break;
+                }
+            }
+            let pivot_ord = if let Some(pivot_ord) = pivot_ord_opt {
+                pivot_ord
+            } else {
+                return false;
+            };
+            let pivot_doc = self.scorers[pivot_ord].doc();
+            let num_scorers_on_pivot = pivot_ord
+                + 1
+                + self.scorers[pivot_ord + 1..]
+                    .iter()
+                    .take_while(|scorer| scorer.doc() == pivot_doc)
+                    .count();
+
+            if self.scorers[0].doc() != pivot_doc {
+                self.skip_scorers(pivot_ord, pivot_doc);
+                continue;
+            }
+
+            let block_upper_bound: Score = self.scorers[..num_scorers_on_pivot]
+                .iter()
+                .map(|scorer| scorer.block_max_score())
+                .sum();
+            if block_upper_bound > threshold {
+                let score: Score = self.scorers[..num_scorers_on_pivot]
+                    .iter_mut()
+                    .map(|scorer| scorer.score())
+                    .sum();
+                if score > threshold {
+                    self.doc = pivot_doc;
+                    self.score = score;
+                    return true;
+                }
+                self.skip_scorers(num_scorers_on_pivot, pivot_doc + 1);
+            } else {
+                // None of the documents up to the end of the current blocks
+                // can reach the threshold, unless they also contain
+                // the term of the following scorer.
+                let mut target = self.scorers[..num_scorers_on_pivot]
+                    .iter()
+                    .map(|scorer| scorer.block_last_doc())
+                    .min()
+                    .unwrap()
+                    + 1;
+                if let Some(next_scorer) = self.scorers.get(num_scorers_on_pivot) {
+                    target = target.min(next_scorer.doc());
+                }
+                self.skip_scorers(num_scorers_on_pivot, target);
+            }
+        }
+    }
+}
+
+impl DocSet for BlockMaxWandScorer {
+    fn advance(&mut self) -> bool {
+        self.advance_with_threshold(f32::NEG_INFINITY)
+    }
+
+    fn doc(&self) -> DocId {
+        self.doc
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.scorers
+            .iter()
+            .map(|scorer| scorer.size_hint())
+            .max()
+            .unwrap_or(0u32)
+    }
+}
+
+impl Scorer for BlockMaxWandScorer {
+    fn score(&mut self) -> Score {
+        self.score
+    }
+
+    fn collect(&mut self, collector: &mut Collector, delete_bitset_opt: Option<&DeleteBitSet>) {
+        loop {
+            let threshold = collector.score_threshold().unwrap_or(f32::NEG_INFINITY);
+            if !self.advance_with_threshold(threshold) {
+                return;
+            }
+            let is_deleted = delete_bitset_opt
+                .map(|delete_bitset| delete_bitset.is_deleted(self.doc))
+                .unwrap_or(false);
+            if !is_deleted {
+                collector.collect(self.doc, self.score);
+            }
+        }
+    }
+}
@@ -1,10 +1,14 @@
 use super::boolean_weight::BooleanWeight;
+use super::MinimumShouldMatch;
 use query::Occur;
 use query::Query;
+use query::QueryDsl;
 use query::TermQuery;
 use query::Weight;
 use schema::IndexRecordOption;
+use schema::Schema;
 use schema::Term;
+use std::collections::BTreeSet;
 use Result;
 use Searcher;
 
@@ -13,19 +17,32 @@ use Searcher;
 /// The documents matched by the boolean query are
 /// those which
 /// * match all of the sub queries associated with the
-/// `Must` occurence
+/// `Must` or the `Filter` occurence
 /// * match none of the sub queries associated with the
 /// `MustNot` occurence.
 /// * match at least one of the subqueries that is not
 /// a `MustNot` occurence.
+///
+/// `Filter` subqueries are evaluated with scoring disabled,
+/// and do not contribute to the score of the documents.
+///
+/// By default, `Should` subqueries are optional as soon as there
+/// is a `Must` or a `Filter` subquery.
+/// A minimum number of `Should` subqueries that the documents
+/// must match can be set with
+/// [`.set_minimum_should_match(...)`](#method.set_minimum_should_match).
 #[derive(Debug)]
 pub struct BooleanQuery {
     subqueries: Vec<(Occur, Box<Query>)>,
+    minimum_should_match: Option<MinimumShouldMatch>,
 }
 
 impl From<Vec<(Occur, Box<Query>)>> for BooleanQuery {
     fn from(subqueries: Vec<(Occur, Box<Query>)>) -> BooleanQuery {
-        BooleanQuery { subqueries }
+        BooleanQuery {
+            subqueries,
+            minimum_should_match: None,
+        }
     }
 }
 
@@ -34,14 +51,76 @@ impl Query for BooleanQuery {
         let sub_weights = self.subqueries
             .iter()
             .map(|&(ref occur, ref subquery)| {
-                Ok((*occur, subquery.weight(searcher, scoring_enabled)?))
+                let sub_scoring_enabled = scoring_enabled && *occur != Occur::Filter;
+                Ok((*occur, subquery.weight(searcher, sub_scoring_enabled)?))
             })
             .collect::<Result<_>>()?;
-        Ok(Box::new(BooleanWeight::new(sub_weights, scoring_enabled)))
+        let num_should = self
+            .subqueries
+            .iter()
+            .filter(|&&(occur, _)| occur == Occur::Should)
+            .count();
+        let minimum_should_match = self
+            .minimum_should_match
+            .map(|minimum_should_match| minimum_should_match.num_should_match(num_should))
+            .unwrap_or(0);
+        Ok(Box::new(BooleanWeight::new(
+            sub_weights,
+            scoring_enabled,
+            minimum_should_match,
+        )))
+    }
+
+    fn rewrite(self: Box<Self>, searcher: &Searcher) -> Result<Box<Query>> {
+        let subqueries = self
+            .subqueries
+            .into_iter()
+            .map(|(occur, subquery)| Ok((occur, subquery.rewrite(searcher)?)))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Box::new(BooleanQuery {
+            subqueries,
+            minimum_should_match: self.minimum_should_match,
+        }))
+    }
+
+    fn query_terms(&self, term_set: &mut BTreeSet<Term>) {
+        for &(_, ref subquery) in &self.subqueries {
+            subquery.query_terms(term_set);
+        }
+    }
+
+    fn to_dsl(&self, schema: &Schema) -> Result<QueryDsl> {
+        let (mut must, mut should, mut must_not, mut filter) =
+            (Vec::new(), Vec::new(), Vec::new(), Vec::new());
+        for &(occur, ref subquery) in &self.subqueries {
+            let subquery_dsl = subquery.to_dsl(schema)?;
+            match occur {
+                Occur::Must => must.push(subquery_dsl),
+                Occur::Should => should.push(subquery_dsl),
+                Occur::MustNot => must_not.push(subquery_dsl),
+                Occur::Filter => filter.push(subquery_dsl),
+            }
+        }
+        Ok(QueryDsl::Bool {
+            must,
+            should,
+            must_not,
+            filter,
+            minimum_should_match: self.minimum_should_match,
+        })
     }
 }
 
 impl BooleanQuery {
+    /// Sets the minimum number of `Should` subqueries that
+    /// the documents must match.
+    ///
+    /// The `Should` subqueries then stop being optional, even
+    /// if there are `Must` or `Filter` subqueries.
+    pub fn set_minimum_should_match(&mut self, minimum_should_match: MinimumShouldMatch) {
+        self.minimum_should_match = Some(minimum_should_match);
+    }
+
     /// Helper method to create a boolean query matching a given list of terms.
     /// The resulting query is a disjunction of the terms.
     pub fn new_multiterms_query(terms: Vec<Term>) -> BooleanQuery {
@@ -1,10 +1,16 @@
+use super::BlockMaxWandScorer;
+use super::MinShouldMatchScorer;
 use core::SegmentReader;
+use docset::{DocSet, SkipResult};
 use downcast::Downcast;
+use query::explanation::does_not_match;
 use query::intersect_scorers;
+use query::ConstScorer;
 use query::score_combiner::{DoNothingCombiner, ScoreCombiner, SumWithCoordsCombiner};
 use query::term_query::TermScorer;
 use query::EmptyScorer;
 use query::Exclude;
+use query::Explanation;
 use query::Occur;
 use query::RequiredOptionalScorer;
 use query::Scorer;
@@ -12,8 +18,23 @@ use query::Union;
 use query::Weight;
 use std::borrow::Borrow;
 use std::collections::HashMap;
+use DocId;
 use Result;
 
+fn is_all_term_scorers(scorers: &[Box<Scorer>]) -> bool {
+    scorers.iter().all(|scorer| {
+        let scorer_ref: &Scorer = scorer.borrow();
+        Downcast::<TermScorer>::is_type(scorer_ref)
+    })
+}
+
+fn downcast_term_scorers(scorers: Vec<Box<Scorer>>) -> Vec<TermScorer> {
+    scorers
+        .into_iter()
+        .map(|scorer| *Downcast::<TermScorer>::downcast(scorer).unwrap())
+        .collect()
+}
+
 fn scorer_union<TScoreCombiner>(scorers: Vec<Box<Scorer>>) -> Box<Scorer>
 where
     TScoreCombiner: ScoreCombiner,
@@ -23,19 +44,10 @@ where
         return scorers.into_iter().next().unwrap(); //< we checked the size beforehands
     }
 
-    {
-        let is_all_term_queries = scorers.iter().all(|scorer| {
-            let scorer_ref: &Scorer = scorer.borrow();
-            Downcast::<TermScorer>::is_type(scorer_ref)
-        });
-        if is_all_term_queries {
-            let scorers: Vec<TermScorer> = scorers
-                .into_iter()
-                .map(|scorer| *Downcast::<TermScorer>::downcast(scorer).unwrap())
-                .collect();
-            let scorer: Box<Scorer> = Box::new(Union::<TermScorer, TScoreCombiner>::from(scorers));
-            return scorer;
-        }
+    if is_all_term_scorers(&scorers) {
+        let scorers: Vec<TermScorer> = downcast_term_scorers(scorers);
+        let scorer: Box<Scorer> = Box::new(Union::<TermScorer, TScoreCombiner>::from(scorers));
+        return scorer;
     }
 
     let scorer: Box<Scorer> = Box::new(Union::<_, TScoreCombiner>::from(scorers));
@@ -45,19 +57,27 @@ where
 pub struct BooleanWeight {
     weights: Vec<(Occur, Box<Weight>)>,
     scoring_enabled: bool,
+    // if non-zero, the should scorers are required.
+    minimum_should_match: usize,
 }
 
 impl BooleanWeight {
-    pub fn new(weights: Vec<(Occur, Box<Weight>)>, scoring_enabled: bool) -> BooleanWeight {
+    pub fn new(
+        weights: Vec<(Occur, Box<Weight>)>,
+        scoring_enabled: bool,
+        minimum_should_match: usize,
+    ) -> BooleanWeight {
         BooleanWeight {
             weights,
             scoring_enabled,
+            minimum_should_match,
         }
     }
 
     fn complex_scorer<TScoreCombiner: ScoreCombiner>(
         &self,
         reader: &SegmentReader,
+        top_k: bool,
     ) -> Result<Box<Scorer>> {
         let mut per_occur_scorers: HashMap<Occur, Vec<Box<Scorer>>> = HashMap::new();
         for &(ref occur, ref subweight) in &self.weights {
@@ -68,21 +88,72 @@ impl BooleanWeight {
                 .push(sub_scorer);
         }
 
-        let should_scorer_opt: Option<Box<Scorer>> = per_occur_scorers
-            .remove(&Occur::Should)
-            .map(scorer_union::<TScoreCombiner>);
+        // When collecting the top documents, a scored disjunction of terms
+        // can skip the documents that cannot make it to the top documents.
+        if top_k
+            && self.scoring_enabled
+            && self.minimum_should_match <= 1
+            && per_occur_scorers.len() == 1
+        {
+            let is_term_disjunction = per_occur_scorers
+                .get(&Occur::Should)
+                .map(|scorers| scorers.len() > 1 && is_all_term_scorers(scorers))
+                .unwrap_or(false);
+            if is_term_disjunction {
+                let scorers = per_occur_scorers.remove(&Occur::Should).unwrap();
+                let scorer = BlockMaxWandScorer::new(downcast_term_scorers(scorers));
+                return Ok(Box::new(scorer));
+            }
+        }
+
+        let minimum_should_match = self.minimum_should_match;
+        let should_scorer_opt: Option<Box<Scorer>> =
+            per_occur_scorers
+                .remove(&Occur::Should)
+                .map(|scorers| -> Box<Scorer> {
+                    if minimum_should_match > 1 {
+                        Box::new(MinShouldMatchScorer::<TScoreCombiner>::new(
+                            scorers,
+                            minimum_should_match,
+                        ))
+                    } else {
+                        scorer_union::<TScoreCombiner>(scorers)
+                    }
+                });
 
         let exclude_scorer_opt: Option<Box<Scorer>> = per_occur_scorers
             .remove(&Occur::MustNot)
             .map(scorer_union::<TScoreCombiner>);
 
-        let must_scorer_opt: Option<Box<Scorer>> = per_occur_scorers
-            .remove(&Occur::Must)
-            .map(intersect_scorers);
+        // Filter scorers are intersected with the must scorers,
+        // with a score of zero so that they do not alter the score.
+        let filter_scorer_opt: Option<Box<Scorer>> = per_occur_scorers
+            .remove(&Occur::Filter)
+            .map(intersect_scorers)
+            .map(|filter_scorer| {
+                let mut const_scorer = ConstScorer::new(filter_scorer);
+                const_scorer.set_score(0f32);
+                let scorer: Box<Scorer> = Box::new(const_scorer);
+                scorer
+            });
+
+        let must_scorer_opt: Option<Box<Scorer>> = match (
+            per_occur_scorers.remove(&Occur::Must),
+            filter_scorer_opt,
+        ) {
+            (Some(mut must_scorers), Some(filter_scorer)) => {
+                must_scorers.push(filter_scorer);
+                Some(intersect_scorers(must_scorers))
+            }
+            (Some(must_scorers), None) => Some(intersect_scorers(must_scorers)),
+            (None, filter_scorer_opt) => filter_scorer_opt,
+        };
 
         let positive_scorer: Box<Scorer> = match (should_scorer_opt, must_scorer_opt) {
             (Some(should_scorer), Some(must_scorer)) => {
-                if self.scoring_enabled {
+                if minimum_should_match > 0 {
+                    intersect_scorers(vec![must_scorer, should_scorer])
+                } else if self.scoring_enabled {
                     Box::new(RequiredOptionalScorer::<_, _, TScoreCombiner>::new(
                         must_scorer,
                         should_scorer,
@@ -104,23 +175,61 @@ impl BooleanWeight {
             Ok(positive_scorer)
         }
     }
-}
 
-impl Weight for BooleanWeight {
-    fn scorer(&self, reader: &SegmentReader) -> Result<Box<Scorer>> {
+    fn boolean_scorer(&self, reader: &SegmentReader, top_k: bool) -> Result<Box<Scorer>> {
         if self.weights.is_empty() {
             Ok(Box::new(EmptyScorer))
         } else if self.weights.len() == 1 {
             let &(occur, ref weight) = &self.weights[0];
-            if occur == Occur::MustNot {
-                Ok(Box::new(EmptyScorer))
-            } else {
-                weight.scorer(reader)
+            match occur {
+                Occur::MustNot => Ok(Box::new(EmptyScorer)),
+                Occur::Filter if self.scoring_enabled => {
+                    self.complex_scorer::<SumWithCoordsCombiner>(reader, top_k)
+                }
+                _ if top_k => weight.top_k_scorer(reader),
+                _ => weight.scorer(reader),
             }
         } else if self.scoring_enabled {
-            self.complex_scorer::<SumWithCoordsCombiner>(reader)
+            self.complex_scorer::<SumWithCoordsCombiner>(reader, top_k)
         } else {
-            self.complex_scorer::<DoNothingCombiner>(reader)
+            self.complex_scorer::<DoNothingCombiner>(reader, top_k)
+        }
+    }
+}
+
+impl Weight for BooleanWeight {
+    fn scorer(&self, reader: &SegmentReader) -> Result<Box<Scorer>> {
+        self.boolean_scorer(reader, false)
+    }
+
+    /// Disjunctions of terms rely on Block-Max WAND
+    /// to skip the documents that cannot make it to the top documents.
+    fn top_k_scorer(&self, reader: &SegmentReader) -> Result<Box<Scorer>> {
+        self.boolean_scorer(reader, true)
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> Result<Explanation> {
+        let mut scorer = self.scorer(reader)?;
+        if scorer.skip_next(doc) != SkipResult::Reached {
+            return Err(does_not_match(doc));
+        }
+        let mut explanation = Explanation::new("BooleanQuery, sum of:", scorer.score());
+        for &(occur, ref weight) in &self.weights {
+            if occur == Occur::MustNot {
+                continue;
+            }
+            if weight.scorer(reader)?.skip_next(doc) != SkipResult::Reached {
+                continue;
+            }
+            if occur == Occur::Filter {
+                explanation.add_detail(Explanation::new(
+                    "filter clause, not taking part in the score",
+                    0f32,
+                ));
+            } else {
+                explanation.add_detail(weight.explain(reader, doc)?);
+            }
         }
+        Ok(explanation)
     }
 }
@@ -0,0 +1,231 @@
+use docset::{DocSet, SkipResult};
+use query::score_combiner::ScoreCombiner;
+use query::Scorer;
+use std::marker::PhantomData;
+use DocId;
+use Score;
+
+/// Disjunction of `Scorer`s matching the documents matched by
+/// at least `minimum_should_match` of the scorers.
+///
+/// Documents are scored by combining the score of the
+/// scorers they match, as in a regular `Union`.
+///
+/// # Implementation
+///
+/// The scorers that are not exhausted are all positioned after the
+/// current document. The next candidate is the smallest document
+/// over the scorers. The scorers positioned on it are counted,
+/// and advanced past it.
+pub struct MinShouldMatchScorer<TScoreCombiner> {
+    // scorers that are not exhausted yet.
+    scorers: Vec<Box<Scorer>>,
+    minimum_should_match: usize,
+    started: bool,
+    doc: DocId,
+    score: Score,
+    _score_combiner: PhantomData<TScoreCombiner>,
+}
+
+impl<TScoreCombiner: ScoreCombiner> MinShouldMatchScorer<TScoreCombiner> {
+    /// Creates a new `MinShouldMatchScorer`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `minimum_should_match` is `0`.
+    pub fn new(
+        scorers: Vec<Box<Scorer>>,
+        minimum_should_match: usize,
+    ) -> MinShouldMatchScorer<TScoreCombiner> {
+        assert!(minimum_should_match > 0);
+        MinShouldMatchScorer {
+            scorers,
+            minimum_should_match,
+            started: false,
+            doc: 0,
+            score: 0f32,
+            _score_combiner: PhantomData,
+        }
+    }
+
+    /// Positions all of the scorers on their first document,
+    /// and removes the empty ones.
+    fn start(&mut self) {
+        self.started = true;
+        for ord in (0..self.scorers.len()).rev() {
+            if !self.scorers[ord].advance() {
+                self.scorers.swap_remove(ord);
+            }
+        }
+    }
+
+    /// Advances to the first document, over the current
+    /// positions of the scorers, that matches enough of the scorers.
+    fn advance_to_match(&mut self) -> bool {
+        let mut score_combiner = TScoreCombiner::default();
+        while self.scorers.len() >= self.minimum_should_match {
+            let doc = self
+                .scorers
+                .iter()
+                .map(|scorer| scorer.doc())
+                .min()
+                .unwrap();
+            score_combiner.clear();
+            let mut num_matching = 0;
+            for ord in (0..self.scorers.len()).rev() {
+                if self.scorers[ord].doc() != doc {
+                    continue;
+                }
+                num_matching += 1;
+                score_combiner.update(&mut self.scorers[ord]);
+                if !self.scorers[ord].advance() {
+                    self.scorers.swap_remove(ord);
+                }
+            }
+            if num_matching >= self.minimum_should_match {
+                self.doc = doc;
+                self.score = score_combiner.score();
+                return true;
+            }
+        }
+        false
+    }
+}
+
+impl<TScoreCombiner: ScoreCombiner> DocSet for MinShouldMatchScorer<TScoreCombiner> {
+    fn advance(&mut self) -> bool {
+        if !self.started {
+            self.start();
+        }
+        self.advance_to_match()
+    }
+
+    fn skip_next(&mut self, target: DocId) -> SkipResult {
+        if !self.started {
+            self.start();
+        }
+        for ord in (0..self.scorers.len()).rev() {
+            let is_exhausted = {
+                let scorer = &mut self.scorers[ord];
+                scorer.doc() < target && scorer.skip_next(target) == SkipResult::End
+            };
+            if is_exhausted {
+                self.scorers.swap_remove(ord);
+            }
+        }
+        if !self.advance_to_match() {
+            SkipResult::End
+        } else if self.doc == target {
+            SkipResult::Reached
+        } else {
+            SkipResult::OverStep
+        }
+    }
+
+    fn doc(&self) -> DocId {
+        self.doc
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.scorers
+            .iter()
+            .map(|scorer| scorer.size_hint())
+            .max()
+            .unwrap_or(0u32)
+    }
+}
+
+impl<TScoreCombiner: ScoreCombiner> Scorer for MinShouldMatchScorer<TScoreCombiner> {
+    fn score(&mut self) -> Score {
+        self.score
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::MinShouldMatchScorer;
+    use docset::{DocSet, SkipResult};
+    use query::score_combiner::{DoNothingCombiner, SumCombiner};
+    use query::{ConstScorer, Scorer, VecDocSet};
+    use tests::sample_with_seed;
+    use DocId;
+
+    fn create_scorers(docs_list: &[Vec<DocId>]) -> Vec<Box<Scorer>> {
+        docs_list
+            .iter()
+            .map(|docs| {
+                let scorer: Box<Scorer> = Box::new(ConstScorer::new(VecDocSet::from(docs.clone())));
+                scorer
+            })
+            .collect()
+    }
+
+    fn expected_docs(docs_list: &[Vec<DocId>], minimum_should_match: usize) -> Vec<DocId> {
+        let mut all_docs: Vec<DocId> = docs_list.iter().flat_map(|docs| docs.clone()).collect();
+        all_docs.sort();
+        all_docs.dedup();
+        all_docs
+            .into_iter()
+            .filter(|doc| {
+                docs_list.iter().filter(|docs| docs.contains(doc)).count() >= minimum_should_match
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_min_should_match_scorer() {
+        let docs_list = vec![
+            vec![1, 3, 5, 7],
+            vec![1, 2, 5],
+            vec![2, 5, 7, 8],
+            vec![5, 9],
+        ];
+        for minimum_should_match in 1..5 {
+            let mut scorer = MinShouldMatchScorer::<SumCombiner>::new(
+                create_scorers(&docs_list),
+                minimum_should_match,
+            );
+            let mut docs = Vec::new();
+            while scorer.advance() {
+                assert_eq!(
+                    scorer.score() as usize,
+                    docs_list
+                        .iter()
+                        .filter(|docs| docs.contains(&scorer.doc()))
+                        .count()
+                );
+                docs.push(scorer.doc());
+            }
+            assert_eq!(docs, expected_docs(&docs_list, minimum_should_match));
+        }
+    }
+
+    #[test]
+    fn test_min_should_match_scorer_skip_next() {
+        let docs_list: Vec<Vec<DocId>> = (0..4)
+            .map(|seed| sample_with_seed(1_000, 0.1, seed))
+            .collect();
+        for minimum_should_match in 1..4 {
+            let expected = expected_docs(&docs_list, minimum_should_match);
+            for target in (0..1_000).step_by(37) {
+                let mut scorer = MinShouldMatchScorer::<DoNothingCombiner>::new(
+                    create_scorers(&docs_list),
+                    minimum_should_match,
+                );
+                match expected.iter().find(|&&doc| doc >= target) {
+                    Some(&doc) => {
+                        let expected_skip_result = if doc == target {
+                            SkipResult::Reached
+                        } else {
+                            SkipResult::OverStep
+                        };
+                        assert_eq!(scorer.skip_next(target), expected_skip_result);
+                        assert_eq!(scorer.doc(), doc);
+                    }
+                    None => assert_eq!(scorer.skip_next(target), SkipResult::End),
+                }
+            }
+        }
+    }
+}
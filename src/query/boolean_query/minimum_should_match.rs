@@ -0,0 +1,137 @@
+use serde::de;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::num::ParseIntError;
+use std::str::FromStr;
+
+/// Minimum number of `Should` subqueries of a `BooleanQuery`
+/// that a document must match.
+///
+/// It is expressed either as an absolute number of subqueries,
+/// or as a percentage of the `Should` subqueries, rounded down.
+/// In both cases, it is capped to the number of `Should` subqueries.
+///
+/// A `MinimumShouldMatch` can be parsed from its string
+/// representation, e.g. `3` or `75%`.
+///
+/// ```rust
+/// # use tantivy::query::MinimumShouldMatch;
+/// assert_eq!("3".parse(), Ok(MinimumShouldMatch::Absolute(3)));
+/// assert_eq!("75%".parse(), Ok(MinimumShouldMatch::Percentage(75)));
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MinimumShouldMatch {
+    /// Absolute number of `Should` subqueries.
+    Absolute(usize),
+    /// Percentage of the `Should` subqueries.
+    Percentage(usize),
+}
+
+impl MinimumShouldMatch {
+    /// Returns the number of `Should` subqueries that a document
+    /// must match, given the number of `Should` subqueries.
+    pub fn num_should_match(&self, num_should: usize) -> usize {
+        let num_should_match = match *self {
+            MinimumShouldMatch::Absolute(num_should_match) => num_should_match,
+            MinimumShouldMatch::Percentage(percentage) => num_should * percentage / 100,
+        };
+        num_should_match.min(num_should)
+    }
+}
+
+impl FromStr for MinimumShouldMatch {
+    type Err = ParseIntError;
+
+    fn from_str(minimum_should_match: &str) -> Result<MinimumShouldMatch, ParseIntError> {
+        if minimum_should_match.ends_with('%') {
+            let percentage = &minimum_should_match[..minimum_should_match.len() - 1];
+            Ok(MinimumShouldMatch::Percentage(usize::from_str(percentage)?))
+        } else {
+            Ok(MinimumShouldMatch::Absolute(usize::from_str(
+                minimum_should_match,
+            )?))
+        }
+    }
+}
+
+impl fmt::Display for MinimumShouldMatch {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            MinimumShouldMatch::Absolute(num_should_match) => {
+                write!(formatter, "{}", num_should_match)
+            }
+            MinimumShouldMatch::Percentage(percentage) => write!(formatter, "{}%", percentage),
+        }
+    }
+}
+
+/// An absolute `MinimumShouldMatch` is serialized as a number,
+/// and a percentage as a string (e.g. `"75%"`).
+impl Serialize for MinimumShouldMatch {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match *self {
+            MinimumShouldMatch::Absolute(num_should_match) => {
+                serializer.serialize_u64(num_should_match as u64)
+            }
+            MinimumShouldMatch::Percentage(_) => serializer.serialize_str(&self.to_string()),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for MinimumShouldMatch {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum RawMinimumShouldMatch {
+            Absolute(usize),
+            Text(String),
+        }
+        match RawMinimumShouldMatch::deserialize(deserializer)? {
+            RawMinimumShouldMatch::Absolute(num_should_match) => {
+                Ok(MinimumShouldMatch::Absolute(num_should_match))
+            }
+            RawMinimumShouldMatch::Text(text) => text
+                .parse()
+                .map_err(|_| de::Error::custom(format!("Invalid minimum should match {:?}", text))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::MinimumShouldMatch;
+    use serde_json;
+
+    #[test]
+    fn test_minimum_should_match_num_should_match() {
+        assert_eq!(MinimumShouldMatch::Absolute(3).num_should_match(5), 3);
+        assert_eq!(MinimumShouldMatch::Absolute(3).num_should_match(2), 2);
+        assert_eq!(MinimumShouldMatch::Percentage(75).num_should_match(5), 3);
+        assert_eq!(MinimumShouldMatch::Percentage(100).num_should_match(5), 5);
+        assert_eq!(MinimumShouldMatch::Percentage(150).num_should_match(5), 5);
+    }
+
+    #[test]
+    fn test_minimum_should_match_parse_and_serialize() {
+        assert_eq!("3".parse(), Ok(MinimumShouldMatch::Absolute(3)));
+        assert_eq!("75%".parse(), Ok(MinimumShouldMatch::Percentage(75)));
+        assert!("-1".parse::<MinimumShouldMatch>().is_err());
+        assert!("%".parse::<MinimumShouldMatch>().is_err());
+        for &(minimum_should_match, json) in &[
+            (MinimumShouldMatch::Absolute(3), "3"),
+            (MinimumShouldMatch::Percentage(75), "\"75%\""),
+        ] {
+            assert_eq!(serde_json::to_string(&minimum_should_match).unwrap(), json);
+            let deserialized: MinimumShouldMatch = serde_json::from_str(json).unwrap();
+            assert_eq!(deserialized, minimum_should_match);
+        }
+        assert!(serde_json::from_str::<MinimumShouldMatch>("\"many\"").is_err());
+    }
+}
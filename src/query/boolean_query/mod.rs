@@ -1,13 +1,20 @@
+mod block_max_wand_scorer;
 mod boolean_query;
 mod boolean_weight;
+mod min_should_match_scorer;
+mod minimum_should_match;
 
+pub use self::block_max_wand_scorer::BlockMaxWandScorer;
 pub use self::boolean_query::BooleanQuery;
+pub use self::min_should_match_scorer::MinShouldMatchScorer;
+pub use self::minimum_should_match::MinimumShouldMatch;
 
 #[cfg(test)]
 mod tests {
 
     use super::*;
-    use collector::tests::TestCollector;
+    use collector::tests::{matching_docs, matching_scores, TestCollector};
+    use collector::TopCollector;
     use downcast::Downcast;
     use query::score_combiner::SumWithCoordsCombiner;
     use query::term_query::TermScorer;
@@ -18,7 +25,13 @@ mod tests {
     use query::RequiredOptionalScorer;
     use query::Scorer;
     use query::TermQuery;
+    use query::{BoostQuery, PhraseQuery, PrefixQuery};
+    use rand::{Rng, SeedableRng, XorShiftRng};
     use schema::*;
+    use std::collections::BTreeSet;
+    use tests::assert_nearly_equals;
+    use DocAddress;
+    use DocId;
     use Index;
 
     fn aux_test_helper() -> (Index, Field) {
@@ -171,6 +184,143 @@ mod tests {
         }
     }
 
+    #[test]
+    pub fn test_boolean_filter() {
+        let (index, text_field) = aux_test_helper();
+
+        let make_term_query = |text: &str| {
+            let term_query = TermQuery::new(
+                Term::from_field_text(text_field, text),
+                IndexRecordOption::WithFreqs,
+            );
+            let query: Box<Query> = Box::new(term_query);
+            query
+        };
+
+        let searcher = index.searcher();
+
+        {
+            let boolean_query = BooleanQuery::from(vec![(Occur::Filter, make_term_query("a"))]);
+            assert_eq!(matching_docs(&searcher, &boolean_query), vec![0, 1, 3]);
+            assert_eq!(
+                matching_scores(&searcher, &boolean_query),
+                vec![0f32, 0f32, 0f32]
+            );
+        }
+        {
+            let unfiltered_query = BooleanQuery::from(vec![(Occur::Should, make_term_query("b"))]);
+            let filtered_query = BooleanQuery::from(vec![
+                (Occur::Should, make_term_query("b")),
+                (Occur::Filter, make_term_query("a")),
+            ]);
+            // the filter is required, while the should clause only affects the score.
+            assert_eq!(matching_docs(&searcher, &filtered_query), vec![0, 1, 3]);
+            let unfiltered_scores = matching_scores(&searcher, &unfiltered_query);
+            assert_eq!(
+                matching_scores(&searcher, &filtered_query),
+                vec![unfiltered_scores[0], 0f32, unfiltered_scores[2]]
+            );
+        }
+        {
+            let unfiltered_query = BooleanQuery::from(vec![
+                (Occur::Must, make_term_query("c")),
+                (Occur::Should, make_term_query("b")),
+            ]);
+            let filtered_query = BooleanQuery::from(vec![
+                (Occur::Must, make_term_query("c")),
+                (Occur::Should, make_term_query("b")),
+                (Occur::Filter, make_term_query("a")),
+                (Occur::MustNot, make_term_query("d")),
+            ]);
+            assert_eq!(matching_docs(&searcher, &filtered_query), vec![0, 1]);
+            let unfiltered_scores = matching_scores(&searcher, &unfiltered_query);
+            assert_eq!(
+                matching_scores(&searcher, &filtered_query),
+                vec![unfiltered_scores[0], unfiltered_scores[1]]
+            );
+        }
+    }
+
+    #[test]
+    pub fn test_boolean_minimum_should_match() {
+        let (index, text_field) = aux_test_helper();
+
+        let make_boolean_query = |clauses: &[(Occur, &str)], minimum_should_match| {
+            let subqueries = clauses
+                .iter()
+                .map(|&(occur, text)| {
+                    let term_query: Box<Query> = Box::new(TermQuery::new(
+                        Term::from_field_text(text_field, text),
+                        IndexRecordOption::Basic,
+                    ));
+                    (occur, term_query)
+                })
+                .collect::<Vec<_>>();
+            let mut boolean_query = BooleanQuery::from(subqueries);
+            boolean_query.set_minimum_should_match(minimum_should_match);
+            boolean_query
+        };
+
+        let searcher = index.searcher();
+
+        let should_clauses = [
+            (Occur::Should, "a"),
+            (Occur::Should, "b"),
+            (Occur::Should, "d"),
+        ];
+        {
+            let boolean_query =
+                make_boolean_query(&should_clauses, MinimumShouldMatch::Absolute(1));
+            assert_eq!(
+                matching_docs(&searcher, &boolean_query),
+                vec![0, 1, 2, 3, 4]
+            );
+        }
+        {
+            let boolean_query =
+                make_boolean_query(&should_clauses, MinimumShouldMatch::Absolute(2));
+            assert_eq!(matching_docs(&searcher, &boolean_query), vec![0, 3]);
+        }
+        {
+            let boolean_query =
+                make_boolean_query(&should_clauses, MinimumShouldMatch::Percentage(67));
+            assert_eq!(matching_docs(&searcher, &boolean_query), vec![0, 3]);
+        }
+        {
+            let boolean_query =
+                make_boolean_query(&should_clauses, MinimumShouldMatch::Absolute(5));
+            assert_eq!(matching_docs(&searcher, &boolean_query), vec![3]);
+        }
+        {
+            let boolean_query = make_boolean_query(
+                &[
+                    (Occur::Must, "c"),
+                    (Occur::Should, "b"),
+                    (Occur::Should, "d"),
+                ],
+                MinimumShouldMatch::Absolute(1),
+            );
+            assert_eq!(matching_docs(&searcher, &boolean_query), vec![0, 2, 3]);
+        }
+        {
+            let boolean_query = make_boolean_query(
+                &[
+                    (Occur::Must, "c"),
+                    (Occur::Should, "a"),
+                    (Occur::Should, "b"),
+                    (Occur::MustNot, "d"),
+                ],
+                MinimumShouldMatch::Absolute(2),
+            );
+            assert_eq!(matching_docs(&searcher, &boolean_query), vec![0]);
+        }
+        {
+            let boolean_query =
+                make_boolean_query(&[(Occur::Must, "c")], MinimumShouldMatch::Absolute(2));
+            assert_eq!(matching_docs(&searcher, &boolean_query), vec![0, 1, 2, 3]);
+        }
+    }
+
     #[test]
     pub fn test_intersection_score() {
         let (index, text_field) = aux_test_helper();
@@ -199,4 +349,151 @@ mod tests {
             assert_eq!(score_docs(&boolean_query), vec![0.977973, 0.84699446]);
         }
     }
+
+    #[test]
+    pub fn test_boolean_query_boost() {
+        let (index, text_field) = aux_test_helper();
+        let searcher = index.searcher();
+        let make_term_query = |text: &str| {
+            let term_query = TermQuery::new(
+                Term::from_field_text(text_field, text),
+                IndexRecordOption::WithFreqs,
+            );
+            let query: Box<Query> = Box::new(term_query);
+            query
+        };
+        let boolean_query = BooleanQuery::from(vec![
+            (
+                Occur::Should,
+                Box::new(BoostQuery::new(make_term_query("a"), 3f32)) as Box<Query>,
+            ),
+            (Occur::Should, make_term_query("b")),
+        ]);
+        // "a c" only matches the boosted clause, and "b c" only matches the other one.
+        let scores = matching_scores(&searcher, &boolean_query);
+        assert_nearly_equals(scores[1], 3f32 * scores[2]);
+        let mut top_collector = TopCollector::with_limit(5);
+        searcher.search(&boolean_query, &mut top_collector).unwrap();
+        let docs: Vec<DocId> = top_collector
+            .docs()
+            .into_iter()
+            .map(|doc_address| doc_address.doc())
+            .collect();
+        let rank = |doc: DocId| {
+            docs.iter()
+                .position(|&ranked_doc| ranked_doc == doc)
+                .unwrap()
+        };
+        assert!(rank(1) < rank(2));
+    }
+
+    #[test]
+    pub fn test_boolean_query_terms_and_rewrite() {
+        let (index, text_field) = aux_test_helper();
+        let searcher = index.searcher();
+        let term = |text: &str| Term::from_field_text(text_field, text);
+        let phrase_query = PhraseQuery::new(vec![term("a"), term("b")]);
+        let boolean_query = BooleanQuery::from(vec![
+            (
+                Occur::Must,
+                Box::new(TermQuery::new(term("c"), IndexRecordOption::Basic)) as Box<Query>,
+            ),
+            (
+                Occur::Should,
+                Box::new(BoostQuery::new(Box::new(phrase_query), 2f32)),
+            ),
+            (Occur::MustNot, Box::new(PrefixQuery::new(term("d")))),
+        ]);
+        let mut query_terms = BTreeSet::new();
+        boolean_query.query_terms(&mut query_terms);
+        assert_eq!(
+            query_terms.into_iter().collect::<Vec<Term>>(),
+            vec![term("a"), term("b"), term("c")]
+        );
+        let count = boolean_query.count(&*searcher).unwrap();
+        let rewritten_query = Box::new(boolean_query).rewrite(&*searcher).unwrap();
+        let mut query_terms = BTreeSet::new();
+        rewritten_query.query_terms(&mut query_terms);
+        assert_eq!(
+            query_terms.into_iter().collect::<Vec<Term>>(),
+            vec![term("a"), term("b"), term("c"), term("d")]
+        );
+        assert_eq!(rewritten_query.count(&*searcher).unwrap(), count);
+    }
+
+    #[test]
+    pub fn test_block_max_wand() {
+        let mut schema_builder = SchemaBuilder::default();
+        let text_field = schema_builder.add_text_field("text", TEXT);
+        let schema = schema_builder.build();
+        let index = Index::create_in_ram(schema);
+        {
+            let seed: &[u32; 4] = &[1, 2, 3, 4];
+            let mut rng: XorShiftRng = XorShiftRng::from_seed(*seed);
+            let words = ["a", "b", "c", "d", "e", "f", "g", "h"];
+            let mut index_writer = index.writer_with_num_threads(1, 40_000_000).unwrap();
+            for _ in 0..2 {
+                for _ in 0..3_000 {
+                    let num_words = rng.gen_range(1, 30);
+                    let text = (0..num_words)
+                        // later words are more frequent.
+                        .map(|_| words[(rng.gen_range(0, 64) as f32).sqrt() as usize])
+                        .collect::<Vec<&str>>()
+                        .join(" ");
+                    index_writer.add_document(doc!(text_field => text));
+                }
+                index_writer.commit().unwrap();
+            }
+        }
+        index.load_searchers().unwrap();
+        let searcher = index.searcher();
+        let query = BooleanQuery::from(
+            ["a", "b", "h"]
+                .iter()
+                .map(|text| {
+                    let term_query = TermQuery::new(
+                        Term::from_field_text(text_field, text),
+                        IndexRecordOption::WithFreqs,
+                    );
+                    let query: Box<Query> = Box::new(term_query);
+                    (Occur::Should, query)
+                })
+                .collect::<Vec<_>>(),
+        );
+        {
+            // Block-Max WAND is only used when collecting the top documents.
+            let weight = query.weight(&*searcher, true).unwrap();
+            let scorer = weight.scorer(searcher.segment_reader(0u32)).unwrap();
+            assert!(!Downcast::<BlockMaxWandScorer>::is_type(&*scorer));
+            let top_k_scorer = weight.top_k_scorer(searcher.segment_reader(0u32)).unwrap();
+            assert!(Downcast::<BlockMaxWandScorer>::is_type(&*top_k_scorer));
+        }
+
+        let mut exhaustive_scores: Vec<(f32, DocAddress)> = vec![];
+        for (segment_ord, segment_reader) in searcher.segment_readers().iter().enumerate() {
+            let weight = query.weight(&*searcher, true).unwrap();
+            let mut scorer = weight.scorer(segment_reader).unwrap();
+            while scorer.advance() {
+                let doc_address = DocAddress(segment_ord as u32, scorer.doc());
+                exhaustive_scores.push((scorer.score(), doc_address));
+            }
+        }
+        assert_eq!(exhaustive_scores.len(), query.count(&*searcher).unwrap());
+        exhaustive_scores.sort_by(|left, right| right.0.partial_cmp(&left.0).unwrap());
+
+        let mut top_collector = TopCollector::with_limit(10);
+        searcher.search(&query, &mut top_collector).unwrap();
+        let top_score_docs = top_collector.score_docs();
+        assert_eq!(top_score_docs.len(), 10);
+        for (&(score, _), &(expected_score, _)) in top_score_docs.iter().zip(&exhaustive_scores) {
+            assert_nearly_equals(expected_score, score);
+        }
+        for &(score, doc_address) in &top_score_docs {
+            let &(expected_score, _) = exhaustive_scores
+                .iter()
+                .find(|&&(_, expected_doc_address)| expected_doc_address == doc_address)
+                .unwrap();
+            assert_nearly_equals(expected_score, score);
+        }
+    }
 }
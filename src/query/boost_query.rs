@@ -0,0 +1,200 @@
+use common::BitSet;
+use core::Searcher;
+use core::SegmentReader;
+use docset::{DocSet, SkipResult};
+use query::{Explanation, Query, QueryDsl, Scorer, Weight};
+use schema::{Schema, Term};
+use std::collections::BTreeSet;
+use DocId;
+use Result;
+use Score;
+
+/// `BoostQuery` is a wrapper over a query used to boost its score.
+///
+/// The document set matched by the `BoostQuery` is strictly the same as the underlying query.
+/// The score of each document is the score of the underlying query multiplied by the `boost`
+/// factor.
+///
+/// Boosting a clause of a `BooleanQuery` makes it possible to
+/// express that a match on a specific clause (e.g. a `title` match)
+/// should count more than a match on another clause (e.g. a `body` match).
+#[derive(Debug)]
+pub struct BoostQuery {
+    query: Box<Query>,
+    boost: Score,
+}
+
+impl BoostQuery {
+    /// Builds a boost query.
+    pub fn new(query: Box<Query>, boost: Score) -> BoostQuery {
+        BoostQuery { query, boost }
+    }
+}
+
+impl Query for BoostQuery {
+    fn weight(&self, searcher: &Searcher, scoring_enabled: bool) -> Result<Box<Weight>> {
+        let weight = self.query.weight(searcher, scoring_enabled)?;
+        if scoring_enabled {
+            Ok(Box::new(BoostWeight::new(weight, self.boost)))
+        } else {
+            // scores will be ignored anyway.
+            Ok(weight)
+        }
+    }
+
+    fn rewrite(self: Box<Self>, searcher: &Searcher) -> Result<Box<Query>> {
+        let query = self.query.rewrite(searcher)?;
+        Ok(Box::new(BoostQuery::new(query, self.boost)))
+    }
+
+    fn query_terms(&self, term_set: &mut BTreeSet<Term>) {
+        self.query.query_terms(term_set);
+    }
+
+    fn to_dsl(&self, schema: &Schema) -> Result<QueryDsl> {
+        Ok(QueryDsl::Boost {
+            query: Box::new(self.query.to_dsl(schema)?),
+            boost: self.boost,
+        })
+    }
+}
+
+/// Weight associated to the `BoostQuery`.
+pub struct BoostWeight {
+    weight: Box<Weight>,
+    boost: Score,
+}
+
+impl BoostWeight {
+    /// Creates a new `BoostWeight`.
+    pub fn new(weight: Box<Weight>, boost: Score) -> BoostWeight {
+        BoostWeight { weight, boost }
+    }
+}
+
+impl Weight for BoostWeight {
+    fn scorer(&self, reader: &SegmentReader) -> Result<Box<Scorer>> {
+        let scorer = self.weight.scorer(reader)?;
+        Ok(Box::new(BoostScorer::new(scorer, self.boost)))
+    }
+
+    fn count(&self, reader: &SegmentReader) -> Result<u32> {
+        self.weight.count(reader)
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> Result<Explanation> {
+        let underlying_explanation = self.weight.explain(reader, doc)?;
+        let mut explanation = Explanation::new(
+            "boosted score, product of:",
+            underlying_explanation.value() * self.boost,
+        );
+        explanation.add_detail(Explanation::new("boost", self.boost));
+        explanation.add_detail(underlying_explanation);
+        Ok(explanation)
+    }
+}
+
+/// `Scorer` multiplying the score of an underlying `Scorer`
+/// by a constant `boost` factor.
+pub struct BoostScorer<TScorer> {
+    underlying: TScorer,
+    boost: Score,
+}
+
+impl<TScorer: Scorer> BoostScorer<TScorer> {
+    /// Creates a new `BoostScorer`.
+    pub fn new(underlying: TScorer, boost: Score) -> BoostScorer<TScorer> {
+        BoostScorer { underlying, boost }
+    }
+}
+
+impl<TScorer: Scorer> DocSet for BoostScorer<TScorer> {
+    fn advance(&mut self) -> bool {
+        self.underlying.advance()
+    }
+
+    fn skip_next(&mut self, target: DocId) -> SkipResult {
+        self.underlying.skip_next(target)
+    }
+
+    fn fill_buffer(&mut self, buffer: &mut [DocId]) -> usize {
+        self.underlying.fill_buffer(buffer)
+    }
+
+    fn doc(&self) -> DocId {
+        self.underlying.doc()
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.underlying.size_hint()
+    }
+
+    fn append_to_bitset(&mut self, bitset: &mut BitSet) {
+        self.underlying.append_to_bitset(bitset);
+    }
+
+    fn count(&mut self) -> u32 {
+        self.underlying.count()
+    }
+}
+
+impl<TScorer: Scorer> Scorer for BoostScorer<TScorer> {
+    fn score(&mut self) -> Score {
+        self.underlying.score() * self.boost
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::BoostQuery;
+    use collector::tests::{matching_docs, matching_scores};
+    use query::{BooleanQuery, Occur, Query, TermQuery};
+    use schema::{IndexRecordOption, SchemaBuilder, Term, TEXT};
+    use tests::assert_nearly_equals;
+    use Index;
+
+    #[test]
+    fn test_boost_query() {
+        let mut schema_builder = SchemaBuilder::default();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let body = schema_builder.add_text_field("body", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 40_000_000).unwrap();
+            index_writer.add_document(doc!(title => "rust", body => "a language"));
+            index_writer.add_document(doc!(title => "a language", body => "rust"));
+            index_writer.add_document(doc!(title => "c", body => "another language"));
+            index_writer.commit().unwrap();
+        }
+        index.load_searchers().unwrap();
+        let searcher = index.searcher();
+        let make_term_query = |field, text: &str| -> Box<Query> {
+            Box::new(TermQuery::new(
+                Term::from_field_text(field, text),
+                IndexRecordOption::WithFreqs,
+            ))
+        };
+        {
+            let unboosted_query = make_term_query(title, "rust");
+            let boosted_query = BoostQuery::new(make_term_query(title, "rust"), 3f32);
+            assert_eq!(matching_docs(&searcher, &boosted_query), vec![0]);
+            let unboosted_scores = matching_scores(&searcher, &*unboosted_query);
+            let boosted_scores = matching_scores(&searcher, &boosted_query);
+            assert_nearly_equals(unboosted_scores[0] * 3f32, boosted_scores[0]);
+            assert_eq!(boosted_query.count(&*searcher).unwrap(), 1);
+        }
+        {
+            let title_clause: Box<Query> =
+                Box::new(BoostQuery::new(make_term_query(title, "rust"), 3f32));
+            let boolean_query = BooleanQuery::from(vec![
+                (Occur::Should, title_clause),
+                (Occur::Should, make_term_query(body, "rust")),
+            ]);
+            assert_eq!(matching_docs(&searcher, &boolean_query), vec![0, 1]);
+            let scores = matching_scores(&searcher, &boolean_query);
+            // the title match now weighs much more than the body match.
+            assert!(scores[0] > 2f32 * scores[1]);
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use core::Searcher;
+use core::SegmentReader;
+use docset::{DocSet, SkipResult};
+use query::explanation::does_not_match;
+use query::ConstScorer;
+use query::{Explanation, Query, QueryDsl, Scorer, Weight};
+use schema::{Schema, Term};
+use std::collections::BTreeSet;
+use DocId;
+use Result;
+use Score;
+
+/// `ConstantScoreQuery` is a wrapper over a query, assigning
+/// the same constant score to all of the documents it matches.
+///
+/// The document set matched by the `ConstantScoreQuery` is strictly the same
+/// as the underlying query. The underlying query is always evaluated with
+/// scoring disabled, so that no time is spent computing scores that
+/// would be discarded anyway.
+///
+/// This is typically useful for structural filters, such as a tenant id,
+/// that should restrict the matched documents without influencing their ranking.
+/// For filters within a `BooleanQuery`, see also `Occur::Filter`.
+#[derive(Debug)]
+pub struct ConstantScoreQuery {
+    query: Box<Query>,
+    score: Score,
+}
+
+impl ConstantScoreQuery {
+    /// Builds a constant score query.
+    pub fn new(query: Box<Query>, score: Score) -> ConstantScoreQuery {
+        ConstantScoreQuery { query, score }
+    }
+}
+
+impl Query for ConstantScoreQuery {
+    fn weight(&self, searcher: &Searcher, _scoring_enabled: bool) -> Result<Box<Weight>> {
+        let weight = self.query.weight(searcher, false)?;
+        Ok(Box::new(ConstantScoreWeight {
+            weight,
+            score: self.score,
+        }))
+    }
+
+    fn rewrite(self: Box<Self>, searcher: &Searcher) -> Result<Box<Query>> {
+        let query = self.query.rewrite(searcher)?;
+        Ok(Box::new(ConstantScoreQuery::new(query, self.score)))
+    }
+
+    fn query_terms(&self, term_set: &mut BTreeSet<Term>) {
+        self.query.query_terms(term_set);
+    }
+
+    fn to_dsl(&self, schema: &Schema) -> Result<QueryDsl> {
+        Ok(QueryDsl::ConstantScore {
+            query: Box::new(self.query.to_dsl(schema)?),
+            score: self.score,
+        })
+    }
+}
+
+struct ConstantScoreWeight {
+    weight: Box<Weight>,
+    score: Score,
+}
+
+impl Weight for ConstantScoreWeight {
+    fn scorer(&self, reader: &SegmentReader) -> Result<Box<Scorer>> {
+        let mut scorer = ConstScorer::new(self.weight.scorer(reader)?);
+        scorer.set_score(self.score);
+        Ok(Box::new(scorer))
+    }
+
+    fn count(&self, reader: &SegmentReader) -> Result<u32> {
+        self.weight.count(reader)
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> Result<Explanation> {
+        if self.weight.scorer(reader)?.skip_next(doc) != SkipResult::Reached {
+            return Err(does_not_match(doc));
+        }
+        Ok(Explanation::new(
+            "ConstantScoreQuery, constant score",
+            self.score,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::ConstantScoreQuery;
+    use collector::tests::{matching_docs, matching_scores};
+    use query::{BooleanQuery, Occur, Query, TermQuery};
+    use schema::{IndexRecordOption, SchemaBuilder, Term, TEXT};
+    use DocAddress;
+    use Index;
+
+    #[test]
+    fn test_constant_score_query() {
+        let mut schema_builder = SchemaBuilder::default();
+        let text = schema_builder.add_text_field("text", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 40_000_000).unwrap();
+            index_writer.add_document(doc!(text => "a b"));
+            index_writer.add_document(doc!(text => "a a a c"));
+            index_writer.add_document(doc!(text => "c"));
+            index_writer.commit().unwrap();
+        }
+        index.load_searchers().unwrap();
+        let searcher = index.searcher();
+        let make_term_query = |text_str: &str| -> Box<Query> {
+            Box::new(TermQuery::new(
+                Term::from_field_text(text, text_str),
+                IndexRecordOption::WithFreqs,
+            ))
+        };
+        {
+            let query = ConstantScoreQuery::new(make_term_query("a"), 2f32);
+            assert_eq!(matching_docs(&searcher, &query), vec![0, 1]);
+            assert_eq!(matching_scores(&searcher, &query), vec![2f32, 2f32]);
+            assert_eq!(query.count(&*searcher).unwrap(), 2);
+            let explanation = query.explain(&*searcher, DocAddress(0, 1)).unwrap();
+            assert_eq!(explanation.value(), 2f32);
+            assert!(query.explain(&*searcher, DocAddress(0, 2)).is_err());
+        }
+        {
+            let constant_clause: Box<Query> =
+                Box::new(ConstantScoreQuery::new(make_term_query("a"), 0.5f32));
+            let boolean_query = BooleanQuery::from(vec![
+                (Occur::Should, constant_clause),
+                (Occur::Should, make_term_query("c")),
+            ]);
+            let scores = matching_scores(&searcher, &boolean_query);
+            assert_eq!(scores[0], 0.5f32);
+            assert!(scores[1] > 0.5f32);
+        }
+    }
+}
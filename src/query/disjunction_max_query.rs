@@ -0,0 +1,217 @@
+use core::Searcher;
+use core::SegmentReader;
+use docset::{DocSet, SkipResult};
+use query::explanation::does_not_match;
+use query::score_combiner::{DisjunctionMaxCombiner, DoNothingCombiner};
+use query::EmptyScorer;
+use query::Union;
+use query::{Explanation, Query, QueryDsl, Scorer, Weight};
+use schema::{Schema, Term};
+use std::collections::BTreeSet;
+use DocId;
+use Result;
+use Score;
+
+/// `DisjunctionMaxQuery` matches all of the documents matched
+/// by at least one of its subqueries (also called disjuncts).
+///
+/// Unlike a `BooleanQuery` of `Should` clauses, the score of a document
+/// is not the sum of the scores of the disjuncts, but the maximum score,
+/// plus the scores of the other matching disjuncts multiplied
+/// by a `tie_breaker`.
+///
+/// This is typically useful to search a term over several fields:
+/// documents containing the term in all of the fields are not over-rewarded,
+/// while the `tie_breaker` still gives them a small advantage.
+///
+/// A `tie_breaker` of `0` only keeps the maximum score,
+/// while a `tie_breaker` of `1` sums all of the scores.
+#[derive(Debug)]
+pub struct DisjunctionMaxQuery {
+    disjuncts: Vec<Box<Query>>,
+    tie_breaker: Score,
+}
+
+impl DisjunctionMaxQuery {
+    /// Creates a new `DisjunctionMaxQuery`.
+    pub fn new(disjuncts: Vec<Box<Query>>, tie_breaker: Score) -> DisjunctionMaxQuery {
+        DisjunctionMaxQuery {
+            disjuncts,
+            tie_breaker,
+        }
+    }
+}
+
+impl Query for DisjunctionMaxQuery {
+    fn weight(&self, searcher: &Searcher, scoring_enabled: bool) -> Result<Box<Weight>> {
+        let weights = self
+            .disjuncts
+            .iter()
+            .map(|disjunct| disjunct.weight(searcher, scoring_enabled))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Box::new(DisjunctionMaxWeight {
+            weights,
+            tie_breaker: self.tie_breaker,
+            scoring_enabled,
+        }))
+    }
+
+    fn rewrite(self: Box<Self>, searcher: &Searcher) -> Result<Box<Query>> {
+        let disjuncts = self
+            .disjuncts
+            .into_iter()
+            .map(|disjunct| disjunct.rewrite(searcher))
+            .collect::<Result<_>>()?;
+        Ok(Box::new(DisjunctionMaxQuery::new(
+            disjuncts,
+            self.tie_breaker,
+        )))
+    }
+
+    fn query_terms(&self, term_set: &mut BTreeSet<Term>) {
+        for disjunct in &self.disjuncts {
+            disjunct.query_terms(term_set);
+        }
+    }
+
+    fn to_dsl(&self, schema: &Schema) -> Result<QueryDsl> {
+        Ok(QueryDsl::DisjunctionMax {
+            queries: self
+                .disjuncts
+                .iter()
+                .map(|disjunct| disjunct.to_dsl(schema))
+                .collect::<Result<_>>()?,
+            tie_breaker: self.tie_breaker,
+        })
+    }
+}
+
+struct DisjunctionMaxWeight {
+    weights: Vec<Box<Weight>>,
+    tie_breaker: Score,
+    scoring_enabled: bool,
+}
+
+impl Weight for DisjunctionMaxWeight {
+    fn scorer(&self, reader: &SegmentReader) -> Result<Box<Scorer>> {
+        let mut scorers = self
+            .weights
+            .iter()
+            .map(|weight| weight.scorer(reader))
+            .collect::<Result<Vec<Box<Scorer>>>>()?;
+        if scorers.is_empty() {
+            return Ok(Box::new(EmptyScorer));
+        }
+        if scorers.len() == 1 {
+            return Ok(scorers.pop().unwrap());
+        }
+        if self.scoring_enabled {
+            let score_combiner = DisjunctionMaxCombiner::with_tie_breaker(self.tie_breaker);
+            Ok(Box::new(Union::with_score_combiner(
+                scorers,
+                score_combiner,
+            )))
+        } else {
+            Ok(Box::new(Union::<_, DoNothingCombiner>::from(scorers)))
+        }
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> Result<Explanation> {
+        let mut scorer = self.scorer(reader)?;
+        if scorer.skip_next(doc) != SkipResult::Reached {
+            return Err(does_not_match(doc));
+        }
+        let mut explanation = Explanation::new(
+            "DisjunctionMaxQuery, max plus tie_breaker times the others of:",
+            scorer.score(),
+        );
+        explanation.add_detail(Explanation::new("tie_breaker", self.tie_breaker));
+        for weight in &self.weights {
+            if weight.scorer(reader)?.skip_next(doc) == SkipResult::Reached {
+                explanation.add_detail(weight.explain(reader, doc)?);
+            }
+        }
+        Ok(explanation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::DisjunctionMaxQuery;
+    use collector::tests::{matching_docs, matching_scores};
+    use query::{Query, TermQuery};
+    use schema::{IndexRecordOption, SchemaBuilder, Term, TEXT};
+    use tests::assert_nearly_equals;
+    use DocAddress;
+    use Index;
+
+    #[test]
+    fn test_disjunction_max_query() {
+        let mut schema_builder = SchemaBuilder::default();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let body = schema_builder.add_text_field("body", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 40_000_000).unwrap();
+            index_writer.add_document(doc!(title => "rust", body => "rust"));
+            index_writer.add_document(doc!(title => "rust", body => "a language"));
+            index_writer.add_document(doc!(title => "c", body => "another language"));
+            index_writer.commit().unwrap();
+        }
+        index.load_searchers().unwrap();
+        let searcher = index.searcher();
+        let make_term_query = |field, text: &str| -> Box<Query> {
+            Box::new(TermQuery::new(
+                Term::from_field_text(field, text),
+                IndexRecordOption::WithFreqs,
+            ))
+        };
+        let title_scores = matching_scores(&searcher, &*make_term_query(title, "rust"));
+        let body_scores = matching_scores(&searcher, &*make_term_query(body, "rust"));
+        {
+            let query = DisjunctionMaxQuery::new(
+                vec![
+                    make_term_query(title, "rust"),
+                    make_term_query(body, "rust"),
+                ],
+                0f32,
+            );
+            assert_eq!(matching_docs(&searcher, &query), vec![0, 1]);
+            assert_eq!(query.count(&*searcher).unwrap(), 2);
+            let scores = matching_scores(&searcher, &query);
+            assert_nearly_equals(title_scores[0].max(body_scores[0]), scores[0]);
+            assert_nearly_equals(title_scores[1], scores[1]);
+        }
+        {
+            let query = DisjunctionMaxQuery::new(
+                vec![
+                    make_term_query(title, "rust"),
+                    make_term_query(body, "rust"),
+                ],
+                0.5f32,
+            );
+            let scores = matching_scores(&searcher, &query);
+            let (max, min) = if title_scores[0] > body_scores[0] {
+                (title_scores[0], body_scores[0])
+            } else {
+                (body_scores[0], title_scores[0])
+            };
+            assert_nearly_equals(max + 0.5f32 * min, scores[0]);
+            assert_nearly_equals(title_scores[1], scores[1]);
+            let explanation = query.explain(&*searcher, DocAddress(0, 0)).unwrap();
+            assert_nearly_equals(scores[0], explanation.value());
+            let details = explanation.details();
+            assert_eq!(details.len(), 3);
+            assert_eq!(details[0].value(), 0.5f32);
+            assert_nearly_equals(title_scores[0], details[1].value());
+            assert_nearly_equals(body_scores[0], details[2].value());
+            let explanation = query.explain(&*searcher, DocAddress(0, 1)).unwrap();
+            assert_eq!(explanation.details().len(), 2);
+        }
+        {
+            let query = DisjunctionMaxQuery::new(vec![], 0f32);
+            assert!(matching_docs(&searcher, &query).is_empty());
+        }
+    }
+}
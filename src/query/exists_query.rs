@@ -0,0 +1,247 @@
+use common::BitSet;
+use core::Searcher;
+use core::SegmentReader;
+use error::ErrorKind;
+use fastfield::{FastValue, MultiValueIntFastFieldReader};
+use query::query_dsl::field_name;
+use query::BitSetDocSet;
+use query::ConstScorer;
+use query::{Query, QueryDsl, Scorer, Weight};
+use schema::{Cardinality, Field, FieldType, IndexRecordOption, Schema};
+use Result;
+
+/// `ExistsQuery` matches all of the documents that have
+/// at least one value for a given field.
+///
+/// Matched documents all get a constant `Score` of one.
+/// Combined with `Occur::MustNot` in a `BooleanQuery`, it makes it
+/// possible to search for the documents missing the field.
+///
+/// # Implementation
+///
+/// Depending on the type of the field, the values are detected:
+///
+/// - for multivalued `u64` and `i64` fast fields and for facets,
+/// as the documents associated to a non-empty list of values.
+/// - for bytes fields, as the documents associated to non-empty bytes.
+/// - for geo point fields, as the documents associated to a geo point.
+/// - for other indexed fields, by appending the documents of all of the
+/// terms of the field into a `BitSet`.
+///
+/// Single-valued fast fields that are not indexed are not supported,
+/// as all documents have a value for them.
+#[derive(Debug)]
+pub struct ExistsQuery {
+    field: Field,
+}
+
+impl ExistsQuery {
+    /// Creates a new `ExistsQuery` over the given field.
+    pub fn new(field: Field) -> ExistsQuery {
+        ExistsQuery { field }
+    }
+}
+
+impl Query for ExistsQuery {
+    fn weight(&self, searcher: &Searcher, _scoring_enabled: bool) -> Result<Box<Weight>> {
+        let field_entry = searcher.schema().get_field_entry(self.field);
+        let source = match *field_entry.field_type() {
+            FieldType::Bytes => ValueSource::Bytes,
+            FieldType::GeoPoint => ValueSource::GeoPoint,
+            FieldType::HierarchicalFacet => ValueSource::U64FastField,
+            FieldType::U64(ref int_options)
+                if int_options.get_fastfield_cardinality() == Some(Cardinality::MultiValues) =>
+            {
+                ValueSource::U64FastField
+            }
+            FieldType::I64(ref int_options)
+                if int_options.get_fastfield_cardinality() == Some(Cardinality::MultiValues) =>
+            {
+                ValueSource::I64FastField
+            }
+            ref field_type if field_type.is_indexed() => ValueSource::Postings,
+            _ => bail!(ErrorKind::SchemaError(format!(
+                "Exists query on field {:?} requires the field to be indexed, \
+                 a multivalued fast field, a bytes field or a geo point field",
+                field_entry.name()
+            ))),
+        };
+        Ok(Box::new(ExistsWeight {
+            field: self.field,
+            source,
+        }))
+    }
+
+    fn to_dsl(&self, schema: &Schema) -> Result<QueryDsl> {
+        Ok(QueryDsl::Exists {
+            field: field_name(schema, self.field),
+        })
+    }
+}
+
+/// Defines where the values of the field are read from.
+#[derive(Clone, Copy)]
+enum ValueSource {
+    Postings,
+    U64FastField,
+    I64FastField,
+    Bytes,
+    GeoPoint,
+}
+
+struct ExistsWeight {
+    field: Field,
+    source: ValueSource,
+}
+
+/// Appends the documents having at least one value
+/// in a multivalued fast field into a `BitSet`.
+fn append_multivalued_docs<Item: FastValue>(
+    ff_reader: &MultiValueIntFastFieldReader<Item>,
+    max_doc: u32,
+    doc_bitset: &mut BitSet,
+) {
+    for doc in 0..max_doc {
+        if ff_reader.num_vals(doc) > 0 {
+            doc_bitset.insert(doc);
+        }
+    }
+}
+
+impl Weight for ExistsWeight {
+    fn scorer(&self, reader: &SegmentReader) -> Result<Box<Scorer>> {
+        let max_doc = reader.max_doc();
+        let mut doc_bitset = BitSet::with_max_value(max_doc);
+        match self.source {
+            ValueSource::Postings => {
+                let inverted_index = reader.inverted_index(self.field);
+                let mut term_stream = inverted_index.terms().stream();
+                while term_stream.advance() {
+                    let mut block_segment_postings = inverted_index
+                        .read_block_postings_from_terminfo(
+                            term_stream.value(),
+                            IndexRecordOption::Basic,
+                        );
+                    while block_segment_postings.advance() {
+                        for &doc in block_segment_postings.docs() {
+                            doc_bitset.insert(doc);
+                        }
+                    }
+                }
+            }
+            ValueSource::U64FastField => {
+                let ff_reader = reader.multi_fast_field_reader::<u64>(self.field)?;
+                append_multivalued_docs(&ff_reader, max_doc, &mut doc_bitset);
+            }
+            ValueSource::I64FastField => {
+                let ff_reader = reader.multi_fast_field_reader::<i64>(self.field)?;
+                append_multivalued_docs(&ff_reader, max_doc, &mut doc_bitset);
+            }
+            ValueSource::Bytes => {
+                let bytes_reader = reader.bytes_fast_field_reader(self.field)?;
+                for doc in 0..max_doc {
+                    if !bytes_reader.get_val(doc).is_empty() {
+                        doc_bitset.insert(doc);
+                    }
+                }
+            }
+            ValueSource::GeoPoint => {
+                let geo_point_reader = reader.geo_point_fast_field_reader(self.field)?;
+                for doc in 0..max_doc {
+                    if geo_point_reader.get(doc).is_some() {
+                        doc_bitset.insert(doc);
+                    }
+                }
+            }
+        }
+        let doc_bitset = BitSetDocSet::from(doc_bitset);
+        Ok(Box::new(ConstScorer::new(doc_bitset)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::ExistsQuery;
+    use collector::tests::matching_docs;
+    use error::ErrorKind;
+    use query::{AllQuery, BooleanQuery, Occur, Query};
+    use schema::{Cardinality, Document, Facet, IntOptions, SchemaBuilder, FAST, STRING, TEXT};
+    use Index;
+
+    #[test]
+    fn test_exists_query() {
+        let mut schema_builder = SchemaBuilder::new();
+        let text_field = schema_builder.add_text_field("text", TEXT);
+        let id_field = schema_builder.add_text_field("id", STRING);
+        let multi_field = schema_builder.add_i64_field(
+            "multi",
+            IntOptions::default().set_fast(Cardinality::MultiValues),
+        );
+        let bytes_field = schema_builder.add_bytes_field("bytes");
+        let facet_field = schema_builder.add_facet_field("facet");
+        let single_fast_field = schema_builder.add_u64_field("single_fast", FAST);
+        let schema = schema_builder.build();
+        let index = Index::create_in_ram(schema);
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 40_000_000).unwrap();
+            for i in 0..10 {
+                let mut doc = Document::new();
+                doc.add_text(id_field, &format!("doc{}", i));
+                if i % 2 == 0 {
+                    doc.add_text(text_field, "a b");
+                }
+                if i % 3 == 0 {
+                    doc.add_i64(multi_field, -(i as i64));
+                    doc.add_i64(multi_field, i as i64);
+                }
+                if i % 4 == 0 {
+                    doc.add_bytes(bytes_field, vec![i as u8]);
+                }
+                if i % 5 == 0 {
+                    doc.add_facet(facet_field, Facet::from("/category/a"));
+                }
+                index_writer.add_document(doc);
+            }
+            index_writer.commit().unwrap();
+        }
+        index.load_searchers().unwrap();
+        let searcher = index.searcher();
+        assert_eq!(
+            matching_docs(&searcher, &ExistsQuery::new(text_field)),
+            vec![0, 2, 4, 6, 8]
+        );
+        assert_eq!(
+            matching_docs(&searcher, &ExistsQuery::new(multi_field)),
+            vec![0, 3, 6, 9]
+        );
+        assert_eq!(
+            matching_docs(&searcher, &ExistsQuery::new(bytes_field)),
+            vec![0, 4, 8]
+        );
+        assert_eq!(
+            matching_docs(&searcher, &ExistsQuery::new(facet_field)),
+            vec![0, 5]
+        );
+        {
+            let missing_text_query = BooleanQuery::from(vec![
+                (Occur::Must, Box::new(AllQuery) as Box<Query>),
+                (
+                    Occur::MustNot,
+                    Box::new(ExistsQuery::new(text_field)) as Box<Query>,
+                ),
+            ]);
+            assert_eq!(
+                matching_docs(&searcher, &missing_text_query),
+                vec![1, 3, 5, 7, 9]
+            );
+        }
+        {
+            let err = ExistsQuery::new(single_fast_field)
+                .weight(&*searcher, false)
+                .err()
+                .unwrap();
+            assert_matches!(err.kind(), &ErrorKind::SchemaError(_));
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use error::ErrorKind;
+use schema::{FieldType, Schema, Term};
+use serde_json;
+use DocId;
+use Error;
+use Score;
+
+/// `Explanation` describes how the score of a document was computed.
+///
+/// It is a tree of score contributions: each node holds a value,
+/// a description of what this value is, and the details
+/// that were combined to compute it.
+///
+/// The explanation of a document can be obtained using
+/// [`Query::explain(...)`](./trait.Query.html#method.explain).
+#[derive(Clone, Debug, Serialize)]
+pub struct Explanation {
+    value: Score,
+    description: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    details: Vec<Explanation>,
+}
+
+impl Explanation {
+    /// Creates an `Explanation` without any detail.
+    pub fn new<T: ToString>(description: T, value: Score) -> Explanation {
+        Explanation {
+            value,
+            description: description.to_string(),
+            details: Vec::new(),
+        }
+    }
+
+    /// Returns the value of this node.
+    pub fn value(&self) -> Score {
+        self.value
+    }
+
+    /// Returns the description of this node.
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    /// Returns the details that were combined to compute the value.
+    pub fn details(&self) -> &[Explanation] {
+        &self.details
+    }
+
+    /// Appends a detail to the explanation.
+    pub fn add_detail(&mut self, detail: Explanation) {
+        self.details.push(detail);
+    }
+
+    /// Serializes the explanation as a human readable JSON string.
+    pub fn to_pretty_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("Explanation serialization should never fail.")
+    }
+}
+
+/// Returns the error emitted when explaining a document
+/// that does not match the query.
+pub(crate) fn does_not_match(doc: DocId) -> Error {
+    ErrorKind::InvalidArgument(format!("Document #({}) does not match", doc)).into()
+}
+
+/// Returns a human readable form of the term, as `field:value`.
+pub(crate) fn term_description(schema: &Schema, term: &Term) -> String {
+    let field_entry = schema.get_field_entry(term.field());
+    let value = match *field_entry.field_type() {
+        FieldType::Str(_) | FieldType::HierarchicalFacet => {
+            String::from_utf8_lossy(term.value_bytes()).into_owned()
+        }
+        FieldType::U64(_) => term.get_u64().to_string(),
+        FieldType::I64(_) => term.get_i64().to_string(),
+        FieldType::Bytes | FieldType::GeoPoint => format!("{:?}", term.value_bytes()),
+    };
+    format!("{}:{}", field_entry.name(), value)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use collector::tests::TestCollector;
+    use error::ErrorKind;
+    use query::{BooleanQuery, Occur, PhraseQuery, Query, TermQuery};
+    use schema::{IndexRecordOption, SchemaBuilder, Term, TEXT};
+    use serde_json;
+    use tests::assert_nearly_equals;
+    use DocAddress;
+    use Index;
+
+    #[test]
+    fn test_explain() {
+        let mut schema_builder = SchemaBuilder::default();
+        let text = schema_builder.add_text_field("text", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 40_000_000).unwrap();
+            index_writer.add_document(doc!(text => "a b c"));
+            index_writer.add_document(doc!(text => "a a c d"));
+            index_writer.add_document(doc!(text => "d e"));
+            index_writer.commit().unwrap();
+        }
+        index.load_searchers().unwrap();
+        let searcher = index.searcher();
+        let term_a = Term::from_field_text(text, "a");
+        let term_c = Term::from_field_text(text, "c");
+        let term_query: Box<Query> =
+            Box::new(TermQuery::new(term_a.clone(), IndexRecordOption::WithFreqs));
+        let phrase_query: Box<Query> = Box::new(PhraseQuery::new(vec![term_a, term_c]));
+        let query = BooleanQuery::from(vec![
+            (Occur::Should, term_query),
+            (Occur::Should, phrase_query),
+        ]);
+        let scores = {
+            let mut test_collector = TestCollector::default();
+            searcher.search(&query, &mut test_collector).unwrap();
+            test_collector.scores()
+        };
+        assert_eq!(scores.len(), 2);
+        for (doc, &score) in scores.iter().enumerate() {
+            let explanation = query
+                .explain(&*searcher, DocAddress(0, doc as u32))
+                .unwrap();
+            assert_nearly_equals(score, explanation.value());
+            assert_eq!(explanation.description(), "BooleanQuery, sum of:");
+            let details_sum = explanation
+                .details()
+                .iter()
+                .map(|detail| detail.value())
+                .sum::<f32>();
+            assert_nearly_equals(score, details_sum);
+        }
+        {
+            // "a c" only appears as a phrase in the second document.
+            let explanation = query.explain(&*searcher, DocAddress(0, 0)).unwrap();
+            assert_eq!(explanation.details().len(), 1);
+            let term_explanation = &explanation.details()[0];
+            assert_eq!(
+                term_explanation.description(),
+                "TermQuery(text:a), result of:"
+            );
+            let bm25_explanation = &term_explanation.details()[0];
+            assert_eq!(bm25_explanation.details().len(), 3);
+        }
+        {
+            let explanation = query.explain(&*searcher, DocAddress(0, 1)).unwrap();
+            assert_eq!(explanation.details().len(), 2);
+            assert_eq!(
+                explanation.details()[1].description(),
+                "PhraseQuery(text:a text:c) with a slop of 0, result of:"
+            );
+            let json: serde_json::Value =
+                serde_json::from_str(&explanation.to_pretty_json()).unwrap();
+            assert_eq!(json["details"].as_array().unwrap().len(), 2);
+            assert_eq!(json["description"], "BooleanQuery, sum of:");
+        }
+        let err = query.explain(&*searcher, DocAddress(0, 2)).err().unwrap();
+        assert_matches!(err.kind(), &ErrorKind::InvalidArgument(_));
+    }
+}
@@ -0,0 +1,831 @@
+use common::BitSet;
+use core::Searcher;
+use core::SegmentReader;
+use docset::{DocSet, SkipResult};
+use error::ErrorKind;
+use fastfield::{FastFieldReader, GeoPointFastFieldReader};
+use query::query_dsl::{field_name, ScoreFunctionDsl};
+use query::{Explanation, Query, QueryDsl, Scorer, Weight};
+use schema::{Cardinality, Field, FieldType, GeoPoint, Schema, Term};
+use std::collections::BTreeSet;
+use DocId;
+use Result;
+use Score;
+
+/// Function applied to the value of the field by a
+/// `ScoreFunction::FieldValueFactor`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FieldValueModifier {
+    /// The value is used as is.
+    None,
+    /// `ln(1 + value)`
+    Log1p,
+    /// `sqrt(value)`
+    Sqrt,
+}
+
+/// Shape of the curve of a `ScoreFunction::Decay` or a `ScoreFunction::GeoDecay`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DecayFunction {
+    /// Normal decay, `exp(-distance² / (2σ²))`.
+    Gauss,
+    /// Exponential decay, `exp(-λ.distance)`.
+    Exp,
+    /// Linear decay, reaching 0 at `scale / (1 - decay)`.
+    Linear,
+}
+
+/// Defines how the score of the underlying query and
+/// the value of the function are combined.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CombineMode {
+    /// The score is the product of the query score and the function value.
+    Multiply,
+    /// The score is the sum of the query score and the function value.
+    Sum,
+    /// The score is the function value. The query score is ignored.
+    Replace,
+}
+
+/// Function computed from the value of a `u64` or `i64` fast field,
+/// or from the geo point of a `GeoPoint` field.
+///
+/// The fast field is required to be single-valued.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ScoreFunction {
+    /// Computes `modifier(factor * value)`.
+    ///
+    /// Negative values are clamped to 0 before the modifier is applied.
+    FieldValueFactor {
+        /// Fast field to read the value from.
+        field: Field,
+        /// Factor the value is multiplied by.
+        factor: f64,
+        /// Modifier applied on the multiplied value.
+        modifier: FieldValueModifier,
+    },
+    /// Computes a value decaying from `1` with the distance between
+    /// the value of the field and an `origin`.
+    ///
+    /// Values closer than `offset` from the origin are scored `1`.
+    /// Values at `offset + scale` from the origin are scored `decay`.
+    ///
+    /// The query fails with an `InvalidArgument` error unless `scale` is
+    /// positive, `offset` is positive or zero, and `decay` is within `]0, 1[`.
+    Decay {
+        /// Fast field to read the value from.
+        field: Field,
+        /// Shape of the decay curve.
+        decay_function: DecayFunction,
+        /// Value for which the function is maximal.
+        origin: f64,
+        /// Distance (in addition to `offset`) at which the function is worth `decay`.
+        scale: f64,
+        /// Distance from the origin under which the function is not decaying.
+        offset: f64,
+        /// Value of the function at `offset + scale` from the origin,
+        /// within `]0, 1[`.
+        decay: f64,
+    },
+    /// Computes a value decaying from `1` with the haversine distance,
+    /// in meters, between the geo point of the document and an `origin`.
+    ///
+    /// Documents without any geo point are scored `0`.
+    ///
+    /// The parameters are checked as for `Decay`.
+    ///
+    /// Used with `CombineMode::Replace`, it sorts the documents
+    /// from the nearest to the farthest.
+    GeoDecay {
+        /// Geo point field to read the geo point from.
+        field: Field,
+        /// Shape of the decay curve.
+        decay_function: DecayFunction,
+        /// Geo point for which the function is maximal.
+        origin: GeoPoint,
+        /// Distance in meters (in addition to `offset`) at which
+        /// the function is worth `decay`.
+        scale: f64,
+        /// Distance in meters from the origin under which
+        /// the function is not decaying.
+        offset: f64,
+        /// Value of the function at `offset + scale` from the origin,
+        /// within `]0, 1[`.
+        decay: f64,
+    },
+}
+
+impl DecayFunction {
+    fn eval(&self, distance: f64, scale: f64, offset: f64, decay: f64) -> f64 {
+        let distance = (distance - offset).max(0f64);
+        match *self {
+            DecayFunction::Gauss => (distance * distance * decay.ln() / (scale * scale)).exp(),
+            DecayFunction::Exp => (distance * decay.ln() / scale).exp(),
+            DecayFunction::Linear => {
+                let zero_distance = scale / (1f64 - decay);
+                ((zero_distance - distance) / zero_distance).max(0f64)
+            }
+        }
+    }
+}
+
+/// Checks that the parameters of a decay function define a decaying curve.
+fn check_decay_parameters(
+    scale: f64,
+    offset: f64,
+    decay: f64,
+) -> ::std::result::Result<(), String> {
+    if !(decay > 0f64 && decay < 1f64) {
+        return Err(format!("The decay must be within ]0, 1[, got {}", decay));
+    }
+    if !(scale > 0f64) {
+        return Err(format!("The scale must be positive, got {}", scale));
+    }
+    if !(offset >= 0f64) {
+        return Err(format!(
+            "The offset must be positive or zero, got {}",
+            offset
+        ));
+    }
+    Ok(())
+}
+
+impl ScoreFunction {
+    /// Checks the parameters of the function.
+    ///
+    /// Returns the description of the invalid parameter, if any.
+    pub(crate) fn validate(&self) -> ::std::result::Result<(), String> {
+        match *self {
+            ScoreFunction::FieldValueFactor { .. } => Ok(()),
+            ScoreFunction::Decay {
+                scale,
+                offset,
+                decay,
+                ..
+            }
+            | ScoreFunction::GeoDecay {
+                scale,
+                offset,
+                decay,
+                ..
+            } => check_decay_parameters(scale, offset, decay),
+        }
+    }
+
+    fn field(&self) -> Field {
+        match *self {
+            ScoreFunction::FieldValueFactor { field, .. }
+            | ScoreFunction::Decay { field, .. }
+            | ScoreFunction::GeoDecay { field, .. } => field,
+        }
+    }
+
+    /// Computes the value of the function, given the value of the field
+    /// or, for `GeoDecay`, the distance to the origin.
+    fn eval(&self, value: f64) -> f64 {
+        match *self {
+            ScoreFunction::FieldValueFactor {
+                factor, modifier, ..
+            } => {
+                let value = (factor * value).max(0f64);
+                match modifier {
+                    FieldValueModifier::None => value,
+                    FieldValueModifier::Log1p => value.ln_1p(),
+                    FieldValueModifier::Sqrt => value.sqrt(),
+                }
+            }
+            ScoreFunction::Decay {
+                decay_function,
+                origin,
+                scale,
+                offset,
+                decay,
+                ..
+            } => decay_function.eval((value - origin).abs(), scale, offset, decay),
+            ScoreFunction::GeoDecay {
+                decay_function,
+                scale,
+                offset,
+                decay,
+                ..
+            } => decay_function.eval(value, scale, offset, decay),
+        }
+    }
+
+    fn to_dsl(&self, schema: &Schema) -> ScoreFunctionDsl {
+        match *self {
+            ScoreFunction::FieldValueFactor {
+                field,
+                factor,
+                modifier,
+            } => ScoreFunctionDsl::FieldValueFactor {
+                field: field_name(schema, field),
+                factor,
+                modifier,
+            },
+            ScoreFunction::Decay {
+                field,
+                decay_function,
+                origin,
+                scale,
+                offset,
+                decay,
+            } => ScoreFunctionDsl::Decay {
+                field: field_name(schema, field),
+                decay_function,
+                origin,
+                scale,
+                offset,
+                decay,
+            },
+            ScoreFunction::GeoDecay {
+                field,
+                decay_function,
+                origin,
+                scale,
+                offset,
+                decay,
+            } => ScoreFunctionDsl::GeoDecay {
+                field: field_name(schema, field),
+                decay_function,
+                origin,
+                scale,
+                offset,
+                decay,
+            },
+        }
+    }
+
+    /// Explains the result of `.eval(...)`, `value` being `None`
+    /// if the document does not have any value.
+    fn explain(&self, value_opt: Option<f64>) -> Explanation {
+        let value = match value_opt {
+            Some(value) => value,
+            None => {
+                return Explanation::new(
+                    "function, 0 as the document does not have any value",
+                    0f32,
+                )
+            }
+        };
+        let (description, value_description) = match *self {
+            ScoreFunction::FieldValueFactor {
+                factor, modifier, ..
+            } => {
+                let formula = match modifier {
+                    FieldValueModifier::None => format!("{} * value", factor),
+                    FieldValueModifier::Log1p => format!("ln(1 + {} * value)", factor),
+                    FieldValueModifier::Sqrt => format!("sqrt({} * value)", factor),
+                };
+                (
+                    format!("field value factor, computed as {} from:", formula),
+                    "value of the field",
+                )
+            }
+            ScoreFunction::Decay {
+                decay_function,
+                origin,
+                scale,
+                offset,
+                decay,
+                ..
+            } => (
+                format!(
+                    "{:?} decay of the distance to {}, with a scale of {}, \
+                     an offset of {} and a decay of {}, from:",
+                    decay_function, origin, scale, offset, decay
+                ),
+                "value of the field",
+            ),
+            ScoreFunction::GeoDecay {
+                decay_function,
+                scale,
+                offset,
+                decay,
+                ..
+            } => (
+                format!(
+                    "{:?} decay of the distance to the origin, with a scale of {}, \
+                     an offset of {} and a decay of {}, from:",
+                    decay_function, scale, offset, decay
+                ),
+                "distance to the origin, in meters",
+            ),
+        };
+        let mut explanation = Explanation::new(description, self.eval(value) as Score);
+        explanation.add_detail(Explanation::new(value_description, value as Score));
+        explanation
+    }
+}
+
+/// `FunctionScoreQuery` modifies the score of an underlying query
+/// given the fast field values of the matched documents.
+///
+/// The document set matched by the `FunctionScoreQuery` is strictly
+/// the same as the underlying query. The value of the `ScoreFunction`
+/// is combined with the score of the underlying query according to
+/// the `CombineMode` (by default, `CombineMode::Multiply`).
+///
+/// Several functions (e.g. popularity and recency) can be
+/// applied by nesting `FunctionScoreQuery`s.
+///
+/// # Example
+///
+/// ```rust
+/// # use tantivy::schema::{SchemaBuilder, TEXT, FAST};
+/// # use tantivy::query::{CombineMode, FieldValueModifier, FunctionScoreQuery};
+/// # use tantivy::query::{ScoreFunction, TermQuery};
+/// # use tantivy::schema::{IndexRecordOption, Term};
+/// # let mut schema_builder = SchemaBuilder::new();
+/// # let title = schema_builder.add_text_field("title", TEXT);
+/// # let popularity = schema_builder.add_u64_field("popularity", FAST);
+/// let term_query = TermQuery::new(
+///     Term::from_field_text(title, "diary"),
+///     IndexRecordOption::WithFreqs,
+/// );
+/// let mut query = FunctionScoreQuery::new(
+///     Box::new(term_query),
+///     ScoreFunction::FieldValueFactor {
+///         field: popularity,
+///         factor: 1.0,
+///         modifier: FieldValueModifier::Log1p,
+///     },
+/// );
+/// query.set_combine_mode(CombineMode::Sum);
+/// ```
+#[derive(Debug)]
+pub struct FunctionScoreQuery {
+    query: Box<Query>,
+    function: ScoreFunction,
+    combine_mode: CombineMode,
+}
+
+impl FunctionScoreQuery {
+    /// Creates a new `FunctionScoreQuery`.
+    pub fn new(query: Box<Query>, function: ScoreFunction) -> FunctionScoreQuery {
+        FunctionScoreQuery {
+            query,
+            function,
+            combine_mode: CombineMode::Multiply,
+        }
+    }
+
+    /// Sets the way the value of the function is combined
+    /// with the score of the underlying query.
+    pub fn set_combine_mode(&mut self, combine_mode: CombineMode) {
+        self.combine_mode = combine_mode;
+    }
+}
+
+impl Query for FunctionScoreQuery {
+    fn weight(&self, searcher: &Searcher, scoring_enabled: bool) -> Result<Box<Weight>> {
+        if let Err(message) = self.function.validate() {
+            bail!(ErrorKind::InvalidArgument(message))
+        }
+        if !scoring_enabled {
+            // scores will be ignored anyway.
+            return self.query.weight(searcher, false);
+        }
+        let field = self.function.field();
+        let field_entry = searcher.schema().get_field_entry(field);
+        if let ScoreFunction::GeoDecay { .. } = self.function {
+            if *field_entry.field_type() != FieldType::GeoPoint {
+                bail!(ErrorKind::SchemaError(format!(
+                    "Geo decay function on field {:?} requires the field to be a geo point field",
+                    field_entry.name()
+                )))
+            }
+        } else {
+            let is_single_valued_fast_field = match *field_entry.field_type() {
+                FieldType::U64(ref int_options) | FieldType::I64(ref int_options) => {
+                    int_options.get_fastfield_cardinality() == Some(Cardinality::SingleValue)
+                }
+                _ => false,
+            };
+            if !is_single_valued_fast_field {
+                bail!(ErrorKind::SchemaError(format!(
+                    "Function score on field {:?} requires the field to be a single-valued int fast field",
+                    field_entry.name()
+                )))
+            }
+        }
+        let query_weight = self
+            .query
+            .weight(searcher, self.combine_mode != CombineMode::Replace)?;
+        Ok(Box::new(FunctionScoreWeight {
+            weight: query_weight,
+            function: self.function,
+            combine_mode: self.combine_mode,
+        }))
+    }
+
+    fn rewrite(self: Box<Self>, searcher: &Searcher) -> Result<Box<Query>> {
+        let FunctionScoreQuery {
+            query,
+            function,
+            combine_mode,
+        } = *self;
+        let mut function_score_query = FunctionScoreQuery::new(query.rewrite(searcher)?, function);
+        function_score_query.set_combine_mode(combine_mode);
+        Ok(Box::new(function_score_query))
+    }
+
+    fn query_terms(&self, term_set: &mut BTreeSet<Term>) {
+        self.query.query_terms(term_set);
+    }
+
+    fn to_dsl(&self, schema: &Schema) -> Result<QueryDsl> {
+        Ok(QueryDsl::FunctionScore {
+            query: Box::new(self.query.to_dsl(schema)?),
+            function: self.function.to_dsl(schema),
+            combine_mode: if self.combine_mode == CombineMode::Multiply {
+                None
+            } else {
+                Some(self.combine_mode)
+            },
+        })
+    }
+}
+
+struct FunctionScoreWeight {
+    weight: Box<Weight>,
+    function: ScoreFunction,
+    combine_mode: CombineMode,
+}
+
+impl FunctionScoreWeight {
+    fn value_reader(&self, reader: &SegmentReader) -> Result<ValueReader> {
+        let field = self.function.field();
+        Ok(match self.function {
+            ScoreFunction::GeoDecay { origin, .. } => {
+                ValueReader::GeoDistance(reader.geo_point_fast_field_reader(field)?, origin)
+            }
+            _ => match *reader.schema().get_field_entry(field).field_type() {
+                FieldType::I64(_) => ValueReader::I64(reader.fast_field_reader(field)?),
+                _ => ValueReader::U64(reader.fast_field_reader(field)?),
+            },
+        })
+    }
+}
+
+impl Weight for FunctionScoreWeight {
+    fn scorer(&self, reader: &SegmentReader) -> Result<Box<Scorer>> {
+        let scorer = self.weight.scorer(reader)?;
+        Ok(Box::new(FunctionScorer {
+            underlying: scorer,
+            value_reader: self.value_reader(reader)?,
+            function: self.function,
+            combine_mode: self.combine_mode,
+        }))
+    }
+
+    fn count(&self, reader: &SegmentReader) -> Result<u32> {
+        self.weight.count(reader)
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> Result<Explanation> {
+        let query_explanation = self.weight.explain(reader, doc)?;
+        let value_opt = self.value_reader(reader)?.get(doc);
+        let function_explanation = self.function.explain(value_opt);
+        let function_score = function_explanation.value();
+        let mut explanation = match self.combine_mode {
+            CombineMode::Multiply => Explanation::new(
+                "FunctionScoreQuery, product of:",
+                query_explanation.value() * function_score,
+            ),
+            CombineMode::Sum => Explanation::new(
+                "FunctionScoreQuery, sum of:",
+                query_explanation.value() + function_score,
+            ),
+            CombineMode::Replace => {
+                let mut explanation = Explanation::new(
+                    "FunctionScoreQuery, value of the function, replacing the query score:",
+                    function_score,
+                );
+                explanation.add_detail(function_explanation);
+                return Ok(explanation);
+            }
+        };
+        explanation.add_detail(query_explanation);
+        explanation.add_detail(function_explanation);
+        Ok(explanation)
+    }
+}
+
+enum ValueReader {
+    U64(FastFieldReader<u64>),
+    I64(FastFieldReader<i64>),
+    // distance to the origin.
+    GeoDistance(GeoPointFastFieldReader, GeoPoint),
+}
+
+impl ValueReader {
+    /// Returns `None` iff the document does not have any value.
+    fn get(&self, doc: DocId) -> Option<f64> {
+        match *self {
+            ValueReader::U64(ref ff_reader) => Some(ff_reader.get(doc) as f64),
+            ValueReader::I64(ref ff_reader) => Some(ff_reader.get(doc) as f64),
+            ValueReader::GeoDistance(ref geo_point_reader, ref origin) => {
+                geo_point_reader.distance(doc, origin)
+            }
+        }
+    }
+}
+
+struct FunctionScorer<TScorer> {
+    underlying: TScorer,
+    value_reader: ValueReader,
+    function: ScoreFunction,
+    combine_mode: CombineMode,
+}
+
+impl<TScorer: Scorer> DocSet for FunctionScorer<TScorer> {
+    fn advance(&mut self) -> bool {
+        self.underlying.advance()
+    }
+
+    fn skip_next(&mut self, target: DocId) -> SkipResult {
+        self.underlying.skip_next(target)
+    }
+
+    fn fill_buffer(&mut self, buffer: &mut [DocId]) -> usize {
+        self.underlying.fill_buffer(buffer)
+    }
+
+    fn doc(&self) -> DocId {
+        self.underlying.doc()
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.underlying.size_hint()
+    }
+
+    fn append_to_bitset(&mut self, bitset: &mut BitSet) {
+        self.underlying.append_to_bitset(bitset);
+    }
+
+    fn count(&mut self) -> u32 {
+        self.underlying.count()
+    }
+}
+
+impl<TScorer: Scorer> Scorer for FunctionScorer<TScorer> {
+    fn score(&mut self) -> Score {
+        let function_score = self
+            .value_reader
+            .get(self.underlying.doc())
+            .map(|value| self.function.eval(value) as Score)
+            .unwrap_or(0f32);
+        match self.combine_mode {
+            CombineMode::Multiply => self.underlying.score() * function_score,
+            CombineMode::Sum => self.underlying.score() + function_score,
+            CombineMode::Replace => function_score,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{CombineMode, DecayFunction, FieldValueModifier};
+    use super::{FunctionScoreQuery, ScoreFunction};
+    use collector::tests::matching_scores;
+    use collector::TopCollector;
+    use error::ErrorKind;
+    use query::{AllQuery, Query, TermQuery};
+    use schema::{Field, GeoPoint, IndexRecordOption, SchemaBuilder, Term};
+    use schema::{FAST, INT_INDEXED, TEXT};
+    use tests::assert_nearly_equals;
+    use DocAddress;
+    use Index;
+
+    fn decay(decay_function: DecayFunction) -> ScoreFunction {
+        ScoreFunction::Decay {
+            field: Field(0),
+            decay_function,
+            origin: 100f64,
+            scale: 10f64,
+            offset: 5f64,
+            decay: 0.5f64,
+        }
+    }
+
+    #[test]
+    fn test_decay_functions() {
+        for &decay_function in &[
+            DecayFunction::Gauss,
+            DecayFunction::Exp,
+            DecayFunction::Linear,
+        ] {
+            let function = decay(decay_function);
+            assert_nearly_equals(1f32, function.eval(100f64) as f32);
+            assert_nearly_equals(1f32, function.eval(95f64) as f32);
+            assert_nearly_equals(0.5f32, function.eval(115f64) as f32);
+            assert_nearly_equals(0.5f32, function.eval(85f64) as f32);
+            assert!(function.eval(110f64) > 0.5f64);
+            assert!(function.eval(120f64) < 0.5f64);
+        }
+        assert_nearly_equals(0.25f32, decay(DecayFunction::Exp).eval(125f64) as f32);
+        assert_nearly_equals(0.0625f32, decay(DecayFunction::Gauss).eval(125f64) as f32);
+        assert_eq!(decay(DecayFunction::Linear).eval(125f64), 0f64);
+    }
+
+    #[test]
+    fn test_invalid_decay_parameters() {
+        let mut schema_builder = SchemaBuilder::new();
+        let date_field = schema_builder.add_i64_field("date", FAST);
+        let location_field = schema_builder.add_geo_point_field("location");
+        let index = Index::create_in_ram(schema_builder.build());
+        index.load_searchers().unwrap();
+        let searcher = index.searcher();
+        let decay = |decay_function, scale, offset, decay| ScoreFunction::Decay {
+            field: date_field,
+            decay_function,
+            origin: 0f64,
+            scale,
+            offset,
+            decay,
+        };
+        let geo_decay = ScoreFunction::GeoDecay {
+            field: location_field,
+            decay_function: DecayFunction::Gauss,
+            origin: GeoPoint::new(0.0, 0.0),
+            scale: 1_000f64,
+            offset: 0f64,
+            decay: 0f64,
+        };
+        let valid_decay = decay(DecayFunction::Gauss, 10f64, 0f64, 0.5f64);
+        let query = FunctionScoreQuery::new(Box::new(AllQuery), valid_decay);
+        assert!(query.weight(&*searcher, true).is_ok());
+        for function in vec![
+            decay(DecayFunction::Gauss, 10f64, 0f64, 0f64),
+            decay(DecayFunction::Linear, 10f64, 0f64, 1f64),
+            decay(DecayFunction::Exp, 10f64, 0f64, 1.5f64),
+            decay(DecayFunction::Exp, 0f64, 0f64, 0.5f64),
+            decay(DecayFunction::Exp, 10f64, -1f64, 0.5f64),
+            geo_decay,
+        ] {
+            let query = FunctionScoreQuery::new(Box::new(AllQuery), function);
+            let err = query.weight(&*searcher, true).err().unwrap();
+            assert_matches!(err.kind(), &ErrorKind::InvalidArgument(_));
+        }
+    }
+
+    #[test]
+    fn test_field_value_factor() {
+        let function = |modifier: FieldValueModifier| ScoreFunction::FieldValueFactor {
+            field: Field(0),
+            factor: 2f64,
+            modifier,
+        };
+        assert_nearly_equals(8f32, function(FieldValueModifier::None).eval(4f64) as f32);
+        assert_nearly_equals(
+            9f64.ln() as f32,
+            function(FieldValueModifier::Log1p).eval(4f64) as f32,
+        );
+        assert_nearly_equals(4f32, function(FieldValueModifier::Sqrt).eval(8f64) as f32);
+        assert_eq!(function(FieldValueModifier::Sqrt).eval(-8f64), 0f64);
+    }
+
+    #[test]
+    fn test_function_score_query() {
+        let mut schema_builder = SchemaBuilder::new();
+        let text_field = schema_builder.add_text_field("text", TEXT);
+        let popularity_field = schema_builder.add_u64_field("popularity", FAST);
+        let date_field = schema_builder.add_i64_field("date", FAST);
+        let indexed_field = schema_builder.add_u64_field("indexed", INT_INDEXED);
+        let schema = schema_builder.build();
+        let index = Index::create_in_ram(schema);
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 40_000_000).unwrap();
+            index_writer.add_document(doc!(
+                text_field => "a b",
+                popularity_field => 3u64,
+                date_field => -10i64,
+                indexed_field => 1u64
+            ));
+            index_writer.add_document(doc!(
+                text_field => "a",
+                popularity_field => 8u64,
+                date_field => 0i64,
+                indexed_field => 1u64
+            ));
+            index_writer.commit().unwrap();
+        }
+        index.load_searchers().unwrap();
+        let searcher = index.searcher();
+        let term_query = || {
+            Box::new(TermQuery::new(
+                Term::from_field_text(text_field, "a"),
+                IndexRecordOption::WithFreqs,
+            ))
+        };
+        let popularity = ScoreFunction::FieldValueFactor {
+            field: popularity_field,
+            factor: 1f64,
+            modifier: FieldValueModifier::None,
+        };
+        let term_scores = matching_scores(&searcher, &*term_query());
+        {
+            let query = FunctionScoreQuery::new(term_query(), popularity);
+            let function_scores = matching_scores(&searcher, &query);
+            assert_nearly_equals(term_scores[0] * 3f32, function_scores[0]);
+            assert_nearly_equals(term_scores[1] * 8f32, function_scores[1]);
+            let explanation = query.explain(&*searcher, DocAddress(0, 1)).unwrap();
+            assert_nearly_equals(function_scores[1], explanation.value());
+            assert_eq!(explanation.description(), "FunctionScoreQuery, product of:");
+            let function_explanation = &explanation.details()[1];
+            assert_eq!(
+                function_explanation.description(),
+                "field value factor, computed as 1 * value from:"
+            );
+            assert_eq!(function_explanation.details()[0].value(), 8f32);
+        }
+        {
+            let mut query = FunctionScoreQuery::new(term_query(), popularity);
+            query.set_combine_mode(CombineMode::Sum);
+            let function_scores = matching_scores(&searcher, &query);
+            assert_nearly_equals(term_scores[0] + 3f32, function_scores[0]);
+            assert_nearly_equals(term_scores[1] + 8f32, function_scores[1]);
+        }
+        {
+            let recency = ScoreFunction::Decay {
+                field: date_field,
+                decay_function: DecayFunction::Exp,
+                origin: 0f64,
+                scale: 10f64,
+                offset: 0f64,
+                decay: 0.5f64,
+            };
+            let mut query = FunctionScoreQuery::new(Box::new(AllQuery), recency);
+            query.set_combine_mode(CombineMode::Replace);
+            assert_eq!(matching_scores(&searcher, &query), vec![0.5f32, 1f32]);
+            let explanation = query.explain(&*searcher, DocAddress(0, 0)).unwrap();
+            assert_eq!(explanation.value(), 0.5f32);
+            assert_eq!(explanation.details().len(), 1);
+            assert_eq!(explanation.details()[0].details()[0].value(), -10f32);
+        }
+        {
+            let query = FunctionScoreQuery::new(
+                term_query(),
+                ScoreFunction::FieldValueFactor {
+                    field: indexed_field,
+                    factor: 1f64,
+                    modifier: FieldValueModifier::None,
+                },
+            );
+            let err = query.weight(&*searcher, true).err().unwrap();
+            assert_matches!(err.kind(), &ErrorKind::SchemaError(_));
+        }
+    }
+
+    #[test]
+    fn test_geo_decay() {
+        let mut schema_builder = SchemaBuilder::new();
+        let location_field = schema_builder.add_geo_point_field("location");
+        let popularity_field = schema_builder.add_u64_field("popularity", FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 40_000_000).unwrap();
+            // about 11km from the origin.
+            index_writer.add_document(doc!(location_field => GeoPoint::new(0.1, 0.0)));
+            index_writer.add_document(doc!(popularity_field => 1u64));
+            // about 1.1km from the origin.
+            index_writer.add_document(doc!(location_field => GeoPoint::new(0.0, 0.01)));
+            // about 5.6km from the origin.
+            index_writer.add_document(doc!(location_field => GeoPoint::new(0.05, 0.0)));
+            index_writer.commit().unwrap();
+        }
+        index.load_searchers().unwrap();
+        let searcher = index.searcher();
+        let geo_decay = |field: Field| ScoreFunction::GeoDecay {
+            field,
+            decay_function: DecayFunction::Exp,
+            origin: GeoPoint::new(0.0, 0.0),
+            scale: 5_000f64,
+            offset: 0f64,
+            decay: 0.5f64,
+        };
+        let mut query = FunctionScoreQuery::new(Box::new(AllQuery), geo_decay(location_field));
+        query.set_combine_mode(CombineMode::Replace);
+        let mut top_collector = TopCollector::with_limit(4);
+        searcher.search(&query, &mut top_collector).unwrap();
+        let docs: Vec<u32> = top_collector
+            .docs()
+            .into_iter()
+            .map(|doc_address| doc_address.doc())
+            .collect();
+        assert_eq!(docs, vec![2, 3, 0, 1]);
+        let score_docs = top_collector.score_docs();
+        assert_eq!(score_docs[3].0, 0f32);
+        let distance = GeoPoint::new(0.0, 0.0).distance(&GeoPoint::new(0.0, 0.01));
+        assert_nearly_equals(0.5f64.powf(distance / 5_000f64) as f32, score_docs[0].0);
+
+        let query = FunctionScoreQuery::new(Box::new(AllQuery), geo_decay(popularity_field));
+        let err = query.weight(&*searcher, true).err().unwrap();
+        assert_matches!(err.kind(), &ErrorKind::SchemaError(_));
+    }
+}
@@ -0,0 +1,389 @@
+use core::searcher::Searcher;
+use error::ErrorKind;
+use fst::Automaton;
+use levenshtein_automata::{Distance, LevenshteinAutomatonBuilder, DFA, SINK_STATE};
+use query::query_dsl::field_name;
+use query::{AutomatonWeight, Query, QueryDsl, Weight};
+use schema::{Schema, Term, Type};
+use std::collections::HashMap;
+use Result;
+
+/// Maximum edit distance supported by the `FuzzyTermQuery`.
+const MAX_DISTANCE: u8 = 2u8;
+
+lazy_static! {
+    // Building a Levenshtein automaton builder is costly.
+    // They are computed once and for all for all of the supported
+    // distances, with and without transpositions.
+    static ref LEV_BUILDER: HashMap<(u8, bool), LevenshteinAutomatonBuilder> = {
+        let mut lev_builder_cache = HashMap::new();
+        for distance in 0..MAX_DISTANCE + 1 {
+            for &transposition_cost_one in &[false, true] {
+                let lev_automaton_builder =
+                    LevenshteinAutomatonBuilder::new(distance, transposition_cost_one);
+                lev_builder_cache.insert((distance, transposition_cost_one), lev_automaton_builder);
+            }
+        }
+        lev_builder_cache
+    };
+}
+
+/// State of a `FuzzyAutomaton`.
+#[derive(Clone, Copy, Debug)]
+enum FuzzyState {
+    /// The automaton is still reading the exact prefix,
+    /// and has consumed the given number of bytes.
+    Prefix(usize),
+    /// The prefix has been consumed, and the remaining
+    /// bytes are fed to the Levenshtein `DFA`.
+    Suffix(u32),
+}
+
+/// Automaton accepting the terms that start with an exact
+/// `prefix`, followed by a suffix within a given Levenshtein
+/// distance of the rest of the query term.
+struct FuzzyAutomaton {
+    prefix: Vec<u8>,
+    dfa: DFA,
+}
+
+impl Automaton for FuzzyAutomaton {
+    type State = FuzzyState;
+
+    fn start(&self) -> FuzzyState {
+        if self.prefix.is_empty() {
+            FuzzyState::Suffix(self.dfa.initial_state())
+        } else {
+            FuzzyState::Prefix(0)
+        }
+    }
+
+    fn is_match(&self, state: &FuzzyState) -> bool {
+        match *state {
+            FuzzyState::Prefix(_) => false,
+            FuzzyState::Suffix(dfa_state) => match self.dfa.distance(dfa_state) {
+                Distance::Exact(_) => true,
+                Distance::AtLeast(_) => false,
+            },
+        }
+    }
+
+    fn can_match(&self, state: &FuzzyState) -> bool {
+        match *state {
+            FuzzyState::Prefix(_) => true,
+            FuzzyState::Suffix(dfa_state) => dfa_state != SINK_STATE,
+        }
+    }
+
+    fn accept(&self, state: &FuzzyState, byte: u8) -> FuzzyState {
+        match *state {
+            FuzzyState::Prefix(num_bytes) => {
+                if self.prefix[num_bytes] != byte {
+                    FuzzyState::Suffix(SINK_STATE)
+                } else if num_bytes + 1 == self.prefix.len() {
+                    FuzzyState::Suffix(self.dfa.initial_state())
+                } else {
+                    FuzzyState::Prefix(num_bytes + 1)
+                }
+            }
+            FuzzyState::Suffix(dfa_state) => {
+                FuzzyState::Suffix(self.dfa.transition(dfa_state, byte))
+            }
+        }
+    }
+}
+
+/// A Fuzzy Query matches all of the documents
+/// containing a specific term that is within
+/// Levenshtein distance.
+///
+/// The `prefix_length` (defaults to `0`) defines the number
+/// of leading characters of the term that must match exactly.
+///
+/// Matched documents all get a constant `Score` of one.
+///
+/// # Implementation
+///
+/// A Levenshtein automaton is built for the term, and intersected
+/// with the term dictionary of each segment. Only the matching
+/// region of the dictionary is visited.
+///
+/// # Example
+///
+/// ```rust
+/// #[macro_use]
+/// extern crate tantivy;
+/// use tantivy::schema::{SchemaBuilder, TEXT};
+/// use tantivy::{Index, Result, Term};
+/// use tantivy::collector::CountCollector;
+/// use tantivy::query::{FuzzyTermQuery, Query};
+///
+/// # fn main() { example().unwrap(); }
+/// fn example() -> Result<()> {
+///     let mut schema_builder = SchemaBuilder::new();
+///     let title = schema_builder.add_text_field("title", TEXT);
+///     let schema = schema_builder.build();
+///     let index = Index::create_in_ram(schema);
+///     {
+///         let mut index_writer = index.writer(3_000_000)?;
+///         index_writer.add_document(doc!(
+///             title => "The Name of the Wind",
+///         ));
+///         index_writer.add_document(doc!(
+///             title => "The Diary of Muadib",
+///         ));
+///         index_writer.add_document(doc!(
+///             title => "A Dairy Cow",
+///         ));
+///         index_writer.add_document(doc!(
+///             title => "The Diary of a Young Girl",
+///         ));
+///         index_writer.commit().unwrap();
+///     }
+///
+///     index.load_searchers()?;
+///     let searcher = index.searcher();
+///
+///     {
+///         let term = Term::from_field_text(title, "diary");
+///         let query = FuzzyTermQuery::new(term, 1, true);
+///         let mut count_collector = CountCollector::default();
+///         query.search(&*searcher, &mut count_collector)?;
+///         assert_eq!(count_collector.count(), 3);
+///     }
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct FuzzyTermQuery {
+    /// What term are we searching
+    term: Term,
+    /// How many changes are we going to allow
+    distance: u8,
+    /// Should a transposition cost 1 or 2?
+    transposition_cost_one: bool,
+    /// Number of leading characters that must match exactly
+    prefix_length: usize,
+}
+
+impl FuzzyTermQuery {
+    /// Creates a new Fuzzy Query
+    ///
+    /// `distance` must be at most 2.
+    /// If `transposition_cost_one` is true, swapping two adjacent
+    /// characters counts as a single edit.
+    pub fn new(term: Term, distance: u8, transposition_cost_one: bool) -> FuzzyTermQuery {
+        FuzzyTermQuery {
+            term,
+            distance,
+            transposition_cost_one,
+            prefix_length: 0,
+        }
+    }
+
+    /// Sets the number of leading characters of the term
+    /// that are required to match exactly.
+    ///
+    /// A non-zero prefix length makes the query significantly cheaper,
+    /// as a smaller part of the term dictionary is visited.
+    pub fn set_prefix_length(&mut self, prefix_length: usize) {
+        self.prefix_length = prefix_length;
+    }
+
+    fn specialized_weight(&self, searcher: &Searcher) -> Result<AutomatonWeight<FuzzyAutomaton>> {
+        let field = self.term.field();
+        let value_type = searcher
+            .schema()
+            .get_field_entry(field)
+            .field_type()
+            .value_type();
+        if value_type != Type::Str {
+            let err_msg = format!(
+                "Create a fuzzy term query on a field of type {:?}, \
+                 when only fields of type {:?} are supported",
+                value_type,
+                Type::Str
+            );
+            bail!(ErrorKind::SchemaError(err_msg))
+        }
+        let automaton_builder = LEV_BUILDER
+            .get(&(self.distance, self.transposition_cost_one))
+            .ok_or_else(|| {
+                ErrorKind::InvalidArgument(format!(
+                    "Levenshtein distance of {} is not supported (max is {})",
+                    self.distance, MAX_DISTANCE
+                ))
+            })?;
+        let text = self.term.text();
+        let prefix_num_bytes = text
+            .char_indices()
+            .nth(self.prefix_length)
+            .map(|(byte_offset, _)| byte_offset)
+            .unwrap_or(text.len());
+        let (prefix, suffix) = text.split_at(prefix_num_bytes);
+        let automaton = FuzzyAutomaton {
+            prefix: prefix.as_bytes().to_vec(),
+            dfa: automaton_builder.build_dfa(suffix),
+        };
+        Ok(AutomatonWeight::new(field, automaton))
+    }
+}
+
+impl Query for FuzzyTermQuery {
+    fn weight(&self, searcher: &Searcher, _scoring_enabled: bool) -> Result<Box<Weight>> {
+        Ok(Box::new(self.specialized_weight(searcher)?))
+    }
+
+    fn rewrite(self: Box<Self>, searcher: &Searcher) -> Result<Box<Query>> {
+        let term_set_query = self.specialized_weight(searcher)?.term_set_query(searcher)?;
+        Ok(Box::new(term_set_query))
+    }
+
+    fn to_dsl(&self, schema: &Schema) -> Result<QueryDsl> {
+        Ok(QueryDsl::Fuzzy {
+            field: field_name(schema, self.term.field()),
+            value: String::from_utf8_lossy(self.term.value_bytes()).into_owned(),
+            distance: self.distance,
+            transpositions: self.transposition_cost_one,
+            prefix_length: self.prefix_length,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::FuzzyTermQuery;
+    use collector::tests::matching_docs;
+    use error::ErrorKind;
+    use query::Query;
+    use schema::{Field, SchemaBuilder, INT_INDEXED, TEXT};
+    use std::collections::BTreeSet;
+    use Index;
+    use Term;
+
+    fn create_index(countries: &[&'static str]) -> (Index, Field) {
+        let mut schema_builder = SchemaBuilder::new();
+        let country_field = schema_builder.add_text_field("country", TEXT);
+        let schema = schema_builder.build();
+        let index = Index::create_in_ram(schema);
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 10_000_000).unwrap();
+            for &country in countries {
+                index_writer.add_document(doc!(country_field => country));
+            }
+            index_writer.commit().unwrap();
+        }
+        index.load_searchers().unwrap();
+        (index, country_field)
+    }
+
+    #[test]
+    pub fn test_fuzzy_term() {
+        let (index, country_field) = create_index(&["japan", "korea", "japon", "jpaan"]);
+        {
+            let term = Term::from_field_text(country_field, "japon");
+            let fuzzy_query = FuzzyTermQuery::new(term, 0, true);
+            assert_eq!(matching_docs(&index.searcher(), &fuzzy_query), vec![2]);
+        }
+        {
+            let term = Term::from_field_text(country_field, "japon");
+            let fuzzy_query = FuzzyTermQuery::new(term, 1, true);
+            assert_eq!(matching_docs(&index.searcher(), &fuzzy_query), vec![0, 2]);
+        }
+        {
+            let term = Term::from_field_text(country_field, "japan");
+            let fuzzy_query = FuzzyTermQuery::new(term, 1, false);
+            assert_eq!(matching_docs(&index.searcher(), &fuzzy_query), vec![0, 2]);
+        }
+        {
+            let term = Term::from_field_text(country_field, "japan");
+            let fuzzy_query = FuzzyTermQuery::new(term, 1, true);
+            assert_eq!(
+                matching_docs(&index.searcher(), &fuzzy_query),
+                vec![0, 2, 3]
+            );
+        }
+        {
+            let term = Term::from_field_text(country_field, "japan");
+            let fuzzy_query = FuzzyTermQuery::new(term, 2, false);
+            assert_eq!(
+                matching_docs(&index.searcher(), &fuzzy_query),
+                vec![0, 2, 3]
+            );
+        }
+    }
+
+    #[test]
+    pub fn test_fuzzy_term_prefix_length() {
+        let (index, country_field) = create_index(&["japan", "korea", "japon", "jpaan", "kapan"]);
+        {
+            let term = Term::from_field_text(country_field, "japan");
+            let fuzzy_query = FuzzyTermQuery::new(term, 1, true);
+            assert_eq!(
+                matching_docs(&index.searcher(), &fuzzy_query),
+                vec![0, 2, 3, 4]
+            );
+        }
+        {
+            let term = Term::from_field_text(country_field, "japan");
+            let mut fuzzy_query = FuzzyTermQuery::new(term, 1, true);
+            fuzzy_query.set_prefix_length(1);
+            assert_eq!(
+                matching_docs(&index.searcher(), &fuzzy_query),
+                vec![0, 2, 3]
+            );
+        }
+        {
+            let term = Term::from_field_text(country_field, "japan");
+            let mut fuzzy_query = FuzzyTermQuery::new(term, 1, true);
+            fuzzy_query.set_prefix_length(2);
+            assert_eq!(matching_docs(&index.searcher(), &fuzzy_query), vec![0, 2]);
+        }
+        {
+            let term = Term::from_field_text(country_field, "japan");
+            let mut fuzzy_query = FuzzyTermQuery::new(term, 1, true);
+            fuzzy_query.set_prefix_length(10);
+            assert_eq!(matching_docs(&index.searcher(), &fuzzy_query), vec![0]);
+        }
+    }
+
+    #[test]
+    pub fn test_fuzzy_term_errors() {
+        let (index, country_field) = create_index(&["japan"]);
+        let searcher = index.searcher();
+        {
+            let term = Term::from_field_text(country_field, "japan");
+            let fuzzy_query = FuzzyTermQuery::new(term, 3, true);
+            let err = fuzzy_query.weight(&*searcher, false).err().unwrap();
+            assert_matches!(err.kind(), &ErrorKind::InvalidArgument(_));
+        }
+        {
+            let mut schema_builder = SchemaBuilder::new();
+            let int_field = schema_builder.add_u64_field("num", INT_INDEXED);
+            let index = Index::create_in_ram(schema_builder.build());
+            index.load_searchers().unwrap();
+            let fuzzy_query = FuzzyTermQuery::new(Term::from_field_u64(int_field, 3u64), 1, true);
+            let err = fuzzy_query.weight(&*index.searcher(), false).err().unwrap();
+            assert_matches!(err.kind(), &ErrorKind::SchemaError(_));
+        }
+    }
+    #[test]
+    pub fn test_fuzzy_term_rewrite() {
+        let (index, country_field) = create_index(&["japan", "korea", "japon", "jpaan"]);
+        let searcher = index.searcher();
+        let term = Term::from_field_text(country_field, "japan");
+        let fuzzy_query = FuzzyTermQuery::new(term, 1, true);
+        let mut query_terms = BTreeSet::new();
+        fuzzy_query.query_terms(&mut query_terms);
+        assert!(query_terms.is_empty());
+        let rewritten_query = Box::new(fuzzy_query).rewrite(&*searcher).unwrap();
+        rewritten_query.query_terms(&mut query_terms);
+        let terms: Vec<&str> = query_terms.iter().map(|term| term.text()).collect();
+        assert_eq!(terms, vec!["japan", "japon", "jpaan"]);
+        assert_eq!(
+            matching_docs(&index.searcher(), &*rewritten_query),
+            vec![0, 2, 3]
+        );
+    }
+}
@@ -0,0 +1,92 @@
+use super::{geo_weight, GeoShape};
+use core::Searcher;
+use query::query_dsl::field_name;
+use query::{Query, QueryDsl, Weight};
+use schema::{Field, GeoPoint, Schema};
+use Result;
+
+/// `GeoBoundingBoxQuery` matches all of the documents whose
+/// geo point lies within a bounding box.
+///
+/// The bounding box is defined by its top left and
+/// bottom right corners. If the longitude of the top left corner
+/// is greater than the longitude of the bottom right corner,
+/// the box is considered to cross the antimeridian.
+///
+/// Matched documents all get a constant `Score` of one.
+///
+/// # Implementation
+///
+/// The geo points of all of the documents are scanned from the
+/// fast field of the `GeoPoint` field.
+///
+/// # Example
+///
+/// ```rust
+/// # use tantivy::schema::{GeoPoint, SchemaBuilder};
+/// # use tantivy::query::GeoBoundingBoxQuery;
+/// # let mut schema_builder = SchemaBuilder::new();
+/// # let location = schema_builder.add_geo_point_field("location");
+/// // Paris, within the ring road.
+/// let query = GeoBoundingBoxQuery::new(
+///     location,
+///     GeoPoint::new(48.902, 2.224),
+///     GeoPoint::new(48.815, 2.470),
+/// );
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct GeoBoundingBoxQuery {
+    field: Field,
+    top_left: GeoPoint,
+    bottom_right: GeoPoint,
+}
+
+impl GeoBoundingBoxQuery {
+    /// Creates a new `GeoBoundingBoxQuery`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the latitude of the top left corner is
+    /// lower than the latitude of the bottom right corner.
+    pub fn new(field: Field, top_left: GeoPoint, bottom_right: GeoPoint) -> GeoBoundingBoxQuery {
+        assert!(
+            top_left.lat() >= bottom_right.lat(),
+            "The top left corner of the bounding box must be above its bottom right corner."
+        );
+        GeoBoundingBoxQuery {
+            field,
+            top_left,
+            bottom_right,
+        }
+    }
+}
+
+impl GeoShape for GeoBoundingBoxQuery {
+    fn contains(&self, geo_point: &GeoPoint) -> bool {
+        let (lat, lon) = (geo_point.lat(), geo_point.lon());
+        if lat > self.top_left.lat() || lat < self.bottom_right.lat() {
+            return false;
+        }
+        let (left, right) = (self.top_left.lon(), self.bottom_right.lon());
+        if left <= right {
+            lon >= left && lon <= right
+        } else {
+            // the bounding box crosses the antimeridian.
+            lon >= left || lon <= right
+        }
+    }
+}
+
+impl Query for GeoBoundingBoxQuery {
+    fn weight(&self, searcher: &Searcher, _scoring_enabled: bool) -> Result<Box<Weight>> {
+        geo_weight(searcher, self.field, *self)
+    }
+
+    fn to_dsl(&self, schema: &Schema) -> Result<QueryDsl> {
+        Ok(QueryDsl::GeoBoundingBox {
+            field: field_name(schema, self.field),
+            top_left: self.top_left,
+            bottom_right: self.bottom_right,
+        })
+    }
+}
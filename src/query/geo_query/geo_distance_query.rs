@@ -0,0 +1,70 @@
+use super::{geo_weight, GeoShape};
+use core::Searcher;
+use query::query_dsl::field_name;
+use query::{Query, QueryDsl, Weight};
+use schema::{Field, GeoPoint, Schema};
+use Result;
+
+/// `GeoDistanceQuery` matches all of the documents whose
+/// geo point is within a given distance of an origin.
+///
+/// Distances are expressed in meters, and computed
+/// with the haversine formula.
+///
+/// Matched documents all get a constant `Score` of one.
+/// Documents can be sorted by distance using a `FunctionScoreQuery`
+/// with a `ScoreFunction::GeoDecay`.
+///
+/// # Implementation
+///
+/// The geo points of all of the documents are scanned from the
+/// fast field of the `GeoPoint` field.
+///
+/// # Example
+///
+/// ```rust
+/// # use tantivy::schema::{GeoPoint, SchemaBuilder};
+/// # use tantivy::query::GeoDistanceQuery;
+/// # let mut schema_builder = SchemaBuilder::new();
+/// # let location = schema_builder.add_geo_point_field("location");
+/// // within 10km of the Eiffel tower.
+/// let query = GeoDistanceQuery::new(location, GeoPoint::new(48.8584, 2.2945), 10_000f64);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct GeoDistanceQuery {
+    field: Field,
+    origin: GeoPoint,
+    distance: f64,
+}
+
+impl GeoDistanceQuery {
+    /// Creates a new `GeoDistanceQuery`, matching the documents
+    /// at most `distance` meters away from `origin`.
+    pub fn new(field: Field, origin: GeoPoint, distance: f64) -> GeoDistanceQuery {
+        GeoDistanceQuery {
+            field,
+            origin,
+            distance,
+        }
+    }
+}
+
+impl GeoShape for GeoDistanceQuery {
+    fn contains(&self, geo_point: &GeoPoint) -> bool {
+        self.origin.distance(geo_point) <= self.distance
+    }
+}
+
+impl Query for GeoDistanceQuery {
+    fn weight(&self, searcher: &Searcher, _scoring_enabled: bool) -> Result<Box<Weight>> {
+        geo_weight(searcher, self.field, *self)
+    }
+
+    fn to_dsl(&self, schema: &Schema) -> Result<QueryDsl> {
+        Ok(QueryDsl::GeoDistance {
+            field: field_name(schema, self.field),
+            origin: self.origin,
+            distance: self.distance,
+        })
+    }
+}
@@ -0,0 +1,181 @@
+/*!
+Queries over the geo points of a `GeoPoint` field.
+*/
+
+mod geo_bounding_box_query;
+mod geo_distance_query;
+
+pub use self::geo_bounding_box_query::GeoBoundingBoxQuery;
+pub use self::geo_distance_query::GeoDistanceQuery;
+
+use common::BitSet;
+use core::Searcher;
+use core::SegmentReader;
+use error::ErrorKind;
+use query::BitSetDocSet;
+use query::ConstScorer;
+use query::{Scorer, Weight};
+use schema::{Field, FieldType, GeoPoint};
+use Result;
+
+/// Area of the earth matched by a geo query.
+trait GeoShape {
+    /// Returns true iff the geo point is within the shape.
+    fn contains(&self, geo_point: &GeoPoint) -> bool;
+}
+
+/// Returns the weight matching the documents whose geo point is within the shape,
+/// after checking that the field is a `GeoPoint` field.
+fn geo_weight<TShape: GeoShape + 'static>(
+    searcher: &Searcher,
+    field: Field,
+    shape: TShape,
+) -> Result<Box<Weight>> {
+    let field_entry = searcher.schema().get_field_entry(field);
+    if *field_entry.field_type() != FieldType::GeoPoint {
+        bail!(ErrorKind::SchemaError(format!(
+            "Geo query on field {:?} requires the field to be a geo point field",
+            field_entry.name()
+        )))
+    }
+    Ok(Box::new(GeoWeight { field, shape }))
+}
+
+/// `Weight` of the geo queries, scanning the geo
+/// points of all of the documents of each segment.
+struct GeoWeight<TShape> {
+    field: Field,
+    shape: TShape,
+}
+
+impl<TShape: GeoShape> Weight for GeoWeight<TShape> {
+    fn scorer(&self, reader: &SegmentReader) -> Result<Box<Scorer>> {
+        let max_doc = reader.max_doc();
+        let geo_point_reader = reader.geo_point_fast_field_reader(self.field)?;
+        let mut doc_bitset = BitSet::with_max_value(max_doc);
+        for doc in 0..max_doc {
+            if let Some(geo_point) = geo_point_reader.get(doc) {
+                if self.shape.contains(&geo_point) {
+                    doc_bitset.insert(doc);
+                }
+            }
+        }
+        let doc_bitset = BitSetDocSet::from(doc_bitset);
+        Ok(Box::new(ConstScorer::new(doc_bitset)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{GeoBoundingBoxQuery, GeoDistanceQuery};
+    use collector::tests::matching_docs;
+    use error::ErrorKind;
+    use futures::Future;
+    use query::{ExistsQuery, Query};
+    use schema::{Field, GeoPoint, SchemaBuilder, STRING};
+    use Index;
+
+    fn create_index() -> (Index, Field, Field) {
+        let mut schema_builder = SchemaBuilder::new();
+        let city = schema_builder.add_text_field("city", STRING);
+        let location = schema_builder.add_geo_point_field("location");
+        let index = Index::create_in_ram(schema_builder.build());
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 40_000_000).unwrap();
+            index_writer.add_document(doc!(
+                city => "paris",
+                location => GeoPoint::new(48.8566, 2.3522)
+            ));
+            index_writer.add_document(doc!(
+                city => "versailles",
+                location => GeoPoint::new(48.8049, 2.1204)
+            ));
+            index_writer.add_document(doc!(
+                city => "london",
+                location => GeoPoint::new(51.5074, -0.1278)
+            ));
+            index_writer.add_document(doc!(city => "nowhere"));
+            index_writer.commit().unwrap();
+            index_writer.add_document(doc!(
+                city => "tokyo",
+                location => GeoPoint::new(35.6762, 139.6503)
+            ));
+            index_writer.add_document(doc!(
+                city => "suva",
+                location => GeoPoint::new(-18.1248, 178.4501)
+            ));
+            index_writer.add_document(doc!(
+                city => "apia",
+                location => GeoPoint::new(-13.8506, -171.7513)
+            ));
+            index_writer.commit().unwrap();
+            let segment_ids = index
+                .searchable_segment_ids()
+                .expect("Searchable segments failed.");
+            index_writer
+                .merge(&segment_ids)
+                .wait()
+                .expect("Merging failed");
+            index_writer.wait_merging_threads().unwrap();
+        }
+        index.load_searchers().unwrap();
+        assert_eq!(index.searcher().segment_readers().len(), 1);
+        (index, city, location)
+    }
+
+    #[test]
+    fn test_geo_distance_query() {
+        let (index, _, location) = create_index();
+        let paris = GeoPoint::new(48.8566, 2.3522);
+        let within = |distance: f64| {
+            matching_docs(
+                &index.searcher(),
+                &GeoDistanceQuery::new(location, paris, distance),
+            )
+        };
+        assert_eq!(within(10_000f64), vec![0]);
+        assert_eq!(within(20_000f64), vec![0, 1]);
+        assert_eq!(within(500_000f64), vec![0, 1, 2]);
+        assert_eq!(within(20_100_000f64), vec![0, 1, 2, 4, 5, 6]);
+    }
+
+    #[test]
+    fn test_geo_bounding_box_query() {
+        let (index, _, location) = create_index();
+        let within = |top_left: GeoPoint, bottom_right: GeoPoint| {
+            let query = GeoBoundingBoxQuery::new(location, top_left, bottom_right);
+            matching_docs(&index.searcher(), &query)
+        };
+        assert_eq!(
+            within(GeoPoint::new(60.0, -10.0), GeoPoint::new(40.0, 20.0)),
+            vec![0, 1, 2]
+        );
+        assert_eq!(
+            within(GeoPoint::new(60.0, 0.0), GeoPoint::new(40.0, 20.0)),
+            vec![0, 1]
+        );
+        // crossing the antimeridian.
+        assert_eq!(
+            within(GeoPoint::new(-10.0, 170.0), GeoPoint::new(-20.0, -170.0)),
+            vec![5, 6]
+        );
+        assert_eq!(
+            within(GeoPoint::new(-10.0, -170.0), GeoPoint::new(-20.0, 170.0)),
+            Vec::<u32>::new()
+        );
+    }
+
+    #[test]
+    fn test_geo_query_requires_geo_point_field() {
+        let (index, city, location) = create_index();
+        assert_eq!(
+            matching_docs(&index.searcher(), &ExistsQuery::new(location)),
+            vec![0, 1, 2, 4, 5, 6]
+        );
+        let searcher = index.searcher();
+        let query = GeoDistanceQuery::new(city, GeoPoint::new(0.0, 0.0), 1_000f64);
+        let err = query.weight(&*searcher, false).err().unwrap();
+        assert_matches!(err.kind(), &ErrorKind::SchemaError(_));
+    }
+}
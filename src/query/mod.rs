@@ -3,21 +3,38 @@ Query
 */
 
 mod all_query;
+mod automaton_weight;
 mod bitset;
 mod bm25;
+mod block_join_query;
 mod boolean_query;
+mod boost_query;
+mod constant_score_query;
+mod disjunction_max_query;
 mod exclude;
+mod exists_query;
+mod explanation;
+mod function_score_query;
+mod fuzzy_query;
+mod geo_query;
 mod intersection;
+mod more_like_this_query;
 mod occur;
 mod phrase_query;
+mod prefix_query;
 mod query;
+mod query_dsl;
 mod query_parser;
 mod range_query;
+mod regex_query;
 mod reqopt_scorer;
 mod scorer;
+mod span_query;
 mod term_query;
+mod term_set_query;
 mod union;
 mod weight;
+mod wildcard_query;
 
 #[cfg(test)]
 mod vec_docset;
@@ -31,19 +48,40 @@ pub use self::union::Union;
 pub use self::vec_docset::VecDocSet;
 
 pub use self::all_query::{AllQuery, AllScorer, AllWeight};
+pub use self::automaton_weight::AutomatonWeight;
 pub use self::bitset::BitSetDocSet;
-pub use self::boolean_query::BooleanQuery;
+pub use self::block_join_query::{ScoreMode, ToChildBlockJoinQuery, ToParentBlockJoinQuery};
+pub use self::boolean_query::{BooleanQuery, MinimumShouldMatch};
+pub use self::boost_query::BoostQuery;
+pub use self::constant_score_query::ConstantScoreQuery;
+pub use self::disjunction_max_query::DisjunctionMaxQuery;
 pub use self::exclude::Exclude;
+pub use self::exists_query::ExistsQuery;
+pub use self::explanation::Explanation;
+pub use self::function_score_query::{CombineMode, DecayFunction, FieldValueModifier};
+pub use self::function_score_query::{FunctionScoreQuery, ScoreFunction};
+pub use self::fuzzy_query::FuzzyTermQuery;
+pub use self::geo_query::{GeoBoundingBoxQuery, GeoDistanceQuery};
 pub use self::intersection::intersect_scorers;
+pub use self::more_like_this_query::MoreLikeThisQuery;
 pub use self::occur::Occur;
-pub use self::phrase_query::PhraseQuery;
-pub use self::query::Query;
+pub use self::phrase_query::{MultiPhraseQuery, PhrasePrefixQuery, PhraseQuery};
+pub use self::prefix_query::PrefixQuery;
+pub use self::query::{BoxableQuery, Query};
+pub use self::query_dsl::{QueryDsl, QueryDslError, ScoreFunctionDsl};
+pub use self::query_parser::GrammarMode;
 pub use self::query_parser::QueryParser;
 pub use self::query_parser::QueryParserError;
-pub use self::range_query::RangeQuery;
+pub use self::range_query::{RangeQuery, RangeStrategy};
+pub use self::regex_query::RegexQuery;
 pub use self::reqopt_scorer::RequiredOptionalScorer;
 pub use self::scorer::ConstScorer;
 pub use self::scorer::EmptyScorer;
 pub use self::scorer::Scorer;
+pub use self::span_query::{Span, SpanQuery, SpanScorer, SpanWeight};
+pub use self::span_query::{SpanFirstQuery, SpanNearQuery, SpanNotQuery, SpanOrQuery};
+pub use self::span_query::{SpanPrefixQuery, SpanTermQuery};
 pub use self::term_query::TermQuery;
+pub use self::term_set_query::TermSetQuery;
 pub use self::weight::Weight;
+pub use self::wildcard_query::WildcardQuery;
@@ -0,0 +1,371 @@
+use core::Index;
+use core::Searcher;
+use error::ErrorKind;
+use query::query_dsl::field_name;
+use query::{BooleanQuery, Query, QueryDsl, Weight};
+use schema::{Field, FieldType, Schema, Value};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+use tokenizer::TokenizerManager;
+use DocAddress;
+use Result;
+use Score;
+use Term;
+
+use query::bm25::idf;
+
+/// Default maximum number of terms of the query built by a `MoreLikeThisQuery`.
+const DEFAULT_MAX_QUERY_TERMS: usize = 25;
+
+#[derive(Debug)]
+pub(crate) enum MoreLikeThisSource {
+    Document(DocAddress),
+    Text(String),
+}
+
+/// `MoreLikeThisQuery` matches documents similar to a given
+/// document, or to a given text.
+///
+/// The text of the source, in the given fields, is tokenized using
+/// the tokenizer of each field. The terms with the highest tf-idf are then
+/// selected, and the query is executed as a `BooleanQuery`, with one
+/// `Should` clause per selected term.
+///
+/// When the source is a document of the index, its fields need to be stored.
+/// Note that this document will typically be matched by the query.
+///
+/// The selected terms can be tuned via
+/// - [`.set_min_doc_freq(...)`](#method.set_min_doc_freq) and
+///   [`.set_max_doc_freq(...)`](#method.set_max_doc_freq), to ignore
+///   rare or frequent terms (e.g. stop words).
+/// - [`.set_min_term_len(...)`](#method.set_min_term_len), to ignore short terms.
+/// - [`.set_max_query_terms(...)`](#method.set_max_query_terms), to cap the number of
+///   selected terms. (25 by default)
+///
+/// # Example
+///
+/// ```rust
+/// #[macro_use]
+/// extern crate tantivy;
+/// use tantivy::schema::{SchemaBuilder, TEXT};
+/// use tantivy::{Index, Result};
+/// use tantivy::collector::CountCollector;
+/// use tantivy::query::{MoreLikeThisQuery, Query};
+///
+/// # fn main() { example().unwrap(); }
+/// fn example() -> Result<()> {
+///     let mut schema_builder = SchemaBuilder::new();
+///     let title = schema_builder.add_text_field("title", TEXT);
+///     let schema = schema_builder.build();
+///     let index = Index::create_in_ram(schema);
+///     {
+///         let mut index_writer = index.writer(3_000_000)?;
+///         index_writer.add_document(doc!(title => "The Name of the Wind"));
+///         index_writer.add_document(doc!(title => "The Wise Man's Fear"));
+///         index_writer.add_document(doc!(title => "A Dairy Cow"));
+///         index_writer.commit().unwrap();
+///     }
+///     index.load_searchers()?;
+///     let searcher = index.searcher();
+///
+///     let mut query = MoreLikeThisQuery::for_text(&index, "the wind of change", vec![title]);
+///     query.set_min_term_len(4);
+///     let mut count_collector = CountCollector::default();
+///     query.search(&*searcher, &mut count_collector)?;
+///     assert_eq!(count_collector.count(), 1);
+///     Ok(())
+/// }
+/// ```
+pub struct MoreLikeThisQuery {
+    source: MoreLikeThisSource,
+    fields: Vec<Field>,
+    tokenizer_manager: TokenizerManager,
+    min_doc_freq: u64,
+    max_doc_freq: Option<u64>,
+    min_term_len: usize,
+    max_query_terms: usize,
+}
+
+impl MoreLikeThisQuery {
+    pub(crate) fn new(
+        source: MoreLikeThisSource,
+        fields: Vec<Field>,
+        tokenizer_manager: TokenizerManager,
+    ) -> MoreLikeThisQuery {
+        MoreLikeThisQuery {
+            source,
+            fields,
+            tokenizer_manager,
+            min_doc_freq: 1,
+            max_doc_freq: None,
+            min_term_len: 0,
+            max_query_terms: DEFAULT_MAX_QUERY_TERMS,
+        }
+    }
+
+    /// Creates a `MoreLikeThisQuery` matching documents similar
+    /// to the document at `doc_address`, over the given `fields`.
+    pub fn for_document(
+        index: &Index,
+        doc_address: DocAddress,
+        fields: Vec<Field>,
+    ) -> MoreLikeThisQuery {
+        MoreLikeThisQuery::new(
+            MoreLikeThisSource::Document(doc_address),
+            fields,
+            index.tokenizers().clone(),
+        )
+    }
+
+    /// Creates a `MoreLikeThisQuery` matching documents similar
+    /// to the given `text`, over the given `fields`.
+    pub fn for_text(index: &Index, text: &str, fields: Vec<Field>) -> MoreLikeThisQuery {
+        MoreLikeThisQuery::new(
+            MoreLikeThisSource::Text(text.to_string()),
+            fields,
+            index.tokenizers().clone(),
+        )
+    }
+
+    /// Ignores the terms appearing in less than `min_doc_freq` documents.
+    pub fn set_min_doc_freq(&mut self, min_doc_freq: u64) {
+        self.min_doc_freq = min_doc_freq;
+    }
+
+    /// Ignores the terms appearing in more than `max_doc_freq` documents.
+    pub fn set_max_doc_freq(&mut self, max_doc_freq: u64) {
+        self.max_doc_freq = Some(max_doc_freq);
+    }
+
+    /// Ignores the terms shorter than `min_term_len` characters.
+    pub fn set_min_term_len(&mut self, min_term_len: usize) {
+        self.min_term_len = min_term_len;
+    }
+
+    /// Sets the maximum number of terms selected to build the query.
+    pub fn set_max_query_terms(&mut self, max_query_terms: usize) {
+        self.max_query_terms = max_query_terms;
+    }
+
+    /// Returns the texts of the source, associated to their field.
+    fn source_texts(&self, searcher: &Searcher) -> Result<Vec<(Field, String)>> {
+        match self.source {
+            MoreLikeThisSource::Text(ref text) => Ok(self
+                .fields
+                .iter()
+                .map(|&field| (field, text.clone()))
+                .collect()),
+            MoreLikeThisSource::Document(ref doc_address) => {
+                let doc = searcher.doc(doc_address)?;
+                let mut texts = Vec::new();
+                for &field in &self.fields {
+                    for value in doc.get_all(field) {
+                        if let Value::Str(ref text) = *value {
+                            texts.push((field, text.clone()));
+                        }
+                    }
+                }
+                Ok(texts)
+            }
+        }
+    }
+
+    /// Returns the frequency of each of the terms of the source.
+    fn source_term_freqs(&self, searcher: &Searcher) -> Result<HashMap<Term, usize>> {
+        let schema = searcher.schema();
+        let mut term_freqs: HashMap<Term, usize> = HashMap::new();
+        for (field, text) in self.source_texts(searcher)? {
+            let field_entry = schema.get_field_entry(field);
+            let indexing_options_opt = match *field_entry.field_type() {
+                FieldType::Str(ref text_options) => text_options.get_indexing_options(),
+                _ => None,
+            };
+            let indexing_options = indexing_options_opt.ok_or_else(|| {
+                ErrorKind::SchemaError(format!(
+                    "Applied more like this query on field {:?}, which is not an indexed text field",
+                    field_entry.name()
+                ))
+            })?;
+            let tokenizer = self
+                .tokenizer_manager
+                .get(indexing_options.tokenizer())
+                .ok_or_else(|| {
+                    ErrorKind::SchemaError(format!(
+                        "Unknown tokenizer {:?} for field {:?}",
+                        indexing_options.tokenizer(),
+                        field_entry.name()
+                    ))
+                })?;
+            let min_term_len = self.min_term_len;
+            let mut token_stream = tokenizer.token_stream(&text);
+            token_stream.process(&mut |token| {
+                if token.text.chars().count() >= min_term_len {
+                    let term = Term::from_field_text(field, &token.text);
+                    *term_freqs.entry(term).or_insert(0) += 1;
+                }
+            });
+        }
+        Ok(term_freqs)
+    }
+
+    /// Selects the terms of the source with the highest tf-idf.
+    fn select_terms(&self, searcher: &Searcher) -> Result<Vec<Term>> {
+        let total_num_docs: u64 = searcher
+            .segment_readers()
+            .iter()
+            .map(|segment_reader| segment_reader.max_doc() as u64)
+            .sum();
+        let mut scored_terms: Vec<(Score, Term)> = self
+            .source_term_freqs(searcher)?
+            .into_iter()
+            .filter_map(|(term, term_freq)| {
+                let doc_freq = searcher.doc_freq(&term);
+                if doc_freq == 0 || doc_freq < self.min_doc_freq {
+                    return None;
+                }
+                if let Some(max_doc_freq) = self.max_doc_freq {
+                    if doc_freq > max_doc_freq {
+                        return None;
+                    }
+                }
+                let score = term_freq as Score * idf(doc_freq, total_num_docs);
+                Some((score, term))
+            })
+            .collect();
+        // ties are broken by term, so that the selected terms are deterministic.
+        scored_terms.sort_by(|left, right| {
+            right
+                .0
+                .partial_cmp(&left.0)
+                .unwrap_or(Ordering::Equal)
+                .then_with(|| left.1.cmp(&right.1))
+        });
+        Ok(scored_terms
+            .into_iter()
+            .take(self.max_query_terms)
+            .map(|(_, term)| term)
+            .collect())
+    }
+}
+
+impl fmt::Debug for MoreLikeThisQuery {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter
+            .debug_struct("MoreLikeThisQuery")
+            .field("source", &self.source)
+            .field("fields", &self.fields)
+            .field("min_doc_freq", &self.min_doc_freq)
+            .field("max_doc_freq", &self.max_doc_freq)
+            .field("min_term_len", &self.min_term_len)
+            .field("max_query_terms", &self.max_query_terms)
+            .finish()
+    }
+}
+
+impl Query for MoreLikeThisQuery {
+    fn weight(&self, searcher: &Searcher, scoring_enabled: bool) -> Result<Box<Weight>> {
+        let terms = self.select_terms(searcher)?;
+        BooleanQuery::new_multiterms_query(terms).weight(searcher, scoring_enabled)
+    }
+
+    fn rewrite(self: Box<Self>, searcher: &Searcher) -> Result<Box<Query>> {
+        let terms = self.select_terms(searcher)?;
+        Ok(Box::new(BooleanQuery::new_multiterms_query(terms)))
+    }
+
+    fn to_dsl(&self, schema: &Schema) -> Result<QueryDsl> {
+        let (text, document) = match self.source {
+            MoreLikeThisSource::Text(ref text) => (Some(text.clone()), None),
+            MoreLikeThisSource::Document(doc_address) => (None, Some(doc_address)),
+        };
+        Ok(QueryDsl::MoreLikeThis {
+            fields: self
+                .fields
+                .iter()
+                .map(|&field| field_name(schema, field))
+                .collect(),
+            text,
+            document,
+            min_doc_freq: if self.min_doc_freq == 1 {
+                None
+            } else {
+                Some(self.min_doc_freq)
+            },
+            max_doc_freq: self.max_doc_freq,
+            min_term_len: self.min_term_len,
+            max_query_terms: if self.max_query_terms == DEFAULT_MAX_QUERY_TERMS {
+                None
+            } else {
+                Some(self.max_query_terms)
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::MoreLikeThisQuery;
+    use collector::tests::matching_docs;
+    use error::ErrorKind;
+    use query::Query;
+    use schema::{SchemaBuilder, INT_INDEXED, STORED, TEXT};
+    use DocAddress;
+    use Index;
+
+    #[test]
+    fn test_more_like_this_query() {
+        let mut schema_builder = SchemaBuilder::default();
+        let title = schema_builder.add_text_field("title", TEXT | STORED);
+        let body = schema_builder.add_text_field("body", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 40_000_000).unwrap();
+            index_writer.add_document(doc!(title => "rust borrow checker", body => "ownership"));
+            index_writer.add_document(doc!(title => "the rust compiler", body => "llvm"));
+            index_writer.add_document(doc!(title => "borrow a checker board", body => "rust"));
+            index_writer.add_document(doc!(title => "the garden", body => "flowers"));
+            index_writer.add_document(doc!(title => "the sea", body => "water"));
+            index_writer.commit().unwrap();
+        }
+        index.load_searchers().unwrap();
+        let searcher = index.searcher();
+        {
+            let query = MoreLikeThisQuery::for_document(&index, DocAddress(0, 0), vec![title]);
+            assert_eq!(matching_docs(&searcher, &query), vec![0, 1, 2]);
+        }
+        {
+            let mut query = MoreLikeThisQuery::for_document(&index, DocAddress(0, 0), vec![title]);
+            query.set_max_query_terms(1);
+            assert_eq!(matching_docs(&searcher, &query).len(), 2);
+        }
+        {
+            let mut query =
+                MoreLikeThisQuery::for_text(&index, "the rust garden", vec![title, body]);
+            assert_eq!(matching_docs(&searcher, &query), vec![0, 1, 2, 3, 4]);
+            query.set_max_doc_freq(2);
+            assert_eq!(matching_docs(&searcher, &query), vec![0, 1, 2, 3]);
+            query.set_min_doc_freq(2);
+            assert_eq!(matching_docs(&searcher, &query), vec![0, 1]);
+            query.set_min_term_len(5);
+            assert!(matching_docs(&searcher, &query).is_empty());
+        }
+        {
+            // body is not stored
+            let query = MoreLikeThisQuery::for_document(&index, DocAddress(0, 0), vec![body]);
+            assert!(matching_docs(&searcher, &query).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_more_like_this_query_non_text_field() {
+        let mut schema_builder = SchemaBuilder::default();
+        let count = schema_builder.add_u64_field("count", INT_INDEXED);
+        let index = Index::create_in_ram(schema_builder.build());
+        index.load_searchers().unwrap();
+        let query = MoreLikeThisQuery::for_text(&index, "12", vec![count]);
+        let err = query.weight(&*index.searcher(), true).err().unwrap();
+        assert_matches!(err.kind(), &ErrorKind::SchemaError(_));
+    }
+}
@@ -1,5 +1,5 @@
 /// Defines whether a term in a query must be present,
-/// should be present or must not be present.
+/// should be present or must not be present, or acts as a filter.
 #[derive(Debug, Clone, Hash, Copy, Eq, PartialEq)]
 pub enum Occur {
     /// For a given document to be considered for scoring,
@@ -11,4 +11,7 @@ pub enum Occur {
     /// Document that contain the term are excluded from the
     /// search.
     MustNot,
+    /// Document without the term are excluded from the search,
+    /// like for `Must`, but the term does not take part in scoring.
+    Filter,
 }
@@ -1,10 +1,18 @@
+mod multi_phrase_query;
+mod multi_phrase_weight;
+mod phrase_prefix_query;
 mod phrase_query;
 mod phrase_scorer;
 mod phrase_weight;
+mod union_postings;
 
+pub use self::multi_phrase_query::MultiPhraseQuery;
+pub use self::multi_phrase_weight::MultiPhraseWeight;
+pub use self::phrase_prefix_query::PhrasePrefixQuery;
 pub use self::phrase_query::PhraseQuery;
 pub use self::phrase_scorer::PhraseScorer;
 pub use self::phrase_weight::PhraseWeight;
+pub use self::union_postings::UnionPostings;
 
 #[cfg(test)]
 mod tests {
@@ -13,7 +21,8 @@ mod tests {
     use collector::tests::TestCollector;
     use core::Index;
     use error::ErrorKind;
-    use schema::{SchemaBuilder, Term, TEXT};
+    use query::{Query, QueryDsl};
+    use schema::{SchemaBuilder, Term, INT_INDEXED, STRING, TEXT};
     use tests::assert_nearly_equals;
 
     fn create_index(texts: &[&'static str]) -> Index {
@@ -130,6 +139,43 @@ mod tests {
         assert_nearly_equals(scores[1], 0.46844664);
     }
 
+    #[test]
+    pub fn test_phrase_query_slop() {
+        let index = create_index(&[
+            "quick fox",
+            "quick brown fox",
+            "quick brown lazy fox",
+            "fox quick",
+            "quick",
+        ]);
+        let schema = index.schema();
+        let text_field = schema.get_field("text").unwrap();
+        let searcher = index.searcher();
+        let test_query = |texts: Vec<&str>, slop: u32| {
+            let mut test_collector = TestCollector::default();
+            let terms: Vec<Term> = texts
+                .iter()
+                .map(|text| Term::from_field_text(text_field, text))
+                .collect();
+            let mut phrase_query = PhraseQuery::new(terms);
+            phrase_query.set_slop(slop);
+            searcher
+                .search(&phrase_query, &mut test_collector)
+                .expect("search should succeed");
+            test_collector
+        };
+        assert_eq!(test_query(vec!["quick", "fox"], 0).docs(), vec![0]);
+        assert_eq!(test_query(vec!["quick", "fox"], 1).docs(), vec![0, 1]);
+        // transposing the terms costs 2 moves.
+        assert_eq!(test_query(vec!["quick", "fox"], 2).docs(), vec![0, 1, 2, 3]);
+        assert_eq!(test_query(vec!["fox", "quick"], 1).docs(), vec![3]);
+        assert_eq!(test_query(vec!["fox", "quick"], 2).docs(), vec![0, 3]);
+        assert_eq!(test_query(vec!["quick", "quick"], 2).docs(), Vec::<u32>::new());
+        let scores = test_query(vec!["quick", "fox"], 2).scores();
+        assert!(scores[0] > scores[1]);
+        assert!(scores[1] > scores[2]);
+    }
+
     #[test] // motivated by #234
     pub fn test_phrase_query_docfreq_order() {
         let mut schema_builder = SchemaBuilder::default();
@@ -173,4 +219,201 @@ mod tests {
         assert_eq!(test_query(vec!["a", "b"]), vec![1]);
         assert_eq!(test_query(vec!["b", "a"]), vec![2]);
     }
+
+    #[test]
+    pub fn test_multi_phrase_query() {
+        let index = create_index(&[
+            "i love new york city",
+            "i love ny city",
+            "new city of york",
+            "york new city",
+            "ny new",
+            "new new york",
+        ]);
+        let schema = index.schema();
+        let text_field = schema.get_field("text").unwrap();
+        let searcher = index.searcher();
+        let test_query = |texts: Vec<Vec<&str>>, slop: u32| {
+            let mut test_collector = TestCollector::default();
+            let terms: Vec<Vec<Term>> = texts
+                .iter()
+                .map(|alternatives| {
+                    alternatives
+                        .iter()
+                        .map(|text| Term::from_field_text(text_field, text))
+                        .collect()
+                })
+                .collect();
+            let mut multi_phrase_query = MultiPhraseQuery::new(terms);
+            multi_phrase_query.set_slop(slop);
+            searcher
+                .search(&multi_phrase_query, &mut test_collector)
+                .expect("search should succeed");
+            test_collector
+        };
+        assert_eq!(
+            test_query(vec![vec!["new", "ny"], vec!["york", "city"]], 0).docs(),
+            vec![0, 1, 2, 3, 5]
+        );
+        assert_eq!(
+            test_query(vec![vec!["love"], vec!["new", "ny"], vec!["city"]], 0).docs(),
+            vec![1]
+        );
+        assert_eq!(
+            test_query(vec![vec!["love"], vec!["new", "ny"], vec!["city"]], 1).docs(),
+            vec![0, 1]
+        );
+        assert_eq!(
+            test_query(vec![vec!["new", "ny"], vec!["new"]], 0).docs(),
+            vec![4, 5]
+        );
+        assert!(test_query(vec![vec!["new", "ny"], vec!["missing"]], 0)
+            .docs()
+            .is_empty());
+        let single_terms_scores = test_query(vec![vec!["new"], vec!["york"]], 0).scores();
+        let phrase_scores = {
+            let mut test_collector = TestCollector::default();
+            let phrase_query = PhraseQuery::new(vec![
+                Term::from_field_text(text_field, "new"),
+                Term::from_field_text(text_field, "york"),
+            ]);
+            searcher.search(&phrase_query, &mut test_collector).unwrap();
+            test_collector.scores()
+        };
+        assert_eq!(single_terms_scores.len(), phrase_scores.len());
+        for (multi_phrase_score, phrase_score) in single_terms_scores.iter().zip(&phrase_scores) {
+            assert_nearly_equals(*phrase_score, *multi_phrase_score);
+        }
+    }
+
+    #[test]
+    pub fn test_phrase_prefix_query() {
+        let index = create_index(&[
+            "rust programming",
+            "rust programs are fast",
+            "rust is a programming language",
+            "python programming",
+            "rust pro",
+            "rust progress",
+        ]);
+        let schema = index.schema();
+        let text_field = schema.get_field("text").unwrap();
+        let searcher = index.searcher();
+        let phrase_prefix_query = |texts: &[&str], slop: u32, max_expansions: usize| {
+            let terms: Vec<Term> = texts
+                .iter()
+                .map(|text| Term::from_field_text(text_field, text))
+                .collect();
+            let mut phrase_prefix_query = PhrasePrefixQuery::new(terms);
+            phrase_prefix_query.set_slop(slop);
+            phrase_prefix_query.set_max_expansions(max_expansions);
+            phrase_prefix_query
+        };
+        let test_query = |query: &Query| {
+            let mut test_collector = TestCollector::default();
+            searcher
+                .search(query, &mut test_collector)
+                .expect("search should succeed");
+            test_collector
+        };
+        assert_eq!(
+            test_query(&phrase_prefix_query(&["rust", "prog"], 0, 50)).docs(),
+            vec![0, 1, 5]
+        );
+        assert_eq!(
+            test_query(&phrase_prefix_query(&["rust", "prog"], 2, 50)).docs(),
+            vec![0, 1, 2, 5]
+        );
+        assert_eq!(
+            test_query(&phrase_prefix_query(&["rust", "prog"], 0, 2)).docs(),
+            vec![0, 1]
+        );
+        assert_eq!(
+            test_query(&phrase_prefix_query(&["rust", "programming"], 0, 50)).docs(),
+            vec![0]
+        );
+        assert!(test_query(&phrase_prefix_query(&["rust", "lang"], 0, 50))
+            .docs()
+            .is_empty());
+        assert!(test_query(&phrase_prefix_query(&["rust", "prog"], 0, 0))
+            .docs()
+            .is_empty());
+
+        let phrase_prefix_scores =
+            test_query(&phrase_prefix_query(&["rust", "prog"], 0, 2)).scores();
+        let multi_phrase_query = MultiPhraseQuery::new(vec![
+            vec![Term::from_field_text(text_field, "rust")],
+            vec![
+                Term::from_field_text(text_field, "programming"),
+                Term::from_field_text(text_field, "programs"),
+            ],
+        ]);
+        let multi_phrase_scores = test_query(&multi_phrase_query).scores();
+        assert_eq!(phrase_prefix_scores.len(), multi_phrase_scores.len());
+        for (phrase_prefix_score, multi_phrase_score) in
+            phrase_prefix_scores.iter().zip(&multi_phrase_scores)
+        {
+            assert_nearly_equals(*phrase_prefix_score, *multi_phrase_score);
+        }
+
+        let rewritten_query = Box::new(phrase_prefix_query(&["rust", "prog"], 0, 2))
+            .rewrite(&*searcher)
+            .unwrap();
+        let expected_dsl = QueryDsl::from_json(
+            r#"{
+                "multi_phrase": {
+                    "field": "text",
+                    "terms": [["rust"], ["programming", "programs"]]
+                }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(rewritten_query.to_dsl(&schema).unwrap(), expected_dsl);
+        assert_eq!(test_query(&*rewritten_query).docs(), vec![0, 1]);
+    }
+
+    #[test]
+    pub fn test_phrase_prefix_query_no_positions() {
+        let mut schema_builder = SchemaBuilder::default();
+        let text_field = schema_builder.add_text_field("text", STRING);
+        let schema = schema_builder.build();
+        let index = Index::create_in_ram(schema);
+        index.load_searchers().unwrap();
+        let searcher = index.searcher();
+        let phrase_prefix_query = PhrasePrefixQuery::new(vec![
+            Term::from_field_text(text_field, "a"),
+            Term::from_field_text(text_field, "b"),
+        ]);
+        let err = phrase_prefix_query.weight(&*searcher, false).err().unwrap();
+        assert_matches!(err.kind(), &ErrorKind::SchemaError(_));
+    }
+
+    #[test]
+    pub fn test_phrase_prefix_query_to_dsl_not_text_field() {
+        let mut schema_builder = SchemaBuilder::default();
+        let int_field = schema_builder.add_u64_field("int", INT_INDEXED);
+        let schema = schema_builder.build();
+        let phrase_prefix_query = PhrasePrefixQuery::new(vec![
+            Term::from_field_u64(int_field, 1u64),
+            Term::from_field_u64(int_field, 2u64),
+        ]);
+        let err = phrase_prefix_query.to_dsl(&schema).err().unwrap();
+        assert_matches!(err.kind(), &ErrorKind::SchemaError(_));
+    }
+
+    #[test]
+    pub fn test_multi_phrase_query_no_positions() {
+        let mut schema_builder = SchemaBuilder::default();
+        let text_field = schema_builder.add_text_field("text", STRING);
+        let schema = schema_builder.build();
+        let index = Index::create_in_ram(schema);
+        index.load_searchers().unwrap();
+        let searcher = index.searcher();
+        let multi_phrase_query = MultiPhraseQuery::new(vec![
+            vec![Term::from_field_text(text_field, "a")],
+            vec![Term::from_field_text(text_field, "b")],
+        ]);
+        let err = multi_phrase_query.weight(&*searcher, false).err().unwrap();
+        assert_matches!(err.kind(), &ErrorKind::SchemaError(_));
+    }
 }
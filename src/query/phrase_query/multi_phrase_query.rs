@@ -0,0 +1,124 @@
+use super::MultiPhraseWeight;
+use core::searcher::Searcher;
+use error::ErrorKind;
+use query::bm25::BM25Weight;
+use query::query_dsl::{field_name, term_value_to_json};
+use query::Query;
+use query::QueryDsl;
+use query::Weight;
+use schema::{Field, Schema, Term};
+use std::collections::BTreeSet;
+use Result;
+
+/// `MultiPhraseQuery` matches a sequence of words, in which
+/// each position of the phrase accepts a set of alternative terms.
+///
+/// For instance, the multi phrase query for `["new", "ny"], ["york", "city"]`
+/// matches `new york`, `ny york`, `new city` and `ny city`.
+/// This is typically useful to search for phrases with synonyms.
+///
+/// Using a `MultiPhraseQuery` on a field requires positions
+/// to be indexed for this field.
+///
+/// Like for the [`PhraseQuery`](./struct.PhraseQuery.html), a slop can
+/// be set to also match documents in which the terms are not
+/// exactly consecutive.
+#[derive(Debug)]
+pub struct MultiPhraseQuery {
+    field: Field,
+    phrase_terms: Vec<Vec<Term>>,
+    slop: u32,
+}
+
+impl MultiPhraseQuery {
+    /// Creates a new `MultiPhraseQuery` given the list of
+    /// the alternative terms for each position of the phrase.
+    ///
+    /// There must be at least two positions, each of them having at least
+    /// one term, and all terms must belong to the same field.
+    pub fn new(terms: Vec<Vec<Term>>) -> MultiPhraseQuery {
+        assert!(
+            terms.len() > 1,
+            "A multi phrase query is required to have strictly more than one position."
+        );
+        assert!(
+            terms.iter().all(|alternatives| !alternatives.is_empty()),
+            "All positions of a multi phrase query must have at least one term."
+        );
+        let field = terms[0][0].field();
+        assert!(
+            terms
+                .iter()
+                .flat_map(|alternatives| alternatives)
+                .all(|term| term.field() == field),
+            "All terms from a multi phrase query must belong to the same field"
+        );
+        MultiPhraseQuery {
+            field,
+            phrase_terms: terms,
+            slop: 0,
+        }
+    }
+
+    /// Sets the slop of the phrase query, that is the maximum number
+    /// of moves allowed to match the terms of the phrase.
+    ///
+    /// A slop of `0` (the default) only matches exact phrases.
+    pub fn set_slop(&mut self, slop: u32) {
+        self.slop = slop;
+    }
+}
+
+impl Query for MultiPhraseQuery {
+    fn weight(&self, searcher: &Searcher, scoring_enabled: bool) -> Result<Box<Weight>> {
+        let schema = searcher.schema();
+        let field_entry = schema.get_field_entry(self.field);
+        let has_positions = field_entry
+            .field_type()
+            .get_index_record_option()
+            .map(|index_record_option| index_record_option.has_positions())
+            .unwrap_or(false);
+        if !has_positions {
+            let field_name = field_entry.name();
+            bail!(ErrorKind::SchemaError(format!(
+                "Applied phrase query on field {:?}, which does not have positions indexed",
+                field_name
+            )))
+        }
+        let terms = self.phrase_terms.clone();
+        let bm25_weight = if scoring_enabled {
+            BM25Weight::for_term_groups(searcher, &terms)
+        } else {
+            BM25Weight::null()
+        };
+        Ok(Box::new(MultiPhraseWeight::new(
+            terms,
+            bm25_weight,
+            scoring_enabled,
+            self.slop,
+        )))
+    }
+
+    fn query_terms(&self, term_set: &mut BTreeSet<Term>) {
+        for alternatives in &self.phrase_terms {
+            term_set.extend(alternatives.iter().cloned());
+        }
+    }
+
+    fn to_dsl(&self, schema: &Schema) -> Result<QueryDsl> {
+        Ok(QueryDsl::MultiPhrase {
+            field: field_name(schema, self.field),
+            terms: self
+                .phrase_terms
+                .iter()
+                .map(|alternatives| {
+                    alternatives
+                        .iter()
+                        .map(|term| term_value_to_json(schema, term))
+                        .collect()
+                })
+                .collect(),
+            slop: self.slop,
+        })
+    }
+}
@@ -0,0 +1,109 @@
+use super::{PhraseScorer, UnionPostings};
+use core::SegmentReader;
+use docset::{DocSet, SkipResult};
+use postings::SegmentPostings;
+use query::bm25::BM25Weight;
+use query::explanation::{does_not_match, term_description};
+use query::EmptyScorer;
+use query::Explanation;
+use query::Scorer;
+use query::Weight;
+use schema::IndexRecordOption;
+use schema::Term;
+use DocId;
+use Result;
+
+pub struct MultiPhraseWeight {
+    phrase_terms: Vec<Vec<Term>>,
+    similarity_weight: BM25Weight,
+    score_needed: bool,
+    slop: u32,
+}
+
+impl MultiPhraseWeight {
+    /// Creates a new multi phrase weight.
+    pub fn new(
+        phrase_terms: Vec<Vec<Term>>,
+        similarity_weight: BM25Weight,
+        score_needed: bool,
+        slop: u32,
+    ) -> MultiPhraseWeight {
+        MultiPhraseWeight {
+            phrase_terms,
+            similarity_weight,
+            score_needed,
+            slop,
+        }
+    }
+
+    /// Returns the phrase scorer, or `None` if none of the
+    /// alternative terms of a position appear in the segment.
+    fn phrase_scorer(
+        &self,
+        reader: &SegmentReader,
+    ) -> Option<PhraseScorer<UnionPostings<SegmentPostings>>> {
+        let similarity_weight = self.similarity_weight.clone();
+        let field = self.phrase_terms[0][0].field();
+        let fieldnorm_reader = reader.get_fieldnorms_reader(field);
+        let inverted_index = reader.inverted_index(field);
+        let mut union_postings_list = Vec::new();
+        for alternatives in &self.phrase_terms {
+            let postings_list: Vec<_> = alternatives
+                .iter()
+                .flat_map(|term| {
+                    inverted_index.read_postings(term, IndexRecordOption::WithFreqsAndPositions)
+                })
+                .collect();
+            if postings_list.is_empty() {
+                return None;
+            }
+            union_postings_list.push(UnionPostings::new(postings_list));
+        }
+        Some(PhraseScorer::new(
+            union_postings_list,
+            similarity_weight,
+            fieldnorm_reader,
+            self.score_needed,
+            self.slop,
+        ))
+    }
+}
+
+impl Weight for MultiPhraseWeight {
+    fn scorer(&self, reader: &SegmentReader) -> Result<Box<Scorer>> {
+        if let Some(phrase_scorer) = self.phrase_scorer(reader) {
+            Ok(Box::new(phrase_scorer))
+        } else {
+            Ok(Box::new(EmptyScorer))
+        }
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> Result<Explanation> {
+        let mut phrase_scorer = self.phrase_scorer(reader)
+            .ok_or_else(|| does_not_match(doc))?;
+        if phrase_scorer.skip_next(doc) != SkipResult::Reached {
+            return Err(does_not_match(doc));
+        }
+        let positions = self.phrase_terms
+            .iter()
+            .map(|alternatives| {
+                let terms = alternatives
+                    .iter()
+                    .map(|term| term_description(reader.schema(), term))
+                    .collect::<Vec<String>>();
+                format!("({})", terms.join(" | "))
+            })
+            .collect::<Vec<String>>();
+        let similarity_explanation = phrase_scorer.explain();
+        let mut explanation = Explanation::new(
+            format!(
+                "MultiPhraseQuery({}) with a slop of {}, result of:",
+                positions.join(" "),
+                self.slop
+            ),
+            similarity_explanation.value(),
+        );
+        explanation.add_detail(similarity_explanation);
+        Ok(explanation)
+    }
+}
@@ -0,0 +1,203 @@
+use super::MultiPhraseWeight;
+use core::searcher::Searcher;
+use error::ErrorKind;
+use query::bm25::BM25Weight;
+use query::prefix_query::prefix_range;
+use query::query_dsl::{field_name, term_value_to_json};
+use query::MultiPhraseQuery;
+use query::Query;
+use query::QueryDsl;
+use query::Weight;
+use schema::{Field, FieldType, Schema, Term};
+use std::collections::BTreeSet;
+use Result;
+
+/// Default maximum number of terms the prefix of a
+/// `PhrasePrefixQuery` expands to.
+const DEFAULT_MAX_PHRASE_PREFIX_EXPANSIONS: usize = 50;
+
+/// `PhrasePrefixQuery` matches a sequence of words, the last
+/// of which is only a prefix.
+///
+/// For instance, the phrase prefix query for `"rust prog"` matches
+/// `rust programming` as well as `rust programs`.
+/// This is typically useful for search-as-you-type, where the
+/// last word of the input may not be complete yet.
+///
+/// Using a `PhrasePrefixQuery` on a field requires positions
+/// to be indexed for this field.
+///
+/// Like for the [`PhraseQuery`](./struct.PhraseQuery.html), a slop can
+/// be set to also match documents in which the terms are not
+/// exactly consecutive.
+///
+/// # Implementation
+///
+/// The prefix is expanded, over all of the segments, to the first terms
+/// of the term dictionary starting by it. The number of expansions is capped
+/// (50 by default. See [`.set_max_expansions(...)`](#method.set_max_expansions)).
+/// Unlike for the `PrefixQuery`, exceeding the cap is not an error:
+/// the remaining terms are simply ignored.
+///
+/// The query is then evaluated as a
+/// [`MultiPhraseQuery`](./struct.MultiPhraseQuery.html), the last
+/// position of which accepts any of the expanded terms.
+#[derive(Debug)]
+pub struct PhrasePrefixQuery {
+    field: Field,
+    phrase_terms: Vec<Term>,
+    prefix: Term,
+    max_expansions: usize,
+    slop: u32,
+}
+
+impl PhrasePrefixQuery {
+    /// Creates a new `PhrasePrefixQuery` given a list of terms.
+    ///
+    /// The last term is used as a prefix.
+    /// There must be at least two terms, and all terms
+    /// must belong to the same field.
+    pub fn new(mut terms: Vec<Term>) -> PhrasePrefixQuery {
+        assert!(
+            terms.len() > 1,
+            "A phrase prefix query is required to have strictly more than one term."
+        );
+        let field = terms[0].field();
+        assert!(
+            terms[1..].iter().all(|term| term.field() == field),
+            "All terms from a phrase prefix query must belong to the same field"
+        );
+        let prefix = terms.pop().unwrap();
+        PhrasePrefixQuery {
+            field,
+            phrase_terms: terms,
+            prefix,
+            max_expansions: DEFAULT_MAX_PHRASE_PREFIX_EXPANSIONS,
+            slop: 0,
+        }
+    }
+
+    /// Sets the maximum number of terms the prefix expands to.
+    pub fn set_max_expansions(&mut self, max_expansions: usize) {
+        self.max_expansions = max_expansions;
+    }
+
+    /// Sets the slop of the phrase prefix query, that is the maximum number
+    /// of moves allowed to match the terms of the phrase.
+    ///
+    /// A slop of `0` (the default) only matches exact phrases.
+    pub fn set_slop(&mut self, slop: u32) {
+        self.slop = slop;
+    }
+
+    /// Returns the first `max_expansions` terms of the index
+    /// starting by the prefix, in the order of the term dictionary.
+    fn prefix_expansions(&self, searcher: &Searcher) -> Vec<Term> {
+        let mut expansions = BTreeSet::new();
+        for reader in searcher.segment_readers() {
+            let inverted_index = reader.inverted_index(self.field);
+            let mut term_stream = prefix_range(inverted_index.terms(), self.prefix.value_bytes());
+            // the first `max_expansions` terms of the searcher are
+            // among the first `max_expansions` terms of each segment.
+            let mut num_expansions = 0;
+            while num_expansions < self.max_expansions && term_stream.advance() {
+                let mut term = Term::for_field(self.field);
+                term.set_bytes(term_stream.key());
+                expansions.insert(term);
+                num_expansions += 1;
+            }
+        }
+        expansions.into_iter().take(self.max_expansions).collect()
+    }
+
+    /// Returns the alternative terms for each position of the phrase.
+    fn term_groups(&self, searcher: &Searcher) -> Vec<Vec<Term>> {
+        let mut term_groups: Vec<Vec<Term>> = self
+            .phrase_terms
+            .iter()
+            .map(|term| vec![term.clone()])
+            .collect();
+        term_groups.push(self.prefix_expansions(searcher));
+        term_groups
+    }
+}
+
+impl Query for PhrasePrefixQuery {
+    fn weight(&self, searcher: &Searcher, scoring_enabled: bool) -> Result<Box<Weight>> {
+        let schema = searcher.schema();
+        let field_entry = schema.get_field_entry(self.field);
+        let has_positions = field_entry
+            .field_type()
+            .get_index_record_option()
+            .map(|index_record_option| index_record_option.has_positions())
+            .unwrap_or(false);
+        if !has_positions {
+            let field_name = field_entry.name();
+            bail!(ErrorKind::SchemaError(format!(
+                "Applied phrase query on field {:?}, which does not have positions indexed",
+                field_name
+            )))
+        }
+        // if the prefix does not expand to any term, the last position
+        // has no alternative, and the weight does not match any document.
+        let terms = self.term_groups(searcher);
+        let bm25_weight = if scoring_enabled {
+            BM25Weight::for_term_groups(searcher, &terms)
+        } else {
+            BM25Weight::null()
+        };
+        Ok(Box::new(MultiPhraseWeight::new(
+            terms,
+            bm25_weight,
+            scoring_enabled,
+            self.slop,
+        )))
+    }
+
+    /// Rewrites the query into the `MultiPhraseQuery` over the
+    /// expanded terms.
+    ///
+    /// The query is left unchanged if the prefix does not
+    /// expand to any term.
+    fn rewrite(self: Box<Self>, searcher: &Searcher) -> Result<Box<Query>> {
+        let terms = self.term_groups(searcher);
+        if terms.iter().any(|alternatives| alternatives.is_empty()) {
+            return Ok(self);
+        }
+        let mut multi_phrase_query = MultiPhraseQuery::new(terms);
+        multi_phrase_query.set_slop(self.slop);
+        Ok(Box::new(multi_phrase_query))
+    }
+
+    fn query_terms(&self, term_set: &mut BTreeSet<Term>) {
+        term_set.extend(self.phrase_terms.iter().cloned());
+    }
+
+    fn to_dsl(&self, schema: &Schema) -> Result<QueryDsl> {
+        let field_entry = schema.get_field_entry(self.field);
+        match *field_entry.field_type() {
+            FieldType::Str(_) => {}
+            _ => bail!(ErrorKind::SchemaError(format!(
+                "Phrase prefix query on field {:?}, which is not a text field, cannot be \
+                 converted to the query DSL",
+                field_entry.name()
+            ))),
+        }
+        let max_expansions = if self.max_expansions == DEFAULT_MAX_PHRASE_PREFIX_EXPANSIONS {
+            None
+        } else {
+            Some(self.max_expansions)
+        };
+        Ok(QueryDsl::PhrasePrefix {
+            field: field_name(schema, self.field),
+            terms: self
+                .phrase_terms
+                .iter()
+                .map(|term| term_value_to_json(schema, term))
+                .collect(),
+            prefix: self.prefix.text().to_string(),
+            slop: self.slop,
+            max_expansions,
+        })
+    }
+}
@@ -2,9 +2,12 @@ use super::PhraseWeight;
 use core::searcher::Searcher;
 use error::ErrorKind;
 use query::bm25::BM25Weight;
+use query::query_dsl::{field_name, term_value_to_json};
 use query::Query;
+use query::QueryDsl;
 use query::Weight;
-use schema::{Field, Term};
+use schema::{Field, Schema, Term};
+use std::collections::BTreeSet;
 use Result;
 
 /// `PhraseQuery` matches a specific sequence of words.
@@ -21,10 +24,23 @@ use Result;
 /// Using a `PhraseQuery` on a field requires positions
 /// to be indexed for this field.
 ///
+/// # Slop
+///
+/// By default, the terms must appear consecutively and in order.
+/// A slop can be set (see [`.set_slop(...)`](#method.set_slop)) to also
+/// match documents in which the terms are at most `slop` moves away
+/// from their expected positions. For instance, with a slop of `1`,
+/// `"part job"` also matches the first sentence above.
+/// Moving a term over another one costs `2` moves,
+/// so that `"time part"` requires a slop of `2`.
+///
+/// Sloppy matches contribute less to the score than exact matches:
+/// each match counts for `1 / (1 + distance)`.
 #[derive(Debug)]
 pub struct PhraseQuery {
     field: Field,
     phrase_terms: Vec<Term>,
+    slop: u32,
 }
 
 impl PhraseQuery {
@@ -45,8 +61,17 @@ impl PhraseQuery {
         PhraseQuery {
             field,
             phrase_terms: terms,
+            slop: 0,
         }
     }
+
+    /// Sets the slop of the phrase query, that is the maximum number
+    /// of moves allowed to match the terms of the phrase.
+    ///
+    /// A slop of `0` (the default) only matches exact phrases.
+    pub fn set_slop(&mut self, slop: u32) {
+        self.slop = slop;
+    }
 }
 
 impl Query for PhraseQuery {
@@ -71,13 +96,35 @@ impl Query for PhraseQuery {
         let terms = self.phrase_terms.clone();
         if scoring_enabled {
             let bm25_weight = BM25Weight::for_terms(searcher, &terms);
-            Ok(Box::new(PhraseWeight::new(terms, bm25_weight, true)))
+            Ok(Box::new(PhraseWeight::new(
+                terms,
+                bm25_weight,
+                true,
+                self.slop,
+            )))
         } else {
             Ok(Box::new(PhraseWeight::new(
                 terms,
                 BM25Weight::null(),
                 false,
+                self.slop,
             )))
         }
     }
+
+    fn query_terms(&self, term_set: &mut BTreeSet<Term>) {
+        term_set.extend(self.phrase_terms.iter().cloned());
+    }
+
+    fn to_dsl(&self, schema: &Schema) -> Result<QueryDsl> {
+        Ok(QueryDsl::Phrase {
+            field: field_name(schema, self.field),
+            terms: self
+                .phrase_terms
+                .iter()
+                .map(|term| term_value_to_json(schema, term))
+                .collect(),
+            slop: self.slop,
+        })
+    }
 }
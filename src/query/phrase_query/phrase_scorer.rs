@@ -2,7 +2,7 @@ use docset::{DocSet, SkipResult};
 use fieldnorm::FieldNormReader;
 use postings::Postings;
 use query::bm25::BM25Weight;
-use query::{Intersection, Scorer};
+use query::{Explanation, Intersection, Scorer};
 use DocId;
 
 struct PostingsWithOffset<TPostings> {
@@ -41,12 +41,21 @@ impl<TPostings: Postings> DocSet for PostingsWithOffset<TPostings> {
     }
 }
 
+/// `Scorer` matching the documents in which the positions of
+/// a sequence of `Postings` are consecutive.
+///
+/// Each element of the sequence is typically the `SegmentPostings`
+/// of a term, or a `UnionPostings` merging the positions of
+/// several alternative terms.
 pub struct PhraseScorer<TPostings: Postings> {
     intersection_docset: Intersection<PostingsWithOffset<TPostings>, PostingsWithOffset<TPostings>>,
     num_docsets: usize,
     left: Vec<u32>,
     right: Vec<u32>,
-    phrase_count: u32,
+    term_positions: Vec<Vec<u32>>,
+    term_offsets: Vec<u32>,
+    slop: u32,
+    phrase_freq: f32,
     fieldnorm_reader: FieldNormReader,
     similarity_weight: BM25Weight,
     score_needed: bool,
@@ -117,12 +126,62 @@ fn intersection(left: &mut [u32], right: &[u32]) -> usize {
     count
 }
 
+/// Returns the sloppy frequency of a phrase, given the positions
+/// of each of its terms, shifted by their offset within the phrase,
+/// as well as these offsets.
+///
+/// The positions are swept in increasing order, by repeatedly advancing
+/// the term with the smallest position. Each time the positions of the
+/// different terms span a window of at most `slop`, a match is counted
+/// with a weight of `1 / (1 + distance)`, where `distance` is the width
+/// of the window. An exact match therefore counts for 1.
+///
+/// Windows where two terms of the phrase are matched on the
+/// same position of the document are ignored.
+fn sloppy_phrase_freq(term_positions: &[Vec<u32>], offsets: &[u32], slop: u32) -> f32 {
+    let num_terms = term_positions.len();
+    let mut cursors = vec![0usize; num_terms];
+    let mut doc_positions = vec![0u32; num_terms];
+    let mut freq = 0f32;
+    loop {
+        let mut min_ord = 0;
+        let mut min_position = u32::max_value();
+        let mut max_position = 0u32;
+        for (ord, positions) in term_positions.iter().enumerate() {
+            let position = match positions.get(cursors[ord]) {
+                Some(&position) => position,
+                None => {
+                    return freq;
+                }
+            };
+            if position < min_position {
+                min_position = position;
+                min_ord = ord;
+            }
+            if position > max_position {
+                max_position = position;
+            }
+            doc_positions[ord] = position - offsets[ord];
+        }
+        let distance = max_position - min_position;
+        if distance <= slop {
+            let has_overlap = (1..num_terms)
+                .any(|ord| doc_positions[..ord].contains(&doc_positions[ord]));
+            if !has_overlap {
+                freq += 1f32 / (1f32 + distance as f32);
+            }
+        }
+        cursors[min_ord] += 1;
+    }
+}
+
 impl<TPostings: Postings> PhraseScorer<TPostings> {
     pub fn new(
         term_postings: Vec<TPostings>,
         similarity_weight: BM25Weight,
         fieldnorm_reader: FieldNormReader,
         score_needed: bool,
+        slop: u32,
     ) -> PhraseScorer<TPostings> {
         let num_docsets = term_postings.len();
         let postings_with_offsets = term_postings
@@ -137,23 +196,46 @@ impl<TPostings: Postings> PhraseScorer<TPostings> {
             num_docsets,
             left: Vec::with_capacity(100),
             right: Vec::with_capacity(100),
-            phrase_count: 0u32,
+            term_positions: vec![Vec::new(); num_docsets],
+            term_offsets: vec![0u32; num_docsets],
+            slop,
+            phrase_freq: 0f32,
             similarity_weight,
             fieldnorm_reader,
             score_needed,
         }
     }
 
+    /// Explains the score of the current document.
+    pub fn explain(&self) -> Explanation {
+        let fieldnorm_id = self.fieldnorm_reader.fieldnorm_id(self.doc());
+        self.similarity_weight
+            .explain(fieldnorm_id, self.phrase_freq)
+    }
+
     fn phrase_match(&mut self) -> bool {
-        if self.score_needed {
+        if self.slop > 0 {
+            let freq = self.sloppy_phrase_freq();
+            self.phrase_freq = freq;
+            freq > 0f32
+        } else if self.score_needed {
             let count = self.phrase_count();
-            self.phrase_count = count;
+            self.phrase_freq = count as f32;
             count > 0u32
         } else {
             self.phrase_exists()
         }
     }
 
+    fn sloppy_phrase_freq(&mut self) -> f32 {
+        for (i, positions) in self.term_positions.iter_mut().enumerate() {
+            let postings = self.intersection_docset.docset_mut_specialized(i);
+            postings.positions(positions);
+            self.term_offsets[i] = postings.offset;
+        }
+        sloppy_phrase_freq(&self.term_positions[..], &self.term_offsets[..], self.slop)
+    }
+
     fn phrase_exists(&mut self) -> bool {
         {
             self.intersection_docset
@@ -247,14 +329,15 @@ impl<TPostings: Postings> Scorer for PhraseScorer<TPostings> {
         let doc = self.doc();
         let fieldnorm_id = self.fieldnorm_reader.fieldnorm_id(doc);
         self.similarity_weight
-            .score(fieldnorm_id, self.phrase_count)
+            .score_freq(fieldnorm_id, self.phrase_freq)
     }
 }
 
 #[cfg(test)]
 mod tests {
 
-    use super::{intersection, intersection_count};
+    use super::{intersection, intersection_count, sloppy_phrase_freq};
+    use tests::assert_nearly_equals;
 
     fn test_intersection_sym(left: &[u32], right: &[u32], expected: &[u32]) {
         test_intersection_aux(left, right, expected);
@@ -277,6 +360,28 @@ mod tests {
         test_intersection_sym(&[5, 7], &[1, 5, 10, 12], &[5]);
         test_intersection_sym(&[1, 5, 6, 9, 10, 12], &[6, 8, 9, 12], &[6, 9, 12]);
     }
+
+    #[test]
+    fn test_sloppy_phrase_freq() {
+        let offsets = [2u32, 1u32];
+        assert_eq!(sloppy_phrase_freq(&[vec![2], vec![1]], &offsets, 1), 0f32);
+        assert_nearly_equals(sloppy_phrase_freq(&[vec![2], vec![2]], &offsets, 1), 1f32);
+        assert_nearly_equals(sloppy_phrase_freq(&[vec![2], vec![3]], &offsets, 1), 0.5f32);
+        assert_eq!(sloppy_phrase_freq(&[vec![2], vec![4]], &offsets, 1), 0f32);
+        assert_nearly_equals(
+            sloppy_phrase_freq(&[vec![2], vec![4]], &offsets, 2),
+            1f32 / 3f32,
+        );
+        // transposed terms
+        assert_nearly_equals(
+            sloppy_phrase_freq(&[vec![3], vec![1]], &offsets, 2),
+            1f32 / 3f32,
+        );
+        assert_nearly_equals(
+            sloppy_phrase_freq(&[vec![2, 10], vec![2, 11]], &offsets, 1),
+            1.5f32,
+        );
+    }
 }
 
 #[cfg(all(test, feature = "unstable"))]
@@ -1,17 +1,23 @@
 use super::PhraseScorer;
 use core::SegmentReader;
+use docset::{DocSet, SkipResult};
+use postings::SegmentPostings;
 use query::bm25::BM25Weight;
+use query::explanation::{does_not_match, term_description};
 use query::EmptyScorer;
+use query::Explanation;
 use query::Scorer;
 use query::Weight;
 use schema::IndexRecordOption;
 use schema::Term;
+use DocId;
 use Result;
 
 pub struct PhraseWeight {
     phrase_terms: Vec<Term>,
     similarity_weight: BM25Weight,
     score_needed: bool,
+    slop: u32,
 }
 
 impl PhraseWeight {
@@ -20,56 +26,72 @@ impl PhraseWeight {
         phrase_terms: Vec<Term>,
         similarity_weight: BM25Weight,
         score_needed: bool,
+        slop: u32,
     ) -> PhraseWeight {
         PhraseWeight {
             phrase_terms,
             similarity_weight,
             score_needed,
+            slop,
         }
     }
-}
 
-impl Weight for PhraseWeight {
-    fn scorer(&self, reader: &SegmentReader) -> Result<Box<Scorer>> {
+    /// Returns the phrase scorer, or `None` if one
+    /// of the terms does not appear in the segment.
+    fn phrase_scorer(&self, reader: &SegmentReader) -> Option<PhraseScorer<SegmentPostings>> {
         let similarity_weight = self.similarity_weight.clone();
         let field = self.phrase_terms[0].field();
         let fieldnorm_reader = reader.get_fieldnorms_reader(field);
-        if reader.has_deletes() {
-            let mut term_postings_list = Vec::new();
-            for term in &self.phrase_terms {
-                if let Some(postings) = reader
-                    .inverted_index(term.field())
-                    .read_postings(term, IndexRecordOption::WithFreqsAndPositions)
-                {
-                    term_postings_list.push(postings);
-                } else {
-                    return Ok(Box::new(EmptyScorer));
-                }
-            }
-            Ok(Box::new(PhraseScorer::new(
-                term_postings_list,
-                similarity_weight,
-                fieldnorm_reader,
-                self.score_needed,
-            )))
-        } else {
-            let mut term_postings_list = Vec::new();
-            for term in &self.phrase_terms {
-                if let Some(postings) = reader
-                    .inverted_index(term.field())
+        let mut term_postings_list = Vec::new();
+        for term in &self.phrase_terms {
+            let inverted_index = reader.inverted_index(term.field());
+            let postings_opt = if reader.has_deletes() {
+                inverted_index.read_postings(term, IndexRecordOption::WithFreqsAndPositions)
+            } else {
+                inverted_index
                     .read_postings_no_deletes(term, IndexRecordOption::WithFreqsAndPositions)
-                {
-                    term_postings_list.push(postings);
-                } else {
-                    return Ok(Box::new(EmptyScorer));
-                }
-            }
-            Ok(Box::new(PhraseScorer::new(
-                term_postings_list,
-                similarity_weight,
-                fieldnorm_reader,
-                self.score_needed,
-            )))
+            };
+            term_postings_list.push(postings_opt?);
+        }
+        Some(PhraseScorer::new(
+            term_postings_list,
+            similarity_weight,
+            fieldnorm_reader,
+            self.score_needed,
+            self.slop,
+        ))
+    }
+}
+
+impl Weight for PhraseWeight {
+    fn scorer(&self, reader: &SegmentReader) -> Result<Box<Scorer>> {
+        if let Some(phrase_scorer) = self.phrase_scorer(reader) {
+            Ok(Box::new(phrase_scorer))
+        } else {
+            Ok(Box::new(EmptyScorer))
+        }
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> Result<Explanation> {
+        let mut phrase_scorer = self.phrase_scorer(reader)
+            .ok_or_else(|| does_not_match(doc))?;
+        if phrase_scorer.skip_next(doc) != SkipResult::Reached {
+            return Err(does_not_match(doc));
         }
+        let terms = self.phrase_terms
+            .iter()
+            .map(|term| term_description(reader.schema(), term))
+            .collect::<Vec<String>>();
+        let similarity_explanation = phrase_scorer.explain();
+        let mut explanation = Explanation::new(
+            format!(
+                "PhraseQuery({}) with a slop of {}, result of:",
+                terms.join(" "),
+                self.slop
+            ),
+            similarity_explanation.value(),
+        );
+        explanation.add_detail(similarity_explanation);
+        Ok(explanation)
     }
 }
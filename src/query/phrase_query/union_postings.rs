@@ -0,0 +1,109 @@
+use docset::{DocSet, SkipResult};
+use postings::Postings;
+use DocId;
+
+/// `Postings` over the union of several `Postings`.
+///
+/// The positions of a document are the merged positions of all of the
+/// underlying postings matching this document. It makes it possible
+/// for a `PhraseScorer` to accept several terms at a given position
+/// of the phrase.
+pub struct UnionPostings<TPostings: Postings> {
+    // postings that are not exhausted yet.
+    postings: Vec<TPostings>,
+    started: bool,
+    doc: DocId,
+    positions_buffer: Vec<u32>,
+}
+
+impl<TPostings: Postings> UnionPostings<TPostings> {
+    /// Creates a new `UnionPostings`.
+    pub fn new(postings: Vec<TPostings>) -> UnionPostings<TPostings> {
+        UnionPostings {
+            postings,
+            started: false,
+            doc: 0,
+            positions_buffer: Vec::new(),
+        }
+    }
+
+    /// Positions `self.doc` on the smallest document
+    /// of the remaining postings.
+    fn update_doc(&mut self) -> bool {
+        self.started = true;
+        match self.postings.iter().map(|postings| postings.doc()).min() {
+            Some(doc) => {
+                self.doc = doc;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl<TPostings: Postings> DocSet for UnionPostings<TPostings> {
+    fn advance(&mut self) -> bool {
+        let mut ord = 0;
+        while ord < self.postings.len() {
+            let needs_advance = !self.started || self.postings[ord].doc() == self.doc;
+            if needs_advance && !self.postings[ord].advance() {
+                self.postings.swap_remove(ord);
+            } else {
+                ord += 1;
+            }
+        }
+        self.update_doc()
+    }
+
+    fn skip_next(&mut self, target: DocId) -> SkipResult {
+        let mut ord = 0;
+        while ord < self.postings.len() {
+            let needs_skip = !self.started || self.postings[ord].doc() < target;
+            if needs_skip && self.postings[ord].skip_next(target) == SkipResult::End {
+                self.postings.swap_remove(ord);
+            } else {
+                ord += 1;
+            }
+        }
+        if !self.update_doc() {
+            SkipResult::End
+        } else if self.doc == target {
+            SkipResult::Reached
+        } else {
+            SkipResult::OverStep
+        }
+    }
+
+    fn doc(&self) -> DocId {
+        self.doc
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.postings
+            .iter()
+            .map(|postings| postings.size_hint())
+            .sum()
+    }
+}
+
+impl<TPostings: Postings> Postings for UnionPostings<TPostings> {
+    fn term_freq(&self) -> u32 {
+        self.postings
+            .iter()
+            .filter(|postings| postings.doc() == self.doc)
+            .map(|postings| postings.term_freq())
+            .sum()
+    }
+
+    fn positions_with_offset(&mut self, offset: u32, output: &mut Vec<u32>) {
+        output.clear();
+        for postings in &mut self.postings {
+            if postings.doc() == self.doc {
+                postings.positions_with_offset(offset, &mut self.positions_buffer);
+                output.extend_from_slice(&self.positions_buffer);
+            }
+        }
+        output.sort();
+        output.dedup();
+    }
+}
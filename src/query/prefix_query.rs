@@ -0,0 +1,260 @@
+use core::Searcher;
+use core::SegmentReader;
+use error::ErrorKind;
+use query::automaton_weight::DEFAULT_MAX_EXPANSIONS;
+use query::automaton_weight::{term_stream_to_bitset, term_stream_to_terms};
+use query::query_dsl::{field_name, max_expansions_to_dsl};
+use query::BitSetDocSet;
+use query::ConstScorer;
+use query::TermSetQuery;
+use query::{Query, QueryDsl, Scorer, Weight};
+use schema::{Field, Schema, Term, Type};
+use std::collections::BTreeSet;
+use termdict::{TermDictionary, TermStreamer};
+use Result;
+
+/// Returns the smallest byte string that is greater than all
+/// of the byte strings starting by `prefix`.
+///
+/// Trailing `0xFF` bytes cannot be incremented, and are
+/// therefore dropped before incrementing the last byte.
+/// If no such byte string exists (e.g. the prefix is empty or only
+/// contains `0xFF` bytes), returns `None`.
+fn prefix_upper_bound(prefix: &[u8]) -> Option<Vec<u8>> {
+    let mut upper_bound = prefix.to_vec();
+    while let Some(last_byte) = upper_bound.pop() {
+        if last_byte != u8::max_value() {
+            upper_bound.push(last_byte + 1u8);
+            return Some(upper_bound);
+        }
+    }
+    None
+}
+
+/// Streams the terms of the term dictionary starting by `prefix`.
+pub(crate) fn prefix_range<'a>(term_dict: &'a TermDictionary, prefix: &[u8]) -> TermStreamer<'a> {
+    let mut term_stream_builder = term_dict.range().ge(prefix);
+    if let Some(upper_bound) = prefix_upper_bound(prefix) {
+        term_stream_builder = term_stream_builder.lt(upper_bound);
+    }
+    term_stream_builder.into_stream()
+}
+
+/// `PrefixQuery` matches all of the documents containing a term
+/// starting by a given prefix.
+///
+/// Matched documents all get a constant `Score` of one.
+///
+/// # Implementation
+///
+/// The terms starting by the prefix form a contiguous range of the
+/// term dictionary, which is streamed. The documents of each term are
+/// appended into a `BitSet`.
+///
+/// In order to protect against very short prefixes, the number of
+/// terms the prefix may expand to within a segment is capped.
+/// (1,024 by default. See [`.set_max_expansions(...)`](#method.set_max_expansions).)
+/// If the cap is exceeded, the search fails with
+/// a `TooManyTerms` error.
+///
+/// # Example
+///
+/// ```rust
+/// #[macro_use]
+/// extern crate tantivy;
+/// use tantivy::schema::{SchemaBuilder, TEXT};
+/// use tantivy::{Index, Result, Term};
+/// use tantivy::collector::CountCollector;
+/// use tantivy::query::{PrefixQuery, Query};
+///
+/// # fn main() { example().unwrap(); }
+/// fn example() -> Result<()> {
+///     let mut schema_builder = SchemaBuilder::new();
+///     let title = schema_builder.add_text_field("title", TEXT);
+///     let schema = schema_builder.build();
+///     let index = Index::create_in_ram(schema);
+///     {
+///         let mut index_writer = index.writer(3_000_000)?;
+///         index_writer.add_document(doc!(title => "The Name of the Wind"));
+///         index_writer.add_document(doc!(title => "Windows and doors"));
+///         index_writer.add_document(doc!(title => "A Dairy Cow"));
+///         index_writer.commit().unwrap();
+///     }
+///     index.load_searchers()?;
+///     let searcher = index.searcher();
+///
+///     let query = PrefixQuery::new(Term::from_field_text(title, "wind"));
+///     let mut count_collector = CountCollector::default();
+///     query.search(&*searcher, &mut count_collector)?;
+///     assert_eq!(count_collector.count(), 2);
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct PrefixQuery {
+    field: Field,
+    prefix: Vec<u8>,
+    max_expansions: usize,
+}
+
+impl PrefixQuery {
+    /// Creates a new `PrefixQuery`.
+    ///
+    /// The text of the term given in argument is used as a prefix.
+    pub fn new(prefix: Term) -> PrefixQuery {
+        PrefixQuery {
+            field: prefix.field(),
+            prefix: prefix.value_bytes().to_owned(),
+            max_expansions: DEFAULT_MAX_EXPANSIONS,
+        }
+    }
+
+    /// Sets the maximum number of terms the prefix is allowed
+    /// to expand to within a segment.
+    pub fn set_max_expansions(&mut self, max_expansions: usize) {
+        self.max_expansions = max_expansions;
+    }
+}
+
+impl PrefixQuery {
+    fn specialized_weight(&self, searcher: &Searcher) -> Result<PrefixWeight> {
+        let value_type = searcher
+            .schema()
+            .get_field_entry(self.field)
+            .field_type()
+            .value_type();
+        if value_type != Type::Str {
+            let err_msg = format!(
+                "Create a prefix query on a field of type {:?}, \
+                 when only fields of type {:?} are supported",
+                value_type,
+                Type::Str
+            );
+            bail!(ErrorKind::SchemaError(err_msg))
+        }
+        Ok(PrefixWeight {
+            field: self.field,
+            prefix: self.prefix.clone(),
+            max_expansions: self.max_expansions,
+        })
+    }
+}
+
+impl Query for PrefixQuery {
+    fn weight(&self, searcher: &Searcher, _scoring_enabled: bool) -> Result<Box<Weight>> {
+        Ok(Box::new(self.specialized_weight(searcher)?))
+    }
+
+    fn rewrite(self: Box<Self>, searcher: &Searcher) -> Result<Box<Query>> {
+        let term_set_query = self.specialized_weight(searcher)?.term_set_query(searcher)?;
+        Ok(Box::new(term_set_query))
+    }
+
+    fn to_dsl(&self, schema: &Schema) -> Result<QueryDsl> {
+        Ok(QueryDsl::Prefix {
+            field: field_name(schema, self.field),
+            prefix: String::from_utf8_lossy(&self.prefix).into_owned(),
+            max_expansions: max_expansions_to_dsl(self.max_expansions),
+        })
+    }
+}
+
+struct PrefixWeight {
+    field: Field,
+    prefix: Vec<u8>,
+    max_expansions: usize,
+}
+
+impl PrefixWeight {
+    /// Returns the `TermSetQuery` over all of the terms starting
+    /// with the prefix, in any of the segments of the searcher.
+    fn term_set_query(&self, searcher: &Searcher) -> Result<TermSetQuery> {
+        let mut terms = BTreeSet::new();
+        for reader in searcher.segment_readers() {
+            let inverted_index = reader.inverted_index(self.field);
+            let term_stream = prefix_range(inverted_index.terms(), &self.prefix);
+            term_stream_to_terms(
+                self.field,
+                term_stream,
+                Some(self.max_expansions),
+                &mut terms,
+            )?;
+        }
+        Ok(TermSetQuery::new(terms.into_iter().collect()))
+    }
+}
+
+impl Weight for PrefixWeight {
+    fn scorer(&self, reader: &SegmentReader) -> Result<Box<Scorer>> {
+        let inverted_index = reader.inverted_index(self.field);
+        let term_stream = prefix_range(inverted_index.terms(), &self.prefix);
+        let doc_bitset = term_stream_to_bitset(
+            &inverted_index,
+            term_stream,
+            reader.max_doc(),
+            Some(self.max_expansions),
+        )?;
+        Ok(Box::new(ConstScorer::new(BitSetDocSet::from(doc_bitset))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{prefix_upper_bound, PrefixQuery};
+    use collector::tests::matching_docs;
+    use collector::CountCollector;
+    use error::ErrorKind;
+    use schema::{SchemaBuilder, STRING};
+    use Index;
+    use Term;
+
+    #[test]
+    fn test_prefix_upper_bound() {
+        assert_eq!(prefix_upper_bound(b""), None);
+        assert_eq!(prefix_upper_bound(b"abc"), Some(b"abd".to_vec()));
+        assert_eq!(prefix_upper_bound(&[1u8, 255u8]), Some(vec![2u8]));
+        assert_eq!(prefix_upper_bound(&[1u8, 255u8, 255u8]), Some(vec![2u8]));
+        assert_eq!(prefix_upper_bound(&[255u8, 255u8]), None);
+    }
+
+    #[test]
+    fn test_prefix_query() {
+        let mut schema_builder = SchemaBuilder::new();
+        let field = schema_builder.add_text_field("name", STRING);
+        let index = Index::create_in_ram(schema_builder.build());
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 10_000_000).unwrap();
+            for &name in &[
+                "wine", "wind", "windows", "win", "wing", "winch", "wio", "x", "",
+            ] {
+                index_writer.add_document(doc!(field => name));
+            }
+            index_writer.commit().unwrap();
+        }
+        index.load_searchers().unwrap();
+        let searcher = index.searcher();
+        {
+            let query = PrefixQuery::new(Term::from_field_text(field, "wind"));
+            assert_eq!(matching_docs(&searcher, &query), vec![1, 2]);
+        }
+        {
+            let query = PrefixQuery::new(Term::from_field_text(field, "win"));
+            assert_eq!(matching_docs(&searcher, &query), vec![0, 1, 2, 3, 4, 5]);
+        }
+        {
+            let query = PrefixQuery::new(Term::from_field_text(field, "windowsill"));
+            assert!(matching_docs(&searcher, &query).is_empty());
+        }
+        {
+            let query = PrefixQuery::new(Term::from_field_text(field, ""));
+            assert_eq!(matching_docs(&searcher, &query).len(), 9);
+        }
+        {
+            let mut query = PrefixQuery::new(Term::from_field_text(field, "win"));
+            query.set_max_expansions(5);
+            let mut count_collector = CountCollector::default();
+            let err = searcher.search(&query, &mut count_collector).unwrap_err();
+            assert_matches!(err.kind(), &ErrorKind::TooManyTerms(5));
+        }
+    }
+}
@@ -1,7 +1,13 @@
+use super::Explanation;
+use super::QueryDsl;
 use super::Weight;
 use collector::Collector;
 use core::searcher::Searcher;
+use error::ErrorKind;
+use schema::{Schema, Term};
+use std::collections::BTreeSet;
 use std::fmt;
+use DocAddress;
 use Result;
 use SegmentLocalId;
 
@@ -38,7 +44,7 @@ use SegmentLocalId;
 ///
 /// When implementing a new type of `Query`, it is normal to implement a
 /// dedicated `Query`, `Weight` and `Scorer`.
-pub trait Query: fmt::Debug {
+pub trait Query: BoxableQuery + fmt::Debug {
     /// Create the weight associated to a query.
     ///
     /// If scoring is not required, setting `scoring_enabled` to `false`
@@ -47,6 +53,49 @@ pub trait Query: fmt::Debug {
     /// See [`Weight`](./trait.Weight.html).
     fn weight(&self, searcher: &Searcher, scoring_enabled: bool) -> Result<Box<Weight>>;
 
+    /// Returns an `Explanation` for the score of the document
+    /// at the given address.
+    ///
+    /// Returns an error if the document does not match the query.
+    fn explain(&self, searcher: &Searcher, doc_address: DocAddress) -> Result<Explanation> {
+        let weight = self.weight(searcher, true)?;
+        let reader = searcher.segment_reader(doc_address.segment_ord());
+        weight.explain(reader, doc_address.doc())
+    }
+
+    /// Rewrites the query into a simpler query, using the terms
+    /// of the index of the searcher.
+    ///
+    /// Multi-term queries (e.g. range, prefix, fuzzy) are expanded into
+    /// a `TermSetQuery` over the terms of the index they match.
+    /// Compound queries rewrite their subqueries.
+    /// By default, the query is returned as is.
+    ///
+    /// Returns a `TooManyTerms` error if a multi-term query expands
+    /// to more terms than it allows.
+    fn rewrite(self: Box<Self>, _searcher: &Searcher) -> Result<Box<Query>> {
+        Ok(self.box_query())
+    }
+
+    /// Extracts all of the terms associated to the query, and inserts them
+    /// in the term set given in arguments.
+    ///
+    /// The terms of multi-term queries (e.g. range, prefix, fuzzy)
+    /// are only known once the query has been [rewritten](#method.rewrite).
+    /// By default, no term is inserted.
+    fn query_terms(&self, _term_set: &mut BTreeSet<Term>) {}
+
+    /// Returns the [`QueryDsl`](./enum.QueryDsl.html) describing the query.
+    ///
+    /// Returns an error if the query cannot be described
+    /// by the query DSL.
+    fn to_dsl(&self, _schema: &Schema) -> Result<QueryDsl> {
+        bail!(ErrorKind::InvalidArgument(format!(
+            "{:?} cannot be converted to the query DSL",
+            self
+        )))
+    }
+
     /// Returns the number of documents matching the query.
     fn count(&self, searcher: &Searcher) -> Result<usize> {
         let weight = self.weight(searcher, false)?;
@@ -71,9 +120,28 @@ pub trait Query: fmt::Debug {
         let weight = self.weight(searcher, scoring_enabled)?;
         for (segment_ord, segment_reader) in searcher.segment_readers().iter().enumerate() {
             collector.set_segment(segment_ord as SegmentLocalId, segment_reader)?;
-            let mut scorer = weight.scorer(segment_reader)?;
+            let mut scorer = if scoring_enabled && collector.is_top_k() {
+                weight.top_k_scorer(segment_reader)?
+            } else {
+                weight.scorer(segment_reader)?
+            };
             scorer.collect(collector, segment_reader.delete_bitset());
         }
         Ok(())
     }
 }
+
+/// Conversion of a boxed query into a `Box<Query>`.
+///
+/// This trait is implemented for all of the queries. It makes it possible
+/// for [`Query::rewrite`](./trait.Query.html#method.rewrite) to return the query itself.
+pub trait BoxableQuery {
+    /// Returns the query, as a `Box<Query>`.
+    fn box_query(self: Box<Self>) -> Box<Query>;
+}
+
+impl<TQuery: Query + 'static> BoxableQuery for TQuery {
+    fn box_query(self: Box<Self>) -> Box<Query> {
+        self
+    }
+}
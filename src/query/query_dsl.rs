@@ -0,0 +1,1604 @@
+use query::automaton_weight::DEFAULT_MAX_EXPANSIONS;
+use query::more_like_this_query::MoreLikeThisSource;
+use query::{AllQuery, BooleanQuery, BoostQuery, ConstantScoreQuery, DisjunctionMaxQuery};
+use query::{CombineMode, DecayFunction, FieldValueModifier, FunctionScoreQuery, ScoreFunction};
+use query::{ExistsQuery, FuzzyTermQuery, GeoBoundingBoxQuery, GeoDistanceQuery};
+use query::{MinimumShouldMatch, MoreLikeThisQuery, MultiPhraseQuery, PhrasePrefixQuery};
+use query::{Occur, PhraseQuery, PrefixQuery, Query, RangeQuery, RangeStrategy, RegexQuery};
+use query::{ScoreMode, TermQuery, TermSetQuery, ToChildBlockJoinQuery, ToParentBlockJoinQuery};
+use query::{SpanFirstQuery, SpanNearQuery, SpanNotQuery, SpanOrQuery, SpanPrefixQuery};
+use query::{SpanQuery, SpanTermQuery, WildcardQuery};
+use schema::{Facet, Field, FieldType, GeoPoint, IndexRecordOption, Schema, Term, Type, Value};
+use serde_json::{self, Value as JsonValue};
+use std::collections::{BTreeMap, Bound};
+use tokenizer::TokenizerManager;
+use DocAddress;
+use Score;
+
+/// Possible error that may happen when parsing a query DSL,
+/// or when converting it into a `Query`.
+///
+/// All of the errors hold the path of the offending element
+/// of the query, as in `$.bool.must[1].term`.
+#[derive(Debug, PartialEq, Eq)]
+pub enum QueryDslError {
+    /// The JSON is not valid, or does not describe a query.
+    InvalidJson {
+        /// Path of the offending query.
+        path: String,
+        /// Description of the error.
+        message: String,
+    },
+    /// The query references a field that is not in the schema.
+    FieldDoesNotExist {
+        /// Path of the offending query.
+        path: String,
+        /// Name of the missing field.
+        field_name: String,
+    },
+    /// A value cannot be converted into a value of the type of its field.
+    InvalidValue {
+        /// Path of the offending query.
+        path: String,
+        /// Description of the error.
+        message: String,
+    },
+    /// The parameters of the query are inconsistent
+    /// (e.g. a phrase with a single term).
+    InvalidQuery {
+        /// Path of the offending query.
+        path: String,
+        /// Description of the error.
+        message: String,
+    },
+}
+
+impl QueryDslError {
+    /// Returns the path of the offending element of the query.
+    pub fn path(&self) -> &str {
+        match *self {
+            QueryDslError::InvalidJson { ref path, .. }
+            | QueryDslError::FieldDoesNotExist { ref path, .. }
+            | QueryDslError::InvalidValue { ref path, .. }
+            | QueryDslError::InvalidQuery { ref path, .. } => path,
+        }
+    }
+}
+
+/// Serializable description of a `Query`.
+///
+/// The query DSL makes it possible to build queries from JSON,
+/// as in `{"bool": {"must": [{"term": {"field": "title", "value": "diary"}}]}}`.
+/// Fields are referenced by their name, and values are given as their JSON
+/// representation in documents.
+///
+/// A `QueryDsl` is converted into a `Query` against a schema with
+/// [`.to_query(...)`](#method.to_query).
+/// Conversely, queries can be converted back into their `QueryDsl`
+/// with [`Query::to_dsl(...)`](./trait.Query.html#method.to_dsl), for instance for logging.
+///
+/// # Example
+///
+/// ```rust
+/// # use tantivy::schema::{SchemaBuilder, TEXT};
+/// # use tantivy::query::QueryDsl;
+/// # let mut schema_builder = SchemaBuilder::new();
+/// # schema_builder.add_text_field("title", TEXT);
+/// # let schema = schema_builder.build();
+/// let query_dsl = QueryDsl::from_json(r#"{
+///     "bool": {
+///         "must": [{"term": {"field": "title", "value": "diary"}}],
+///         "must_not": [{"phrase": {"field": "title", "terms": ["cow", "girl"]}}]
+///     }
+/// }"#).unwrap();
+/// let query = query_dsl.to_query(&schema).unwrap();
+/// assert_eq!(query.to_dsl(&schema).unwrap(), query_dsl);
+/// ```
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum QueryDsl {
+    /// Matches all of the documents. See `AllQuery`.
+    All {},
+    /// See `TermQuery`.
+    Term {
+        /// Name of the field.
+        field: String,
+        /// Value of the term.
+        value: JsonValue,
+        /// Information read from the postings of the term, as
+        /// `"basic"`, `"freq"` or `"position"`. (`"freq"` by default)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        index_record_option: Option<IndexRecordOption>,
+    },
+    /// See `TermSetQuery`.
+    TermSet {
+        /// Values of the terms, per field name.
+        terms: BTreeMap<String, Vec<JsonValue>>,
+    },
+    /// See `PhraseQuery`.
+    Phrase {
+        /// Name of the field.
+        field: String,
+        /// Values of the terms of the phrase.
+        terms: Vec<JsonValue>,
+        /// Slop of the phrase.
+        #[serde(default, skip_serializing_if = "is_zero")]
+        slop: u32,
+    },
+    /// See `MultiPhraseQuery`.
+    MultiPhrase {
+        /// Name of the field.
+        field: String,
+        /// Values of the alternative terms, for each position of the phrase.
+        terms: Vec<Vec<JsonValue>>,
+        /// Slop of the phrase.
+        #[serde(default, skip_serializing_if = "is_zero")]
+        slop: u32,
+    },
+    /// See `PhrasePrefixQuery`.
+    PhrasePrefix {
+        /// Name of the field.
+        field: String,
+        /// Values of the terms of the phrase, preceding the prefix.
+        terms: Vec<JsonValue>,
+        /// Prefix of the last term of the phrase.
+        prefix: String,
+        /// Slop of the phrase.
+        #[serde(default, skip_serializing_if = "is_zero")]
+        slop: u32,
+        /// Maximum number of terms the prefix may expand to.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max_expansions: Option<usize>,
+    },
+    /// See `PrefixQuery`.
+    Prefix {
+        /// Name of the field.
+        field: String,
+        /// Prefix of the terms.
+        prefix: String,
+        /// Maximum number of terms the prefix may expand to within a segment.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max_expansions: Option<usize>,
+    },
+    /// See `FuzzyTermQuery`.
+    Fuzzy {
+        /// Name of the field.
+        field: String,
+        /// Text of the term.
+        value: String,
+        /// Maximum Levenshtein distance.
+        distance: u8,
+        /// Whether swapping two adjacent characters counts as a single edit.
+        #[serde(default)]
+        transpositions: bool,
+        /// Number of leading characters that must match exactly.
+        #[serde(default, skip_serializing_if = "is_zero")]
+        prefix_length: usize,
+    },
+    /// See `RegexQuery`.
+    Regex {
+        /// Name of the field.
+        field: String,
+        /// Regular expression the terms must match.
+        pattern: String,
+    },
+    /// See `WildcardQuery`.
+    Wildcard {
+        /// Name of the field.
+        field: String,
+        /// Wildcard pattern the terms must match.
+        pattern: String,
+        /// Maximum number of terms the pattern may expand to within a segment.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max_expansions: Option<usize>,
+    },
+    /// See `RangeQuery`. Missing bounds are unbounded.
+    Range {
+        /// Name of the field.
+        field: String,
+        /// Excluded lower bound.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        gt: Option<JsonValue>,
+        /// Included lower bound.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        gte: Option<JsonValue>,
+        /// Excluded upper bound.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        lt: Option<JsonValue>,
+        /// Included upper bound.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        lte: Option<JsonValue>,
+        /// Strategy used to find the documents within the range,
+        /// as `"auto"`, `"terms"` or `"fast_field"`. (`"auto"` by default)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        strategy: Option<RangeStrategy>,
+        /// Maximum number of terms the range may expand to within a segment
+        /// when rewritten.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max_expansions: Option<usize>,
+    },
+    /// See `ExistsQuery`.
+    Exists {
+        /// Name of the field.
+        field: String,
+    },
+    /// See `BooleanQuery`.
+    Bool {
+        /// Subqueries with the `Must` occurence.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        must: Vec<QueryDsl>,
+        /// Subqueries with the `Should` occurence.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        should: Vec<QueryDsl>,
+        /// Subqueries with the `MustNot` occurence.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        must_not: Vec<QueryDsl>,
+        /// Subqueries with the `Filter` occurence.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        filter: Vec<QueryDsl>,
+        /// Minimum number of `should` subqueries that must match,
+        /// as `3` or `"75%"`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        minimum_should_match: Option<MinimumShouldMatch>,
+    },
+    /// See `BoostQuery`.
+    Boost {
+        /// Boosted query.
+        query: Box<QueryDsl>,
+        /// Factor the score is multiplied by.
+        boost: Score,
+    },
+    /// See `ConstantScoreQuery`.
+    ConstantScore {
+        /// Underlying query.
+        query: Box<QueryDsl>,
+        /// Score of the matched documents.
+        score: Score,
+    },
+    /// See `DisjunctionMaxQuery`.
+    DisjunctionMax {
+        /// Disjuncts.
+        queries: Vec<QueryDsl>,
+        /// Weight of the scores of the non-maximal disjuncts.
+        #[serde(default)]
+        tie_breaker: Score,
+    },
+    /// See `FunctionScoreQuery`.
+    FunctionScore {
+        /// Underlying query.
+        query: Box<QueryDsl>,
+        /// Function combined with the score of the underlying query.
+        function: ScoreFunctionDsl,
+        /// Way the function and the score are combined, as `"multiply"`,
+        /// `"sum"` or `"replace"`. (`"multiply"` by default)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        combine_mode: Option<CombineMode>,
+    },
+    /// See `MoreLikeThisQuery`. Exactly one of `text` and `document` must be set.
+    ///
+    /// The text is tokenized with the built-in tokenizers, so that the fields
+    /// must not use a tokenizer registered on the index.
+    MoreLikeThis {
+        /// Names of the fields.
+        fields: Vec<String>,
+        /// Text the documents must be similar to.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        text: Option<String>,
+        /// Address of the document the documents must be similar to,
+        /// as `[segment_ord, doc_id]`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        document: Option<DocAddress>,
+        /// Minimum number of documents the selected terms appear in. (1 by default)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        min_doc_freq: Option<u64>,
+        /// Maximum number of documents the selected terms appear in.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max_doc_freq: Option<u64>,
+        /// Minimum number of characters of the selected terms.
+        #[serde(default, skip_serializing_if = "is_zero")]
+        min_term_len: usize,
+        /// Maximum number of selected terms. (25 by default)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max_query_terms: Option<usize>,
+    },
+    /// See `SpanTermQuery`.
+    SpanTerm {
+        /// Name of the field.
+        field: String,
+        /// Value of the term.
+        value: JsonValue,
+    },
+    /// See `SpanPrefixQuery`.
+    SpanPrefix {
+        /// Name of the field.
+        field: String,
+        /// Prefix of the terms.
+        prefix: String,
+        /// Maximum number of terms the prefix may expand to within a segment.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max_expansions: Option<usize>,
+    },
+    /// See `SpanNearQuery`.
+    SpanNear {
+        /// Span queries, on the same field.
+        clauses: Vec<QueryDsl>,
+        /// Maximum number of positions between the spans of the clauses.
+        #[serde(default, skip_serializing_if = "is_zero")]
+        slop: u32,
+        /// Whether the spans must appear in the order of the clauses.
+        #[serde(default)]
+        in_order: bool,
+    },
+    /// See `SpanOrQuery`.
+    SpanOr {
+        /// Span queries, on the same field.
+        clauses: Vec<QueryDsl>,
+    },
+    /// See `SpanNotQuery`.
+    SpanNot {
+        /// Span query whose spans are matched.
+        include: Box<QueryDsl>,
+        /// Span query whose spans must not overlap the matched spans.
+        exclude: Box<QueryDsl>,
+    },
+    /// See `SpanFirstQuery`.
+    SpanFirst {
+        /// Span query.
+        query: Box<QueryDsl>,
+        /// Position the spans must end at or before.
+        end: u32,
+    },
+    /// See `GeoDistanceQuery`.
+    GeoDistance {
+        /// Name of the field.
+        field: String,
+        /// Origin, as `{"lat": .., "lon": ..}`.
+        origin: GeoPoint,
+        /// Distance from the origin, in meters.
+        distance: f64,
+    },
+    /// See `GeoBoundingBoxQuery`.
+    GeoBoundingBox {
+        /// Name of the field.
+        field: String,
+        /// Top left corner, as `{"lat": .., "lon": ..}`.
+        top_left: GeoPoint,
+        /// Bottom right corner, as `{"lat": .., "lon": ..}`.
+        bottom_right: GeoPoint,
+    },
+    /// See `ToParentBlockJoinQuery`.
+    ToParentBlockJoin {
+        /// Query on the child documents.
+        query: Box<QueryDsl>,
+        /// Query matching the parent documents.
+        parent_filter: Box<QueryDsl>,
+        /// Way the scores of the child documents are aggregated.
+        score_mode: ScoreMode,
+    },
+    /// See `ToChildBlockJoinQuery`.
+    ToChildBlockJoin {
+        /// Query on the parent documents.
+        query: Box<QueryDsl>,
+        /// Query matching the parent documents.
+        parent_filter: Box<QueryDsl>,
+    },
+}
+
+/// Serializable description of a `ScoreFunction`,
+/// as used by `QueryDsl::FunctionScore`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum ScoreFunctionDsl {
+    /// See `ScoreFunction::FieldValueFactor`.
+    FieldValueFactor {
+        /// Name of the field.
+        field: String,
+        /// Factor the value is multiplied by.
+        factor: f64,
+        /// Modifier applied on the multiplied value,
+        /// as `"none"`, `"log1p"` or `"sqrt"`.
+        modifier: FieldValueModifier,
+    },
+    /// See `ScoreFunction::Decay`.
+    Decay {
+        /// Name of the field.
+        field: String,
+        /// Shape of the decay curve, as `"gauss"`, `"exp"` or `"linear"`.
+        decay_function: DecayFunction,
+        /// Value for which the function is maximal.
+        origin: f64,
+        /// Distance (in addition to `offset`) at which the function is worth `decay`.
+        scale: f64,
+        /// Distance from the origin under which the function is not decaying.
+        offset: f64,
+        /// Value of the function at `offset + scale` from the origin.
+        decay: f64,
+    },
+    /// See `ScoreFunction::GeoDecay`.
+    GeoDecay {
+        /// Name of the field.
+        field: String,
+        /// Shape of the decay curve, as `"gauss"`, `"exp"` or `"linear"`.
+        decay_function: DecayFunction,
+        /// Origin, as `{"lat": .., "lon": ..}`.
+        origin: GeoPoint,
+        /// Distance in meters (in addition to `offset`) at which
+        /// the function is worth `decay`.
+        scale: f64,
+        /// Distance in meters from the origin under which
+        /// the function is not decaying.
+        offset: f64,
+        /// Value of the function at `offset + scale` from the origin.
+        decay: f64,
+    },
+}
+
+fn is_zero<T: Default + PartialEq>(val: &T) -> bool {
+    *val == T::default()
+}
+
+/// Keys of the query DSL objects holding nested queries.
+const NESTED_QUERY_KEYS: [&str; 4] = ["query", "parent_filter", "include", "exclude"];
+
+/// Keys of the query DSL objects holding lists of nested queries.
+const NESTED_QUERY_LIST_KEYS: [&str; 6] =
+    ["must", "should", "must_not", "filter", "queries", "clauses"];
+
+/// Locates the deepest query of the JSON value that cannot be deserialized,
+/// in order to report its path along with the deserialization error.
+fn locate_json_error(json: &JsonValue, path: &str, err: serde_json::Error) -> QueryDslError {
+    let query_type_and_params = json
+        .as_object()
+        .filter(|json_obj| json_obj.len() == 1)
+        .and_then(|json_obj| json_obj.iter().next());
+    if let Some((query_type, params)) = query_type_and_params {
+        let path = format!("{}.{}", path, query_type);
+        if let Some(params_obj) = params.as_object() {
+            for (key, val) in params_obj {
+                let nested_queries: Vec<(String, &JsonValue)> =
+                    if NESTED_QUERY_KEYS.contains(&key.as_str()) {
+                        vec![(format!("{}.{}", path, key), val)]
+                    } else if NESTED_QUERY_LIST_KEYS.contains(&key.as_str()) {
+                        val.as_array()
+                            .map(|nested_jsons| {
+                                nested_jsons
+                                    .iter()
+                                    .enumerate()
+                                    .map(|(ord, nested_json)| {
+                                        (format!("{}.{}[{}]", path, key, ord), nested_json)
+                                    })
+                                    .collect()
+                            })
+                            .unwrap_or_else(Vec::new)
+                    } else {
+                        Vec::new()
+                    };
+                for (nested_path, nested_json) in nested_queries {
+                    if let Err(nested_err) = serde_json::from_value::<QueryDsl>(nested_json.clone())
+                    {
+                        return locate_json_error(nested_json, &nested_path, nested_err);
+                    }
+                }
+            }
+        }
+        return QueryDslError::InvalidJson {
+            path,
+            message: err.to_string(),
+        };
+    }
+    QueryDslError::InvalidJson {
+        path: path.to_string(),
+        message: err.to_string(),
+    }
+}
+
+/// Returns the name of the field, as referenced in the query DSL.
+pub(crate) fn field_name(schema: &Schema, field: Field) -> String {
+    schema.get_field_name(field).to_string()
+}
+
+/// Returns the JSON representation of the value of a term.
+pub(crate) fn term_value_to_json(schema: &Schema, term: &Term) -> JsonValue {
+    match *schema.get_field_entry(term.field()).field_type() {
+        FieldType::U64(_) => JsonValue::from(term.get_u64()),
+        FieldType::I64(_) => JsonValue::from(term.get_i64()),
+        FieldType::HierarchicalFacet => {
+            let facet = unsafe { Facet::from_encoded(term.value_bytes().to_owned()) };
+            JsonValue::from(facet.to_string())
+        }
+        _ => JsonValue::from(String::from_utf8_lossy(term.value_bytes()).into_owned()),
+    }
+}
+
+/// Returns the JSON representation of the value of a term,
+/// given its field and its value bytes.
+pub(crate) fn term_bytes_to_json(schema: &Schema, field: Field, value_bytes: &[u8]) -> JsonValue {
+    let mut term = Term::for_field(field);
+    term.set_bytes(value_bytes);
+    term_value_to_json(schema, &term)
+}
+
+/// Returns the maximum number of expansions, as held by the query DSL,
+/// that is `None` for the default maximum number of expansions.
+pub(crate) fn max_expansions_to_dsl(max_expansions: usize) -> Option<usize> {
+    if max_expansions == DEFAULT_MAX_EXPANSIONS {
+        None
+    } else {
+        Some(max_expansions)
+    }
+}
+
+/// Returns the `IndexRecordOption` of a term query, as held by the query DSL,
+/// that is `None` for the default `IndexRecordOption::WithFreqs`.
+pub(crate) fn index_record_option_to_dsl(
+    index_record_option: IndexRecordOption,
+) -> Option<IndexRecordOption> {
+    if index_record_option == IndexRecordOption::WithFreqs {
+        None
+    } else {
+        Some(index_record_option)
+    }
+}
+
+fn invalid_query(path: &str, message: &str) -> QueryDslError {
+    QueryDslError::InvalidQuery {
+        path: path.to_string(),
+        message: message.to_string(),
+    }
+}
+
+/// Boxes span queries as either `Query` or `SpanQuery` trait objects,
+/// so that span queries are built the same way at the root
+/// of a query and nested within span queries.
+trait BoxedSpanQuery: Sized {
+    fn from_span_query<TSpanQuery: SpanQuery + 'static>(span_query: TSpanQuery) -> Self;
+}
+
+impl BoxedSpanQuery for Box<Query> {
+    fn from_span_query<TSpanQuery: SpanQuery + 'static>(span_query: TSpanQuery) -> Self {
+        Box::new(span_query)
+    }
+}
+
+impl BoxedSpanQuery for Box<SpanQuery> {
+    fn from_span_query<TSpanQuery: SpanQuery + 'static>(span_query: TSpanQuery) -> Self {
+        Box::new(span_query)
+    }
+}
+
+/// Conversion of a `QueryDsl` into a `Query`, keeping track
+/// of the path of the query being converted.
+struct QueryBuilder<'a> {
+    schema: &'a Schema,
+}
+
+impl<'a> QueryBuilder<'a> {
+    fn field(&self, path: &str, field_name: &str) -> Result<Field, QueryDslError> {
+        self.schema
+            .get_field(field_name)
+            .ok_or_else(|| QueryDslError::FieldDoesNotExist {
+                path: path.to_string(),
+                field_name: field_name.to_string(),
+            })
+    }
+
+    fn term(&self, path: &str, field: Field, json: &JsonValue) -> Result<Term, QueryDslError> {
+        let field_entry = self.schema.get_field_entry(field);
+        let invalid_value = |message: String| QueryDslError::InvalidValue {
+            path: path.to_string(),
+            message: format!(
+                "Invalid value for field {:?}: {}",
+                field_entry.name(),
+                message
+            ),
+        };
+        let value = field_entry
+            .field_type()
+            .value_from_json(json)
+            .map_err(|err| invalid_value(format!("{:?}", err)))?;
+        match value {
+            Value::Str(ref text) => Ok(Term::from_field_text(field, text)),
+            Value::U64(val) => Ok(Term::from_field_u64(field, val)),
+            Value::I64(val) => Ok(Term::from_field_i64(field, val)),
+            Value::Facet(ref facet) => Ok(Term::from_facet(field, facet)),
+            Value::Bytes(_) | Value::GeoPoint(_) => Err(invalid_value(format!(
+                "{:?} fields cannot be searched by terms",
+                field_entry.field_type().value_type()
+            ))),
+        }
+    }
+
+    fn terms(
+        &self,
+        path: &str,
+        field: Field,
+        jsons: &[JsonValue],
+    ) -> Result<Vec<Term>, QueryDslError> {
+        jsons
+            .iter()
+            .map(|json| self.term(path, field, json))
+            .collect()
+    }
+
+    fn queries(
+        &self,
+        path: &str,
+        query_dsls: &[QueryDsl],
+    ) -> Result<Vec<Box<Query>>, QueryDslError> {
+        query_dsls
+            .iter()
+            .enumerate()
+            .map(|(ord, query_dsl)| self.query(&format!("{}[{}]", path, ord), query_dsl))
+            .collect()
+    }
+
+    /// Converts the clauses of a span query, which must be
+    /// span queries on the same field.
+    fn span_clauses(
+        &self,
+        path: &str,
+        query_dsls: &[QueryDsl],
+    ) -> Result<Vec<Box<SpanQuery>>, QueryDslError> {
+        if query_dsls.is_empty() {
+            return Err(invalid_query(
+                path,
+                "A span query requires at least one clause",
+            ));
+        }
+        let clauses: Vec<Box<SpanQuery>> = query_dsls
+            .iter()
+            .enumerate()
+            .map(|(ord, query_dsl)| self.span_query(&format!("{}[{}]", path, ord), query_dsl))
+            .collect::<Result<_, _>>()?;
+        let field = clauses[0].field();
+        if clauses.iter().any(|clause| clause.field() != field) {
+            return Err(invalid_query(
+                path,
+                "All clauses of a span query must belong to the same field",
+            ));
+        }
+        Ok(clauses)
+    }
+
+    fn span_query<TBoxedSpanQuery: BoxedSpanQuery>(
+        &self,
+        path: &str,
+        query_dsl: &QueryDsl,
+    ) -> Result<TBoxedSpanQuery, QueryDslError> {
+        Ok(match *query_dsl {
+            QueryDsl::SpanTerm {
+                ref field,
+                ref value,
+            } => {
+                let path = &format!("{}.span_term", path);
+                let field = self.field(path, field)?;
+                let term = self.term(path, field, value)?;
+                TBoxedSpanQuery::from_span_query(SpanTermQuery::new(term))
+            }
+            QueryDsl::SpanPrefix {
+                ref field,
+                ref prefix,
+                max_expansions,
+            } => {
+                let field = self.field(&format!("{}.span_prefix", path), field)?;
+                let mut span_prefix_query =
+                    SpanPrefixQuery::new(Term::from_field_text(field, prefix));
+                if let Some(max_expansions) = max_expansions {
+                    span_prefix_query.set_max_expansions(max_expansions);
+                }
+                TBoxedSpanQuery::from_span_query(span_prefix_query)
+            }
+            QueryDsl::SpanNear {
+                ref clauses,
+                slop,
+                in_order,
+            } => {
+                let path = &format!("{}.span_near.clauses", path);
+                let clauses = self.span_clauses(path, clauses)?;
+                TBoxedSpanQuery::from_span_query(SpanNearQuery::new(clauses, slop, in_order))
+            }
+            QueryDsl::SpanOr { ref clauses } => {
+                let path = &format!("{}.span_or.clauses", path);
+                let clauses = self.span_clauses(path, clauses)?;
+                TBoxedSpanQuery::from_span_query(SpanOrQuery::new(clauses))
+            }
+            QueryDsl::SpanNot {
+                ref include,
+                ref exclude,
+            } => {
+                let path = &format!("{}.span_not", path);
+                let include: Box<SpanQuery> =
+                    self.span_query(&format!("{}.include", path), include)?;
+                let exclude: Box<SpanQuery> =
+                    self.span_query(&format!("{}.exclude", path), exclude)?;
+                if include.field() != exclude.field() {
+                    return Err(invalid_query(
+                        path,
+                        "All clauses of a span query must belong to the same field",
+                    ));
+                }
+                TBoxedSpanQuery::from_span_query(SpanNotQuery::new(include, exclude))
+            }
+            QueryDsl::SpanFirst { ref query, end } => {
+                let span_query = self.span_query(&format!("{}.span_first.query", path), query)?;
+                TBoxedSpanQuery::from_span_query(SpanFirstQuery::new(span_query, end))
+            }
+            _ => {
+                return Err(invalid_query(
+                    path,
+                    "Only span queries are allowed within span queries",
+                ))
+            }
+        })
+    }
+
+    fn score_function(
+        &self,
+        path: &str,
+        score_function_dsl: &ScoreFunctionDsl,
+    ) -> Result<ScoreFunction, QueryDslError> {
+        let score_function = match *score_function_dsl {
+            ScoreFunctionDsl::FieldValueFactor {
+                ref field,
+                factor,
+                modifier,
+            } => ScoreFunction::FieldValueFactor {
+                field: self.field(path, field)?,
+                factor,
+                modifier,
+            },
+            ScoreFunctionDsl::Decay {
+                ref field,
+                decay_function,
+                origin,
+                scale,
+                offset,
+                decay,
+            } => ScoreFunction::Decay {
+                field: self.field(path, field)?,
+                decay_function,
+                origin,
+                scale,
+                offset,
+                decay,
+            },
+            ScoreFunctionDsl::GeoDecay {
+                ref field,
+                decay_function,
+                origin,
+                scale,
+                offset,
+                decay,
+            } => ScoreFunction::GeoDecay {
+                field: self.field(path, field)?,
+                decay_function,
+                origin,
+                scale,
+                offset,
+                decay,
+            },
+        };
+        score_function
+            .validate()
+            .map_err(|message| invalid_query(path, &message))?;
+        Ok(score_function)
+    }
+
+    fn range_query(
+        &self,
+        path: &str,
+        field: Field,
+        bounds: [&Option<JsonValue>; 4],
+    ) -> Result<RangeQuery, QueryDslError> {
+        let [gt, gte, lt, lte] = bounds;
+        let (left, right) = match (gt, gte, lt, lte) {
+            (&Some(_), &Some(_), _, _) => {
+                return Err(invalid_query(path, "Both gt and gte are set"))
+            }
+            (_, _, &Some(_), &Some(_)) => {
+                return Err(invalid_query(path, "Both lt and lte are set"))
+            }
+            _ => (
+                gt.as_ref()
+                    .map(Bound::Excluded)
+                    .or_else(|| gte.as_ref().map(Bound::Included))
+                    .unwrap_or(Bound::Unbounded),
+                lt.as_ref()
+                    .map(Bound::Excluded)
+                    .or_else(|| lte.as_ref().map(Bound::Included))
+                    .unwrap_or(Bound::Unbounded),
+            ),
+        };
+        let to_term_bound = |bound: Bound<&JsonValue>| -> Result<Bound<Term>, QueryDslError> {
+            Ok(match bound {
+                Bound::Included(json) => Bound::Included(self.term(path, field, json)?),
+                Bound::Excluded(json) => Bound::Excluded(self.term(path, field, json)?),
+                Bound::Unbounded => Bound::Unbounded,
+            })
+        };
+        let (left, right) = (to_term_bound(left)?, to_term_bound(right)?);
+        let value_type = self.schema.get_field_entry(field).field_type().value_type();
+        Ok(match value_type {
+            Type::U64 => RangeQuery::new_u64_bounds(
+                field,
+                map_bound(left, |term| term.get_u64()),
+                map_bound(right, |term| term.get_u64()),
+            ),
+            Type::I64 => RangeQuery::new_i64_bounds(
+                field,
+                map_bound(left, |term| term.get_i64()),
+                map_bound(right, |term| term.get_i64()),
+            ),
+            Type::Str => RangeQuery::new_str_bounds(
+                field,
+                map_bound(left.as_ref(), |term| term.text()),
+                map_bound(right.as_ref(), |term| term.text()),
+            ),
+            _ => {
+                return Err(invalid_query(
+                    path,
+                    &format!("Range queries are not supported on {:?} fields", value_type),
+                ))
+            }
+        })
+    }
+
+    fn query(&self, path: &str, query_dsl: &QueryDsl) -> Result<Box<Query>, QueryDslError> {
+        Ok(match *query_dsl {
+            QueryDsl::All {} => Box::new(AllQuery),
+            QueryDsl::Term {
+                ref field,
+                ref value,
+                index_record_option,
+            } => {
+                let path = &format!("{}.term", path);
+                let field = self.field(path, field)?;
+                let term = self.term(path, field, value)?;
+                let index_record_option =
+                    index_record_option.unwrap_or(IndexRecordOption::WithFreqs);
+                Box::new(TermQuery::new(term, index_record_option))
+            }
+            QueryDsl::TermSet { ref terms } => {
+                let path = &format!("{}.term_set", path);
+                let mut all_terms = Vec::new();
+                for (field_name, values) in terms {
+                    let field = self.field(path, field_name)?;
+                    all_terms.extend(self.terms(path, field, values)?);
+                }
+                Box::new(TermSetQuery::new(all_terms))
+            }
+            QueryDsl::Phrase {
+                ref field,
+                ref terms,
+                slop,
+            } => {
+                let path = &format!("{}.phrase", path);
+                if terms.len() < 2 {
+                    return Err(invalid_query(path, "A phrase requires at least two terms"));
+                }
+                let field = self.field(path, field)?;
+                let mut phrase_query = PhraseQuery::new(self.terms(path, field, terms)?);
+                phrase_query.set_slop(slop);
+                Box::new(phrase_query)
+            }
+            QueryDsl::MultiPhrase {
+                ref field,
+                ref terms,
+                slop,
+            } => {
+                let path = &format!("{}.multi_phrase", path);
+                if terms.is_empty() || terms.iter().any(|alternatives| alternatives.is_empty()) {
+                    return Err(invalid_query(
+                        path,
+                        "A multi phrase requires at least one term at each position",
+                    ));
+                }
+                let field = self.field(path, field)?;
+                let terms = terms
+                    .iter()
+                    .map(|alternatives| self.terms(path, field, alternatives))
+                    .collect::<Result<_, _>>()?;
+                let mut multi_phrase_query = MultiPhraseQuery::new(terms);
+                multi_phrase_query.set_slop(slop);
+                Box::new(multi_phrase_query)
+            }
+            QueryDsl::PhrasePrefix {
+                ref field,
+                ref terms,
+                ref prefix,
+                slop,
+                max_expansions,
+            } => {
+                let path = &format!("{}.phrase_prefix", path);
+                if terms.is_empty() {
+                    return Err(invalid_query(
+                        path,
+                        "A phrase prefix requires at least one term before the prefix",
+                    ));
+                }
+                let field = self.field(path, field)?;
+                let mut terms = self.terms(path, field, terms)?;
+                terms.push(Term::from_field_text(field, prefix));
+                let mut phrase_prefix_query = PhrasePrefixQuery::new(terms);
+                phrase_prefix_query.set_slop(slop);
+                if let Some(max_expansions) = max_expansions {
+                    phrase_prefix_query.set_max_expansions(max_expansions);
+                }
+                Box::new(phrase_prefix_query)
+            }
+            QueryDsl::Prefix {
+                ref field,
+                ref prefix,
+                max_expansions,
+            } => {
+                let field = self.field(&format!("{}.prefix", path), field)?;
+                let mut prefix_query = PrefixQuery::new(Term::from_field_text(field, prefix));
+                if let Some(max_expansions) = max_expansions {
+                    prefix_query.set_max_expansions(max_expansions);
+                }
+                Box::new(prefix_query)
+            }
+            QueryDsl::Fuzzy {
+                ref field,
+                ref value,
+                distance,
+                transpositions,
+                prefix_length,
+            } => {
+                let field = self.field(&format!("{}.fuzzy", path), field)?;
+                let term = Term::from_field_text(field, value);
+                let mut fuzzy_query = FuzzyTermQuery::new(term, distance, transpositions);
+                fuzzy_query.set_prefix_length(prefix_length);
+                Box::new(fuzzy_query)
+            }
+            QueryDsl::Regex {
+                ref field,
+                ref pattern,
+            } => {
+                let field = self.field(&format!("{}.regex", path), field)?;
+                Box::new(RegexQuery::new(field, pattern))
+            }
+            QueryDsl::Wildcard {
+                ref field,
+                ref pattern,
+                max_expansions,
+            } => {
+                let field = self.field(&format!("{}.wildcard", path), field)?;
+                let mut wildcard_query = WildcardQuery::new(field, pattern);
+                if let Some(max_expansions) = max_expansions {
+                    wildcard_query.set_max_expansions(max_expansions);
+                }
+                Box::new(wildcard_query)
+            }
+            QueryDsl::Range {
+                ref field,
+                ref gt,
+                ref gte,
+                ref lt,
+                ref lte,
+                strategy,
+                max_expansions,
+            } => {
+                let path = &format!("{}.range", path);
+                let field = self.field(path, field)?;
+                let mut range_query = self.range_query(path, field, [gt, gte, lt, lte])?;
+                if let Some(strategy) = strategy {
+                    range_query.set_strategy(strategy);
+                }
+                if let Some(max_expansions) = max_expansions {
+                    range_query.set_max_expansions(max_expansions);
+                }
+                Box::new(range_query)
+            }
+            QueryDsl::Exists { ref field } => {
+                let field = self.field(&format!("{}.exists", path), field)?;
+                Box::new(ExistsQuery::new(field))
+            }
+            QueryDsl::Bool {
+                ref must,
+                ref should,
+                ref must_not,
+                ref filter,
+                minimum_should_match,
+            } => {
+                let mut subqueries = Vec::new();
+                for &(occur, key, query_dsls) in &[
+                    (Occur::Must, "must", must),
+                    (Occur::Should, "should", should),
+                    (Occur::MustNot, "must_not", must_not),
+                    (Occur::Filter, "filter", filter),
+                ] {
+                    let clause_path = format!("{}.bool.{}", path, key);
+                    for subquery in self.queries(&clause_path, query_dsls)? {
+                        subqueries.push((occur, subquery));
+                    }
+                }
+                let mut boolean_query = BooleanQuery::from(subqueries);
+                if let Some(minimum_should_match) = minimum_should_match {
+                    boolean_query.set_minimum_should_match(minimum_should_match);
+                }
+                Box::new(boolean_query)
+            }
+            QueryDsl::Boost { ref query, boost } => {
+                let query = self.query(&format!("{}.boost.query", path), query)?;
+                Box::new(BoostQuery::new(query, boost))
+            }
+            QueryDsl::ConstantScore { ref query, score } => {
+                let query = self.query(&format!("{}.constant_score.query", path), query)?;
+                Box::new(ConstantScoreQuery::new(query, score))
+            }
+            QueryDsl::DisjunctionMax {
+                ref queries,
+                tie_breaker,
+            } => {
+                let queries =
+                    self.queries(&format!("{}.disjunction_max.queries", path), queries)?;
+                Box::new(DisjunctionMaxQuery::new(queries, tie_breaker))
+            }
+            QueryDsl::FunctionScore {
+                ref query,
+                ref function,
+                combine_mode,
+            } => {
+                let path = format!("{}.function_score", path);
+                let query = self.query(&format!("{}.query", path), query)?;
+                let function = self.score_function(&format!("{}.function", path), function)?;
+                let mut function_score_query = FunctionScoreQuery::new(query, function);
+                if let Some(combine_mode) = combine_mode {
+                    function_score_query.set_combine_mode(combine_mode);
+                }
+                Box::new(function_score_query)
+            }
+            QueryDsl::MoreLikeThis {
+                ref fields,
+                ref text,
+                document,
+                min_doc_freq,
+                max_doc_freq,
+                min_term_len,
+                max_query_terms,
+            } => {
+                let path = &format!("{}.more_like_this", path);
+                let source = match (text, document) {
+                    (&Some(ref text), None) => MoreLikeThisSource::Text(text.clone()),
+                    (&None, Some(doc_address)) => MoreLikeThisSource::Document(doc_address),
+                    _ => {
+                        return Err(invalid_query(
+                            path,
+                            "Exactly one of text and document must be set",
+                        ))
+                    }
+                };
+                let fields = fields
+                    .iter()
+                    .map(|field_name| self.field(path, field_name))
+                    .collect::<Result<Vec<Field>, _>>()?;
+                let tokenizer_manager = TokenizerManager::default();
+                for &field in &fields {
+                    let field_entry = self.schema.get_field_entry(field);
+                    let tokenizer_opt = match *field_entry.field_type() {
+                        FieldType::Str(ref text_options) => text_options
+                            .get_indexing_options()
+                            .map(|indexing_options| indexing_options.tokenizer()),
+                        _ => None,
+                    };
+                    if let Some(tokenizer) = tokenizer_opt {
+                        if tokenizer_manager.get(tokenizer).is_none() {
+                            return Err(invalid_query(
+                                path,
+                                &format!(
+                                    "The field {:?} uses the tokenizer {:?}, \
+                                     which is not built in",
+                                    field_entry.name(),
+                                    tokenizer
+                                ),
+                            ));
+                        }
+                    }
+                }
+                let mut more_like_this_query =
+                    MoreLikeThisQuery::new(source, fields, tokenizer_manager);
+                if let Some(min_doc_freq) = min_doc_freq {
+                    more_like_this_query.set_min_doc_freq(min_doc_freq);
+                }
+                if let Some(max_doc_freq) = max_doc_freq {
+                    more_like_this_query.set_max_doc_freq(max_doc_freq);
+                }
+                more_like_this_query.set_min_term_len(min_term_len);
+                if let Some(max_query_terms) = max_query_terms {
+                    more_like_this_query.set_max_query_terms(max_query_terms);
+                }
+                Box::new(more_like_this_query)
+            }
+            QueryDsl::SpanTerm { .. }
+            | QueryDsl::SpanPrefix { .. }
+            | QueryDsl::SpanNear { .. }
+            | QueryDsl::SpanOr { .. }
+            | QueryDsl::SpanNot { .. }
+            | QueryDsl::SpanFirst { .. } => self.span_query(path, query_dsl)?,
+            QueryDsl::GeoDistance {
+                ref field,
+                origin,
+                distance,
+            } => {
+                let field = self.field(&format!("{}.geo_distance", path), field)?;
+                Box::new(GeoDistanceQuery::new(field, origin, distance))
+            }
+            QueryDsl::GeoBoundingBox {
+                ref field,
+                top_left,
+                bottom_right,
+            } => {
+                let path = &format!("{}.geo_bounding_box", path);
+                if top_left.lat() < bottom_right.lat() {
+                    return Err(invalid_query(
+                        path,
+                        "The top left corner is below the bottom right corner",
+                    ));
+                }
+                let field = self.field(path, field)?;
+                Box::new(GeoBoundingBoxQuery::new(field, top_left, bottom_right))
+            }
+            QueryDsl::ToParentBlockJoin {
+                ref query,
+                ref parent_filter,
+                score_mode,
+            } => {
+                let path = format!("{}.to_parent_block_join", path);
+                Box::new(ToParentBlockJoinQuery::new(
+                    self.query(&format!("{}.query", path), query)?,
+                    self.query(&format!("{}.parent_filter", path), parent_filter)?,
+                    score_mode,
+                ))
+            }
+            QueryDsl::ToChildBlockJoin {
+                ref query,
+                ref parent_filter,
+            } => {
+                let path = format!("{}.to_child_block_join", path);
+                Box::new(ToChildBlockJoinQuery::new(
+                    self.query(&format!("{}.query", path), query)?,
+                    self.query(&format!("{}.parent_filter", path), parent_filter)?,
+                ))
+            }
+        })
+    }
+}
+
+fn map_bound<TFrom, TTo, Transform: Fn(TFrom) -> TTo>(
+    bound: Bound<TFrom>,
+    transform: Transform,
+) -> Bound<TTo> {
+    match bound {
+        Bound::Included(val) => Bound::Included(transform(val)),
+        Bound::Excluded(val) => Bound::Excluded(transform(val)),
+        Bound::Unbounded => Bound::Unbounded,
+    }
+}
+
+impl QueryDsl {
+    /// Parses a `QueryDsl` from its JSON representation.
+    pub fn from_json(json: &str) -> Result<QueryDsl, QueryDslError> {
+        let json_value: JsonValue =
+            serde_json::from_str(json).map_err(|err| QueryDslError::InvalidJson {
+                path: "$".to_string(),
+                message: err.to_string(),
+            })?;
+        serde_json::from_value(json_value.clone())
+            .map_err(|err| locate_json_error(&json_value, "$", err))
+    }
+
+    /// Serializes the `QueryDsl` into its JSON representation.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("Query DSL serialization should never fail.")
+    }
+
+    /// Converts the `QueryDsl` into a `Query`, resolving
+    /// the fields and their values against the schema.
+    pub fn to_query(&self, schema: &Schema) -> Result<Box<Query>, QueryDslError> {
+        QueryBuilder { schema }.query("$", self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{QueryDsl, QueryDslError};
+    use collector::tests::matching_docs;
+    use query::{BooleanQuery, Occur, Query, RangeQuery, TermQuery};
+    use schema::{IndexRecordOption, Schema, SchemaBuilder, Term};
+    use schema::{TextFieldIndexing, TextOptions};
+    use schema::{FAST, INT_INDEXED, STRING, TEXT};
+    use serde_json::{self, Value as JsonValue};
+    use Index;
+
+    fn create_schema() -> Schema {
+        let mut schema_builder = SchemaBuilder::new();
+        schema_builder.add_text_field("title", TEXT);
+        schema_builder.add_text_field("id", STRING);
+        schema_builder.add_u64_field("year", INT_INDEXED);
+        schema_builder.add_i64_field("temperature", INT_INDEXED | FAST);
+        schema_builder.add_facet_field("category");
+        schema_builder.add_geo_point_field("location");
+        schema_builder.build()
+    }
+
+    fn assert_round_trip(schema: &Schema, json: JsonValue) {
+        let query_dsl = QueryDsl::from_json(&json.to_string()).unwrap();
+        let query = query_dsl.to_query(schema).unwrap();
+        let query_dsl_round_trip = query.to_dsl(schema).unwrap();
+        assert_eq!(query_dsl_round_trip, query_dsl);
+        let json_round_trip: JsonValue =
+            serde_json::from_str(&query_dsl_round_trip.to_json()).unwrap();
+        assert_eq!(json_round_trip, json);
+    }
+
+    #[test]
+    fn test_query_dsl_round_trip() {
+        let schema = create_schema();
+        for json in vec![
+            json!({"all": {}}),
+            json!({"term": {"field": "year", "value": 1984}}),
+            json!({"term": {"field": "temperature", "value": -3}}),
+            json!({"term": {"field": "category", "value": "/fiction/classics"}}),
+            json!({"term": {"field": "title", "value": "diary", "index_record_option": "basic"}}),
+            json!({"term_set": {"terms": {"id": ["a", "b"], "year": [1984]}}}),
+            json!({"phrase": {"field": "title", "terms": ["part", "time"], "slop": 1}}),
+            json!({"multi_phrase": {"field": "title", "terms": [["new", "ny"], ["york"]]}}),
+            json!({"phrase_prefix": {"field": "title", "terms": ["my"], "prefix": "dia"}}),
+            json!({
+                "phrase_prefix": {
+                    "field": "title",
+                    "terms": ["a", "secret"],
+                    "prefix": "dia",
+                    "slop": 1,
+                    "max_expansions": 10
+                }
+            }),
+            json!({"prefix": {"field": "title", "prefix": "dia", "max_expansions": 10}}),
+            json!({
+                "fuzzy": {
+                    "field": "title",
+                    "value": "diary",
+                    "distance": 1,
+                    "transpositions": true,
+                    "prefix_length": 2
+                }
+            }),
+            json!({"regex": {"field": "title", "pattern": "d[ai]{2}ry"}}),
+            json!({"wildcard": {"field": "title", "pattern": "d?ary*"}}),
+            json!({"range": {"field": "year", "gte": 1960, "lt": 1970}}),
+            json!({"range": {"field": "temperature", "gt": -10}}),
+            json!({"range": {"field": "id", "gte": "a", "lte": "c"}}),
+            json!({
+                "range": {
+                    "field": "temperature",
+                    "lt": 0,
+                    "strategy": "fast_field",
+                    "max_expansions": 10
+                }
+            }),
+            json!({"exists": {"field": "location"}}),
+            json!({
+                "bool": {
+                    "must": [{"term": {"field": "title", "value": "diary"}}],
+                    "should": [{"all": {}}],
+                    "must_not": [{"term": {"field": "id", "value": "x"}}],
+                    "filter": [{"range": {"field": "year", "lte": 2000}}]
+                }
+            }),
+            json!({
+                "bool": {
+                    "should": [
+                        {"term": {"field": "title", "value": "diary"}},
+                        {"term": {"field": "title", "value": "novel"}}
+                    ],
+                    "minimum_should_match": "75%"
+                }
+            }),
+            json!({"bool": {"should": [{"all": {}}], "minimum_should_match": 1}}),
+            json!({"boost": {"query": {"all": {}}, "boost": 2.0}}),
+            json!({"constant_score": {"query": {"all": {}}, "score": 3.0}}),
+            json!({
+                "disjunction_max": {
+                    "queries": [
+                        {"term": {"field": "title", "value": "diary"}},
+                        {"term": {"field": "id", "value": "diary"}}
+                    ],
+                    "tie_breaker": 0.5
+                }
+            }),
+            json!({
+                "function_score": {
+                    "query": {"term": {"field": "title", "value": "diary"}},
+                    "function": {
+                        "field_value_factor": {
+                            "field": "temperature",
+                            "factor": 2.0,
+                            "modifier": "log1p"
+                        }
+                    },
+                    "combine_mode": "sum"
+                }
+            }),
+            json!({
+                "function_score": {
+                    "query": {"all": {}},
+                    "function": {
+                        "decay": {
+                            "field": "temperature",
+                            "decay_function": "gauss",
+                            "origin": 20.0,
+                            "scale": 5.0,
+                            "offset": 1.0,
+                            "decay": 0.5
+                        }
+                    }
+                }
+            }),
+            json!({
+                "function_score": {
+                    "query": {"all": {}},
+                    "function": {
+                        "geo_decay": {
+                            "field": "location",
+                            "decay_function": "linear",
+                            "origin": {"lat": 48.8566, "lon": 2.3522},
+                            "scale": 1000.0,
+                            "offset": 0.0,
+                            "decay": 0.5
+                        }
+                    },
+                    "combine_mode": "replace"
+                }
+            }),
+            json!({"more_like_this": {"fields": ["title"], "text": "a secret diary"}}),
+            json!({
+                "more_like_this": {
+                    "fields": ["title", "id"],
+                    "document": [0, 3],
+                    "min_doc_freq": 2,
+                    "max_doc_freq": 100,
+                    "min_term_len": 3,
+                    "max_query_terms": 10
+                }
+            }),
+            json!({"span_term": {"field": "title", "value": "diary"}}),
+            json!({"span_prefix": {"field": "title", "prefix": "dia", "max_expansions": 10}}),
+            json!({
+                "span_near": {
+                    "clauses": [
+                        {"span_term": {"field": "title", "value": "secret"}},
+                        {"span_or": {"clauses": [
+                            {"span_term": {"field": "title", "value": "diary"}},
+                            {"span_prefix": {"field": "title", "prefix": "note"}}
+                        ]}}
+                    ],
+                    "slop": 2,
+                    "in_order": true
+                }
+            }),
+            json!({
+                "span_not": {
+                    "include": {"span_term": {"field": "title", "value": "diary"}},
+                    "exclude": {"span_term": {"field": "title", "value": "secret"}}
+                }
+            }),
+            json!({
+                "span_first": {
+                    "query": {"span_term": {"field": "title", "value": "diary"}},
+                    "end": 3
+                }
+            }),
+            json!({
+                "geo_distance": {
+                    "field": "location",
+                    "origin": {"lat": 48.8566, "lon": 2.3522},
+                    "distance": 10000.0
+                }
+            }),
+            json!({
+                "geo_bounding_box": {
+                    "field": "location",
+                    "top_left": {"lat": 60.0, "lon": -10.0},
+                    "bottom_right": {"lat": 40.0, "lon": 20.0}
+                }
+            }),
+            json!({
+                "to_parent_block_join": {
+                    "query": {"term": {"field": "id", "value": "red"}},
+                    "parent_filter": {"term": {"field": "id", "value": "product"}},
+                    "score_mode": "avg"
+                }
+            }),
+            json!({
+                "to_child_block_join": {
+                    "query": {"term": {"field": "id", "value": "shirt"}},
+                    "parent_filter": {"term": {"field": "id", "value": "product"}}
+                }
+            }),
+        ] {
+            assert_round_trip(&schema, json);
+        }
+    }
+
+    #[test]
+    fn test_query_dsl_errors() {
+        let schema = create_schema();
+        let parse_error = |json: JsonValue| QueryDsl::from_json(&json.to_string()).unwrap_err();
+        let query_error = |json: JsonValue| {
+            QueryDsl::from_json(&json.to_string())
+                .unwrap()
+                .to_query(&schema)
+                .err()
+                .unwrap()
+        };
+        assert_matches!(
+            QueryDsl::from_json("{\"all\": "),
+            Err(QueryDslError::InvalidJson { .. })
+        );
+        {
+            let err = parse_error(json!({
+                "bool": {
+                    "must": [
+                        {"all": {}},
+                        {"boost": {"query": {"term": {"field": "title"}}, "boost": 2.0}}
+                    ]
+                }
+            }));
+            assert_matches!(err, QueryDslError::InvalidJson { .. });
+            assert_eq!(err.path(), "$.bool.must[1].boost.query.term");
+        }
+        {
+            let err = parse_error(json!({"unknown": {}}));
+            assert_eq!(err.path(), "$.unknown");
+        }
+        {
+            let err = query_error(json!({
+                "bool": {"should": [{"all": {}}, {"term": {"field": "author", "value": "x"}}]}
+            }));
+            assert_eq!(
+                err,
+                QueryDslError::FieldDoesNotExist {
+                    path: "$.bool.should[1].term".to_string(),
+                    field_name: "author".to_string(),
+                }
+            );
+        }
+        {
+            let err = query_error(json!({
+                "boost": {"query": {"range": {"field": "year", "gte": "1984"}}, "boost": 2.0}
+            }));
+            assert_matches!(err, QueryDslError::InvalidValue { .. });
+            assert_eq!(err.path(), "$.boost.query.range");
+        }
+        {
+            let err = query_error(json!({"range": {"field": "year", "gt": 1, "gte": 2}}));
+            assert_matches!(err, QueryDslError::InvalidQuery { .. });
+            assert_eq!(err.path(), "$.range");
+        }
+        {
+            let err = query_error(json!({"phrase": {"field": "title", "terms": ["single"]}}));
+            assert_matches!(err, QueryDslError::InvalidQuery { .. });
+            assert_eq!(err.path(), "$.phrase");
+        }
+        {
+            let err = query_error(json!({
+                "span_near": {
+                    "clauses": [
+                        {"span_term": {"field": "title", "value": "diary"}},
+                        {"term": {"field": "title", "value": "secret"}}
+                    ]
+                }
+            }));
+            assert_matches!(err, QueryDslError::InvalidQuery { .. });
+            assert_eq!(err.path(), "$.span_near.clauses[1]");
+        }
+        {
+            let err = query_error(json!({
+                "span_or": {
+                    "clauses": [
+                        {"span_term": {"field": "title", "value": "diary"}},
+                        {"span_term": {"field": "id", "value": "secret"}}
+                    ]
+                }
+            }));
+            assert_matches!(err, QueryDslError::InvalidQuery { .. });
+            assert_eq!(err.path(), "$.span_or.clauses");
+        }
+        {
+            let err = query_error(json!({"span_or": {"clauses": []}}));
+            assert_matches!(err, QueryDslError::InvalidQuery { .. });
+            assert_eq!(err.path(), "$.span_or.clauses");
+        }
+        {
+            let err = query_error(json!({"more_like_this": {"fields": ["title"]}}));
+            assert_matches!(err, QueryDslError::InvalidQuery { .. });
+            assert_eq!(err.path(), "$.more_like_this");
+        }
+        {
+            let mut schema_builder = SchemaBuilder::new();
+            let text_options = TextOptions::default().set_indexing_options(
+                TextFieldIndexing::default().set_tokenizer("custom_tokenizer"),
+            );
+            schema_builder.add_text_field("title", text_options);
+            let schema = schema_builder.build();
+            let json = json!({"bool": {"must": [
+                {"more_like_this": {"fields": ["title"], "text": "a secret diary"}}
+            ]}});
+            let query_dsl = QueryDsl::from_json(&json.to_string()).unwrap();
+            let err = query_dsl.to_query(&schema).err().unwrap();
+            assert_matches!(err, QueryDslError::InvalidQuery { .. });
+            assert_eq!(err.path(), "$.bool.must[0].more_like_this");
+        }
+        for &(decay_function, scale, offset, decay) in &[
+            ("gauss", 5.0, 0.0, 0.0),
+            ("linear", 5.0, 0.0, 1.0),
+            ("exp", 0.0, 0.0, 0.5),
+            ("exp", 5.0, -1.0, 0.5),
+        ] {
+            let err = query_error(json!({
+                "function_score": {
+                    "query": {"all": {}},
+                    "function": {
+                        "decay": {
+                            "field": "temperature",
+                            "decay_function": decay_function,
+                            "origin": 20.0,
+                            "scale": scale,
+                            "offset": offset,
+                            "decay": decay
+                        }
+                    }
+                }
+            }));
+            assert_matches!(err, QueryDslError::InvalidQuery { .. });
+            assert_eq!(err.path(), "$.function_score.function");
+        }
+    }
+
+    #[test]
+    fn test_query_dsl_search() {
+        let schema = create_schema();
+        let title = schema.get_field("title").unwrap();
+        let year = schema.get_field("year").unwrap();
+        let index = Index::create_in_ram(schema.clone());
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 40_000_000).unwrap();
+            index_writer.add_document(doc!(title => "a diary", year => 1960u64));
+            index_writer.add_document(doc!(title => "another diary", year => 1990u64));
+            index_writer.add_document(doc!(title => "a novel", year => 1965u64));
+            index_writer.commit().unwrap();
+        }
+        index.load_searchers().unwrap();
+        let searcher = index.searcher();
+        let query_dsl = QueryDsl::from_json(
+            r#"{
+                "bool": {
+                    "must": [{"term": {"field": "title", "value": "diary"}}],
+                    "filter": [{"range": {"field": "year", "gte": 1950, "lt": 1970}}]
+                }
+            }"#,
+        )
+        .unwrap();
+        let query = BooleanQuery::from(vec![
+            (
+                Occur::Must,
+                Box::new(TermQuery::new(
+                    Term::from_field_text(title, "diary"),
+                    IndexRecordOption::WithFreqs,
+                )) as Box<Query>,
+            ),
+            (
+                Occur::Filter,
+                Box::new(RangeQuery::new_u64(year, 1950..1970)),
+            ),
+        ]);
+        assert_eq!(query.to_dsl(&schema).unwrap(), query_dsl);
+        assert_eq!(
+            matching_docs(&searcher, &*query_dsl.to_query(&schema).unwrap()),
+            vec![0]
+        );
+        assert_eq!(matching_docs(&searcher, &query), vec![0]);
+    }
+}
@@ -1,16 +1,23 @@
 use query::Occur;
-use schema::Term;
+use schema::{Field, Term, Type};
+use std::collections::Bound;
 use std::fmt;
+use Score;
 
 #[derive(Clone)]
 pub enum LogicalLiteral {
     Term(Term),
-    Phrase(Vec<Term>),
+    /// Terms, and slop.
+    Phrase(Vec<Term>, u32),
+    /// Field, value type, lower and upper bounds.
+    Range(Field, Type, Bound<Term>, Bound<Term>),
 }
 
 #[derive(Clone)]
 pub enum LogicalAST {
     Clause(Vec<(Occur, LogicalAST)>),
+    /// Disjuncts, and tie breaker.
+    DisjunctionMax(Vec<LogicalAST>, Score),
     Leaf(Box<LogicalLiteral>),
 }
 
@@ -19,6 +26,7 @@ fn occur_letter(occur: Occur) -> &'static str {
         Occur::Must => "+",
         Occur::MustNot => "-",
         Occur::Should => "",
+        Occur::Filter => "#",
     }
 }
 
@@ -38,6 +46,16 @@ impl fmt::Debug for LogicalAST {
                 }
                 Ok(())
             }
+            LogicalAST::DisjunctionMax(ref disjuncts, _) => {
+                formatter.write_str("dismax(")?;
+                for (i, disjunct) in disjuncts.iter().enumerate() {
+                    if i > 0 {
+                        formatter.write_str(" ")?;
+                    }
+                    write!(formatter, "{:?}", disjunct)?;
+                }
+                formatter.write_str(")")
+            }
             LogicalAST::Leaf(ref literal) => write!(formatter, "{:?}", literal),
         }
     }
@@ -53,7 +71,26 @@ impl fmt::Debug for LogicalLiteral {
     fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
         match *self {
             LogicalLiteral::Term(ref term) => write!(formatter, "{:?}", term),
-            LogicalLiteral::Phrase(ref terms) => write!(formatter, "\"{:?}\"", terms),
+            LogicalLiteral::Phrase(ref terms, slop) => {
+                write!(formatter, "\"{:?}\"", terms)?;
+                if slop > 0 {
+                    write!(formatter, "~{}", slop)?;
+                }
+                Ok(())
+            }
+            LogicalLiteral::Range(_, _, ref lower, ref upper) => {
+                match *lower {
+                    Bound::Included(ref term) => write!(formatter, "[{:?}", term)?,
+                    Bound::Excluded(ref term) => write!(formatter, "{{{:?}", term)?,
+                    Bound::Unbounded => write!(formatter, "{{*")?,
+                }
+                write!(formatter, " TO ")?;
+                match *upper {
+                    Bound::Included(ref term) => write!(formatter, "{:?}]", term),
+                    Bound::Excluded(ref term) => write!(formatter, "{:?}}}", term),
+                    Bound::Unbounded => write!(formatter, "*}}"),
+                }
+            }
         }
     }
 }
@@ -3,5 +3,6 @@ mod query_parser;
 mod user_input_ast;
 
 pub mod logical_ast;
+pub use self::query_parser::GrammarMode;
 pub use self::query_parser::QueryParser;
 pub use self::query_parser::QueryParserError;
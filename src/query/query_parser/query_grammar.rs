@@ -2,34 +2,53 @@ use super::user_input_ast::*;
 use combine::char::*;
 use combine::*;
 
+fn field<I>(input: I) -> ParseResult<String, I>
+where
+    I: Stream<Item = char>,
+{
+    (
+        letter(),
+        many(satisfy(|c: char| c.is_alphanumeric() || c == '_')),
+    ).map(|(s1, s2): (char, String)| format!("{}{}", s1, s2))
+        .parse_stream(input)
+}
+
 fn literal<I>(input: I) -> ParseResult<UserInputAST, I>
 where
     I: Stream<Item = char>,
 {
     let term_val = || {
-        let word = many1(satisfy(|c: char| c.is_alphanumeric()));
-        let phrase = (char('"'), many1(satisfy(|c| c != '"')), char('"')).map(|(_, s, _)| s);
+        let word = many1(satisfy(|c: char| c.is_alphanumeric())).map(|s: String| (s, 0u32));
+        let slop = (char('~'), many1(digit())).map(|(_, slop): (char, String)| {
+            // an absurdly large slop is as good as the largest one.
+            slop.parse::<u32>().unwrap_or(u32::max_value())
+        });
+        let phrase = (
+            char('"'),
+            many1(satisfy(|c| c != '"')),
+            char('"'),
+            optional(slop),
+        ).map(|(_, s, _, slop): (_, String, _, Option<u32>)| (s, slop.unwrap_or(0u32)));
         phrase.or(word)
     };
 
     let negative_numbers = (char('-'), many1(satisfy(|c: char| c.is_numeric())))
-        .map(|(s1, s2): (char, String)| format!("{}{}", s1, s2));
-
-    let field = (
-        letter(),
-        many(satisfy(|c: char| c.is_alphanumeric() || c == '_')),
-    ).map(|(s1, s2): (char, String)| format!("{}{}", s1, s2));
+        .map(|(s1, s2): (char, String)| (format!("{}{}", s1, s2), 0u32));
 
     let term_val_with_field = negative_numbers.or(term_val());
 
     let term_query =
-        (field, char(':'), term_val_with_field).map(|(field_name, _, phrase)| UserInputLiteral {
-            field_name: Some(field_name),
-            phrase,
+        (parser(field), char(':'), term_val_with_field).map(|(field_name, _, (phrase, slop))| {
+            UserInputLiteral {
+                field_name: Some(field_name),
+                phrase,
+                slop,
+            }
         });
-    let term_default_field = term_val().map(|phrase| UserInputLiteral {
+    let term_default_field = term_val().map(|(phrase, slop)| UserInputLiteral {
         field_name: None,
         phrase,
+        slop,
     });
     try(term_query)
         .or(term_default_field)
@@ -37,6 +56,68 @@ where
         .parse_stream(input)
 }
 
+/// Parses a range, as in `[a TO b]`, `{a TO *}` or `>=a`,
+/// optionally preceded by a field name, as in `age:>=18`.
+///
+/// Square brackets include their bound, curly brackets exclude it,
+/// and `*` leaves the range unbounded.
+fn range<I>(input: I) -> ParseResult<UserInputAST, I>
+where
+    I: Stream<Item = char>,
+{
+    let range_term_val = || {
+        let word = many1(satisfy(|c: char| {
+            c.is_alphanumeric() || c == '-' || c == '.' || c == '_'
+        }));
+        let quoted =
+            (char('"'), many(satisfy(|c| c != '"')), char('"')).map(|(_, s, _): (_, String, _)| s);
+        quoted.or(word)
+    };
+    let bound_val = || char('*').map(|_| None).or(range_term_val().map(Some));
+    let lower_bound = (one_of("[{".chars()), spaces(), bound_val()).map(
+        |(boundary, _, val): (char, _, Option<String>)| match val {
+            Some(val) if boundary == '[' => UserInputBound::Inclusive(val),
+            Some(val) => UserInputBound::Exclusive(val),
+            None => UserInputBound::Unbounded,
+        },
+    );
+    let upper_bound = (bound_val(), spaces(), one_of("]}".chars())).map(
+        |(val, _, boundary): (Option<String>, _, char)| match val {
+            Some(val) if boundary == ']' => UserInputBound::Inclusive(val),
+            Some(val) => UserInputBound::Exclusive(val),
+            None => UserInputBound::Unbounded,
+        },
+    );
+    let bounds = (
+        lower_bound,
+        skip_many1(space()),
+        string("TO"),
+        skip_many1(space()),
+        upper_bound,
+    ).map(|(lower, _, _, _, upper)| (lower, upper));
+    let comparison = (
+        try(string(">="))
+            .or(try(string("<=")))
+            .or(string(">"))
+            .or(string("<")),
+        range_term_val(),
+    ).map(|(comparator, val): (&str, String)| match comparator {
+        ">=" => (UserInputBound::Inclusive(val), UserInputBound::Unbounded),
+        ">" => (UserInputBound::Exclusive(val), UserInputBound::Unbounded),
+        "<=" => (UserInputBound::Unbounded, UserInputBound::Inclusive(val)),
+        _ => (UserInputBound::Unbounded, UserInputBound::Exclusive(val)),
+    });
+    (
+        optional(try((parser(field), char(':')))),
+        bounds.or(comparison),
+    ).map(|(field_opt, (lower, upper))| UserInputAST::Range {
+        field_name: field_opt.map(|(field_name, _)| field_name),
+        lower,
+        upper,
+    })
+        .parse_stream(input)
+}
+
 fn leaf<I>(input: I) -> ParseResult<UserInputAST, I>
 where
     I: Stream<Item = char>,
@@ -45,25 +126,135 @@ where
         .map(|(_, expr)| UserInputAST::Not(Box::new(expr)))
         .or((char('+'), parser(leaf)).map(|(_, expr)| UserInputAST::Must(Box::new(expr))))
         .or((char('('), parser(parse_to_ast), char(')')).map(|(_, expr, _)| expr))
+        .or(try(parser(range)))
         .or(parser(literal))
         .parse_stream(input)
 }
 
+fn into_clause(subqueries: Vec<UserInputAST>) -> UserInputAST {
+    if subqueries.len() == 1 {
+        subqueries.into_iter().next().unwrap()
+    } else {
+        UserInputAST::Clause(subqueries.into_iter().map(Box::new).collect())
+    }
+}
+
 pub fn parse_to_ast<I>(input: I) -> ParseResult<UserInputAST, I>
 where
     I: Stream<Item = char>,
 {
     sep_by(parser(leaf), spaces())
-        .map(|subqueries: Vec<UserInputAST>| {
-            if subqueries.len() == 1 {
-                subqueries.into_iter().next().unwrap()
+        .map(into_clause)
+        .parse_stream(input)
+}
+
+fn and_operator<I>(input: I) -> ParseResult<&'static str, I>
+where
+    I: Stream<Item = char>,
+{
+    try(string("AND").skip(not_followed_by(alpha_num())))
+        .or(try(string("&&")))
+        .parse_stream(input)
+}
+
+fn or_operator<I>(input: I) -> ParseResult<&'static str, I>
+where
+    I: Stream<Item = char>,
+{
+    try(string("OR").skip(not_followed_by(alpha_num())))
+        .or(try(string("||")))
+        .parse_stream(input)
+}
+
+fn not_operator<I>(input: I) -> ParseResult<&'static str, I>
+where
+    I: Stream<Item = char>,
+{
+    try(string("NOT").skip(not_followed_by(alpha_num()))).parse_stream(input)
+}
+
+/// Same as `leaf`, except that `NOT` negates the leaf it precedes,
+/// and that the boolean operators are not parsed as words.
+fn boolean_leaf<I>(input: I) -> ParseResult<UserInputAST, I>
+where
+    I: Stream<Item = char>,
+{
+    let operator = parser(and_operator)
+        .or(parser(or_operator))
+        .or(parser(not_operator));
+    (parser(not_operator), spaces(), parser(boolean_leaf))
+        .map(|(_, _, expr)| UserInputAST::Not(Box::new(expr)))
+        .or((char('-'), parser(boolean_leaf)).map(|(_, expr)| UserInputAST::Not(Box::new(expr))))
+        .or((char('+'), parser(boolean_leaf)).map(|(_, expr)| UserInputAST::Must(Box::new(expr))))
+        .or((char('('), parser(parse_to_boolean_ast), char(')')).map(|(_, expr, _)| expr))
+        .or(try(parser(range)))
+        .or((not_followed_by(operator), parser(literal)).map(|(_, expr)| expr))
+        .parse_stream(input)
+}
+
+/// Parses leaves separated by `AND` or `&&`.
+fn conjunction<I>(input: I) -> ParseResult<UserInputAST, I>
+where
+    I: Stream<Item = char>,
+{
+    let and_operand = (
+        try((spaces(), parser(and_operator))),
+        spaces(),
+        parser(boolean_leaf),
+    )
+        .map(|(_, _, expr)| expr);
+    (parser(boolean_leaf), many(and_operand))
+        .map(|(first, others): (UserInputAST, Vec<UserInputAST>)| {
+            if others.is_empty() {
+                first
             } else {
-                UserInputAST::Clause(subqueries.into_iter().map(Box::new).collect())
+                let operands = Some(first).into_iter().chain(others);
+                UserInputAST::And(operands.map(Box::new).collect())
             }
         })
         .parse_stream(input)
 }
 
+/// Parses conjunctions separated by `OR` or `||`.
+fn disjunction<I>(input: I) -> ParseResult<UserInputAST, I>
+where
+    I: Stream<Item = char>,
+{
+    let or_operand = (
+        try((spaces(), parser(or_operator))),
+        spaces(),
+        parser(conjunction),
+    )
+        .map(|(_, _, expr)| expr);
+    (parser(conjunction), many(or_operand))
+        .map(|(first, others): (UserInputAST, Vec<UserInputAST>)| {
+            if others.is_empty() {
+                first
+            } else {
+                let operands = Some(first).into_iter().chain(others);
+                UserInputAST::Or(operands.map(Box::new).collect())
+            }
+        })
+        .parse_stream(input)
+}
+
+/// Parses a query in which `AND`, `OR` and `NOT` (or `&&` and `||`
+/// for the first two) are boolean operators.
+///
+/// From the highest to the lowest precedence, the query is made of:
+/// - leaves, possibly negated by `NOT`, `-`, or made required by `+`,
+/// - conjunctions of leaves, separated by `AND`,
+/// - disjunctions of conjunctions, separated by `OR`,
+/// - clauses of disjunctions, separated by spaces, as in `parse_to_ast`.
+pub fn parse_to_boolean_ast<I>(input: I) -> ParseResult<UserInputAST, I>
+where
+    I: Stream<Item = char>,
+{
+    sep_by(parser(disjunction), spaces())
+        .map(into_clause)
+        .parse_stream(input)
+}
+
 #[cfg(test)]
 mod test {
 
@@ -79,6 +270,12 @@ mod test {
         assert!(parse_to_ast(query).is_err());
     }
 
+    fn test_parse_boolean_query_to_ast_helper(query: &str, expected: &str) {
+        let query = parse_to_boolean_ast(query).unwrap().0;
+        let query_str = format!("{:?}", query);
+        assert_eq!(query_str, expected);
+    }
+
     #[test]
     fn test_parse_query_to_ast() {
         test_parse_query_to_ast_helper("+(a b) +d", "(+((\"a\" \"b\")) +(\"d\"))");
@@ -91,6 +288,64 @@ mod test {
         test_parse_query_to_ast_helper("-abc:toto", "-(abc:\"toto\")");
         test_parse_query_to_ast_helper("abc:a b", "(abc:\"a\" \"b\")");
         test_parse_query_to_ast_helper("abc:\"a b\"", "abc:\"a b\"");
+        test_parse_query_to_ast_helper("abc:\"a b\"~2", "abc:\"a b\"~2");
+        test_parse_query_to_ast_helper("\"a b\"~2 c", "(\"a b\"~2 \"c\")");
         test_is_parse_err("abc +    ");
     }
+
+    #[test]
+    fn test_parse_query_to_ast_range() {
+        test_parse_query_to_ast_helper("price:[10 TO 100]", "price:[\"10\" TO \"100\"]");
+        test_parse_query_to_ast_helper("price:{10 TO 100]", "price:{\"10\" TO \"100\"]");
+        test_parse_query_to_ast_helper("price:[10 TO 100}", "price:[\"10\" TO \"100\"}");
+        test_parse_query_to_ast_helper("date:{a TO *}", "date:{\"a\" TO *}");
+        test_parse_query_to_ast_helper("date:[* TO 2018-01-01]", "date:{* TO \"2018-01-01\"]");
+        test_parse_query_to_ast_helper("[-5 TO 5]", "[\"-5\" TO \"5\"]");
+        test_parse_query_to_ast_helper("name:[\"a b\" TO c]", "name:[\"a b\" TO \"c\"]");
+        test_parse_query_to_ast_helper("age:>=18", "age:[\"18\" TO *}");
+        test_parse_query_to_ast_helper("age:>18", "age:{\"18\" TO *}");
+        test_parse_query_to_ast_helper("age:<=18", "age:{* TO \"18\"]");
+        test_parse_query_to_ast_helper("age:<18", "age:{* TO \"18\"}");
+        test_parse_query_to_ast_helper(
+            "+age:>=18 -price:[10 TO 100] a",
+            "(+(age:[\"18\" TO *}) -(price:[\"10\" TO \"100\"]) \"a\")",
+        );
+        for &query in &["price:[10 100]", "price:[10 TO 100"] {
+            let (_, remaining) = parse_to_ast(query).unwrap();
+            assert!(!remaining.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_parse_query_to_ast_boolean_operators() {
+        test_parse_query_to_ast_helper("a AND b", "(\"a\" \"AND\" \"b\")");
+        test_parse_boolean_query_to_ast_helper("a AND b", "(\"a\" AND \"b\")");
+        test_parse_boolean_query_to_ast_helper("a && b", "(\"a\" AND \"b\")");
+        test_parse_boolean_query_to_ast_helper("a OR b || c", "(\"a\" OR \"b\" OR \"c\")");
+        test_parse_boolean_query_to_ast_helper("a OR b AND c", "(\"a\" OR (\"b\" AND \"c\"))");
+        test_parse_boolean_query_to_ast_helper("a AND b OR c", "((\"a\" AND \"b\") OR \"c\")");
+        test_parse_boolean_query_to_ast_helper(
+            "a AND NOT b OR -c",
+            "((\"a\" AND -(\"b\")) OR -(\"c\"))",
+        );
+        test_parse_boolean_query_to_ast_helper(
+            "rust AND (tantivy OR lucene) NOT java",
+            "((\"rust\" AND (\"tantivy\" OR \"lucene\")) -(\"java\"))",
+        );
+        test_parse_boolean_query_to_ast_helper(
+            "+title:a OR b c",
+            "((+(title:\"a\") OR \"b\") \"c\")",
+        );
+        test_parse_boolean_query_to_ast_helper(
+            "ANDROID ORACLE NOTE",
+            "(\"ANDROID\" \"ORACLE\" \"NOTE\")",
+        );
+        test_parse_boolean_query_to_ast_helper(
+            "age:>=18 AND title:\"a b\"",
+            "(age:[\"18\" TO *} AND title:\"a b\")",
+        );
+        assert!(parse_to_boolean_ast("a AND").is_err());
+        assert!(parse_to_boolean_ast("a OR ").is_err());
+        assert!(parse_to_boolean_ast("NOT").is_err());
+    }
 }
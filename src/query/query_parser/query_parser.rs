@@ -1,18 +1,24 @@
 use super::logical_ast::*;
-use super::query_grammar::parse_to_ast;
+use super::query_grammar::{parse_to_ast, parse_to_boolean_ast};
 use super::user_input_ast::*;
 use core::Index;
 use query::BooleanQuery;
+use query::DisjunctionMaxQuery;
+use query::MinimumShouldMatch;
 use query::Occur;
 use query::PhraseQuery;
 use query::Query;
+use query::RangeQuery;
 use query::TermQuery;
 use schema::IndexRecordOption;
+use schema::TextFieldIndexing;
 use schema::{Field, Schema};
-use schema::{FieldType, Term};
+use schema::{FieldType, Term, Type};
+use std::collections::Bound;
 use std::num::ParseIntError;
 use std::str::FromStr;
 use tokenizer::TokenizerManager;
+use Score;
 
 /// Possible error that may happen when parsing a query.
 #[derive(Debug, PartialEq, Eq)]
@@ -39,6 +45,9 @@ pub enum QueryParserError {
     /// The tokenizer for the given field is unknown
     /// The two argument strings are the name of the field, the name of the tokenizer
     UnknownTokenizer(String, String),
+    /// A range query was requested for a field that is not
+    /// a `u64`, `i64` or `str` field.
+    RangeNotSupported(String),
 }
 
 impl From<ParseIntError> for QueryParserError {
@@ -47,6 +56,15 @@ impl From<ParseIntError> for QueryParserError {
     }
 }
 
+/// Syntax of the queries accepted by the `QueryParser`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum GrammarMode {
+    /// `AND`, `OR` and `NOT` are searched as regular words. This is the default.
+    Simple,
+    /// `AND`, `OR` and `NOT` are boolean operators.
+    BooleanOperators,
+}
+
 /// Tantivy's Query parser
 ///
 /// The language covered by the current parser is extremely simple.
@@ -75,10 +93,36 @@ impl From<ParseIntError> for QueryParserError {
 ///
 /// * must terms: By prepending a term by a `+`, a term can be made required for the search.
 ///
+/// * phrase terms: Quoted terms become phrase searches on fields that have positions indexed.
+///   e.g., `title:"Barack Obama"` will only find documents that have "barack" immediately
+///   followed by "obama".
+///   A slop can be appended to a phrase to allow for some distance between its terms.
+///   e.g. `"barack obama"~2` also matches "barack hussein obama".
+///
+/// * range terms: Ranges over `u64`, `i64` and `str` fields.
+///   Square brackets include their bound, curly brackets exclude it,
+///   and `*` leaves a side of the range open.
+///   e.g. `price:[10 TO 100]`, `price:{10 TO 100}` or `title:{apple TO *}`.
+///   Comparisons are shorthands for open-ended ranges.
+///   e.g. `age:>=18` is equivalent to `age:[18 TO *}`.
+///   The bounds of ranges over text fields are processed by the
+///   tokenizer of the field, hence `title:[Apple TO Cherry]` matches `banana`.
+///
+/// * boolean operators: After calling `.set_grammar_mode(GrammarMode::BooleanOperators)`,
+///   `AND` (or `&&`), `OR` (or `||`) and `NOT` combine the terms they surround.
+///   `NOT` binds tighter than `AND`, which binds tighter than `OR`, and
+///   parentheses can be used for grouping.
+///   e.g. `rust AND (tantivy OR lucene) NOT java`.
+///   Terms that are not separated by an operator are still composed
+///   as described above, and `+` and `-` keep their meaning.
+///
 pub struct QueryParser {
     schema: Schema,
     default_fields: Vec<Field>,
     conjunction_by_default: bool,
+    grammar_mode: GrammarMode,
+    default_fields_tie_breaker: Option<Score>,
+    minimum_should_match: Option<MinimumShouldMatch>,
     tokenizer_manager: TokenizerManager,
 }
 
@@ -97,6 +141,9 @@ impl QueryParser {
             default_fields,
             tokenizer_manager,
             conjunction_by_default: false,
+            grammar_mode: GrammarMode::Simple,
+            default_fields_tie_breaker: None,
+            minimum_should_match: None,
         }
     }
 
@@ -117,6 +164,43 @@ impl QueryParser {
         self.conjunction_by_default = true;
     }
 
+    /// Sets the syntax of the queries.
+    ///
+    /// By default, the query `happy AND tax` searches for the words
+    /// `happy`, `and` and `tax`. After calling
+    /// `.set_grammar_mode(GrammarMode::BooleanOperators)`, it only
+    /// matches the documents containing both `happy` and `tax`.
+    pub fn set_grammar_mode(&mut self, grammar_mode: GrammarMode) {
+        self.grammar_mode = grammar_mode;
+    }
+
+    /// Combine the default fields using a `DisjunctionMaxQuery`.
+    ///
+    /// By default, a term without any field specified is searched in all of the
+    /// default fields, and the resulting queries are combined in a `BooleanQuery`.
+    /// The scores over the different fields then add up, and documents
+    /// containing the term in all of the fields get over-rewarded.
+    ///
+    /// After calling `.set_default_fields_disjunction_max(tie_breaker)`,
+    /// the score is the maximum score over the default fields, plus the
+    /// scores over the other default fields multiplied by the `tie_breaker`.
+    pub fn set_default_fields_disjunction_max(&mut self, tie_breaker: Score) {
+        self.default_fields_tie_breaker = Some(tie_breaker);
+    }
+
+    /// Sets the minimum number of optional clauses of the query
+    /// that the documents must match.
+    ///
+    /// For instance, after calling
+    /// `.set_minimum_should_match(MinimumShouldMatch::Absolute(2))`,
+    /// `happy tax payer` only matches the documents containing at least
+    /// two of the three terms.
+    ///
+    /// It only applies to the top-level clauses of the query.
+    pub fn set_minimum_should_match(&mut self, minimum_should_match: MinimumShouldMatch) {
+        self.minimum_should_match = Some(minimum_should_match);
+    }
+
     /// Parse a query
     ///
     /// Note that `parse_query` returns an error if the input
@@ -128,17 +212,40 @@ impl QueryParser {
     /// Implementing a lenient mode for this query parser is tracked
     /// in [Issue 5](https://github.com/fulmicoton/tantivy/issues/5)
     pub fn parse_query(&self, query: &str) -> Result<Box<Query>, QueryParserError> {
-        let logical_ast = self.parse_query_to_logical_ast(query)?;
-        Ok(convert_to_query(logical_ast))
+        let user_input_ast = self.parse_query_to_user_input_ast(query)?;
+        // the expansion of a single term over the default fields
+        // is not a clause of the user query.
+        let is_user_clause = match user_input_ast {
+            UserInputAST::Clause(_) | UserInputAST::And(_) | UserInputAST::Or(_) => true,
+            _ => false,
+        };
+        let logical_ast = self.compute_logical_ast(user_input_ast)?;
+        match (logical_ast, self.minimum_should_match) {
+            (LogicalAST::Clause(clause), Some(minimum_should_match)) if is_user_clause => {
+                let mut boolean_query = convert_clause_to_boolean_query(clause);
+                boolean_query.set_minimum_should_match(minimum_should_match);
+                Ok(Box::new(boolean_query))
+            }
+            (logical_ast, _) => Ok(convert_to_query(logical_ast)),
+        }
     }
 
     /// Parse the user query into an AST.
     fn parse_query_to_logical_ast(&self, query: &str) -> Result<LogicalAST, QueryParserError> {
-        let (user_input_ast, _remaining) =
-            parse_to_ast(query).map_err(|_| QueryParserError::SyntaxError)?;
+        let user_input_ast = self.parse_query_to_user_input_ast(query)?;
         self.compute_logical_ast(user_input_ast)
     }
 
+    fn parse_query_to_user_input_ast(&self, query: &str) -> Result<UserInputAST, QueryParserError> {
+        let parse_result = match self.grammar_mode {
+            GrammarMode::Simple => parse_to_ast(query),
+            GrammarMode::BooleanOperators => parse_to_boolean_ast(query),
+        };
+        let (user_input_ast, _remaining) =
+            parse_result.map_err(|_| QueryParserError::SyntaxError)?;
+        Ok(user_input_ast)
+    }
+
     fn resolve_field_name(&self, field_name: &str) -> Result<Field, QueryParserError> {
         self.schema
             .get_field(field_name)
@@ -159,6 +266,7 @@ impl QueryParser {
         &self,
         field: Field,
         phrase: &str,
+        slop: u32,
     ) -> Result<Option<LogicalLiteral>, QueryParserError> {
         let field_entry = self.schema.get_field_entry(field);
         let field_type = field_entry.field_type();
@@ -179,20 +287,7 @@ impl QueryParser {
             }
             FieldType::Str(ref str_options) => {
                 if let Some(option) = str_options.get_indexing_options() {
-                    let mut tokenizer = self.tokenizer_manager.get(option.tokenizer()).ok_or_else(
-                        || {
-                            QueryParserError::UnknownTokenizer(
-                                field_entry.name().to_string(),
-                                option.tokenizer().to_string(),
-                            )
-                        },
-                    )?;
-                    let mut terms: Vec<Term> = Vec::new();
-                    let mut token_stream = tokenizer.token_stream(phrase);
-                    token_stream.process(&mut |token| {
-                        let term = Term::from_field_text(field, &token.text);
-                        terms.push(term);
-                    });
+                    let terms = self.tokenize(field, option, phrase)?;
                     if terms.is_empty() {
                         Ok(None)
                     } else if terms.len() == 1 {
@@ -204,7 +299,7 @@ impl QueryParser {
                         let field_type = field_entry.field_type();
                         if let Some(index_record_option) = field_type.get_index_record_option() {
                             if index_record_option.has_positions() {
-                                Ok(Some(LogicalLiteral::Phrase(terms)))
+                                Ok(Some(LogicalLiteral::Phrase(terms, slop)))
                             } else {
                                 let fieldname = self.schema.get_field_name(field).to_string();
                                 Err(QueryParserError::FieldDoesNotHavePositionsIndexed(
@@ -227,13 +322,90 @@ impl QueryParser {
                 let term = Term::from_field_text(field, phrase);
                 Ok(Some(LogicalLiteral::Term(term)))
             }
-            FieldType::Bytes => {
+            FieldType::Bytes | FieldType::GeoPoint => {
                 let field_name = self.schema.get_field_name(field).to_string();
                 Err(QueryParserError::FieldNotIndexed(field_name))
             }
         }
     }
 
+    fn compute_logical_ast_for_range(
+        &self,
+        field: Field,
+        lower: &UserInputBound,
+        upper: &UserInputBound,
+    ) -> Result<LogicalLiteral, QueryParserError> {
+        let field_entry = self.schema.get_field_entry(field);
+        let field_type = field_entry.field_type();
+        if !field_type.is_indexed() {
+            let field_name = field_entry.name().to_string();
+            return Err(QueryParserError::FieldNotIndexed(field_name));
+        }
+        match *field_type {
+            FieldType::I64(_) | FieldType::U64(_) | FieldType::Str(_) => {}
+            _ => {
+                let field_name = field_entry.name().to_string();
+                return Err(QueryParserError::RangeNotSupported(field_name));
+            }
+        }
+        let make_term = |word: &str| -> Result<Term, QueryParserError> {
+            match *field_type {
+                FieldType::I64(_) => Ok(Term::from_field_i64(field, i64::from_str(word)?)),
+                FieldType::U64(_) => Ok(Term::from_field_u64(field, u64::from_str(word)?)),
+                FieldType::Str(ref str_options) => {
+                    // bounds are normalized like the indexed terms. A bound
+                    // that is not tokenized into a single token is used as is.
+                    if let Some(option) = str_options.get_indexing_options() {
+                        let mut terms = self.tokenize(field, option, word)?;
+                        if terms.len() == 1 {
+                            return Ok(terms.pop().unwrap());
+                        }
+                    }
+                    Ok(Term::from_field_text(field, word))
+                }
+                _ => Ok(Term::from_field_text(field, word)),
+            }
+        };
+        let make_bound = |bound: &UserInputBound| -> Result<Bound<Term>, QueryParserError> {
+            match *bound {
+                UserInputBound::Inclusive(ref word) => Ok(Bound::Included(make_term(word)?)),
+                UserInputBound::Exclusive(ref word) => Ok(Bound::Excluded(make_term(word)?)),
+                UserInputBound::Unbounded => Ok(Bound::Unbounded),
+            }
+        };
+        Ok(LogicalLiteral::Range(
+            field,
+            field_type.value_type(),
+            make_bound(lower)?,
+            make_bound(upper)?,
+        ))
+    }
+
+    /// Returns the terms of `text`, tokenized by the tokenizer of the field.
+    fn tokenize(
+        &self,
+        field: Field,
+        indexing_options: &TextFieldIndexing,
+        text: &str,
+    ) -> Result<Vec<Term>, QueryParserError> {
+        let tokenizer = self
+            .tokenizer_manager
+            .get(indexing_options.tokenizer())
+            .ok_or_else(|| {
+                QueryParserError::UnknownTokenizer(
+                    self.schema.get_field_name(field).to_string(),
+                    indexing_options.tokenizer().to_string(),
+                )
+            })?;
+        let mut terms: Vec<Term> = Vec::new();
+        let mut token_stream = tokenizer.token_stream(text);
+        token_stream.process(&mut |token| {
+            let term = Term::from_field_text(field, &token.text);
+            terms.push(term);
+        });
+        Ok(terms)
+    }
+
     fn default_occur(&self) -> Occur {
         if self.conjunction_by_default {
             Occur::Must
@@ -249,13 +421,13 @@ impl QueryParser {
         match user_input_ast {
             UserInputAST::Clause(sub_queries) => {
                 let default_occur = self.default_occur();
-                let mut logical_sub_queries: Vec<(Occur, LogicalAST)> = Vec::new();
-                for sub_query in sub_queries {
-                    let (occur, sub_ast) = self.compute_logical_ast_with_occur(*sub_query)?;
-                    let new_occur = compose_occur(default_occur, occur);
-                    logical_sub_queries.push((new_occur, sub_ast));
-                }
-                Ok((Occur::Should, LogicalAST::Clause(logical_sub_queries)))
+                self.compute_logical_ast_for_clause(default_occur, sub_queries)
+            }
+            UserInputAST::And(sub_queries) => {
+                self.compute_logical_ast_for_clause(Occur::Must, sub_queries)
+            }
+            UserInputAST::Or(sub_queries) => {
+                self.compute_logical_ast_for_clause(Occur::Should, sub_queries)
             }
             UserInputAST::Not(subquery) => {
                 let (occur, logical_sub_queries) = self.compute_logical_ast_with_occur(*subquery)?;
@@ -265,79 +437,160 @@ impl QueryParser {
                 let (occur, logical_sub_queries) = self.compute_logical_ast_with_occur(*subquery)?;
                 Ok((compose_occur(Occur::Must, occur), logical_sub_queries))
             }
+            UserInputAST::Range {
+                field_name,
+                lower,
+                upper,
+            } => {
+                let mut asts: Vec<LogicalAST> = Vec::new();
+                for field in self.resolve_field_name_opt(&field_name)? {
+                    let ast = self.compute_logical_ast_for_range(field, &lower, &upper)?;
+                    asts.push(LogicalAST::Leaf(Box::new(ast)));
+                }
+                Ok((Occur::Should, self.compose_field_asts(asts)?))
+            }
             UserInputAST::Leaf(literal) => {
-                let term_phrases: Vec<(Field, String)> = match literal.field_name {
-                    Some(ref field_name) => {
-                        let field = self.resolve_field_name(field_name)?;
-                        vec![(field, literal.phrase.clone())]
-                    }
-                    None => {
-                        if self.default_fields.is_empty() {
-                            return Err(QueryParserError::NoDefaultFieldDeclared);
-                        } else {
-                            self.default_fields
-                                .iter()
-                                .map(|default_field| (*default_field, literal.phrase.clone()))
-                                .collect::<Vec<(Field, String)>>()
-                        }
-                    }
-                };
                 let mut asts: Vec<LogicalAST> = Vec::new();
-                for (field, phrase) in term_phrases {
-                    if let Some(ast) = self.compute_logical_ast_for_leaf(field, &phrase)? {
+                for field in self.resolve_field_name_opt(&literal.field_name)? {
+                    if let Some(ast) =
+                        self.compute_logical_ast_for_leaf(field, &literal.phrase, literal.slop)?
+                    {
                         asts.push(LogicalAST::Leaf(Box::new(ast)));
                     }
                 }
-                let result_ast = if asts.is_empty() {
-                    // this should never happen
-                    return Err(QueryParserError::SyntaxError);
-                } else if asts.len() == 1 {
-                    asts[0].clone()
+                Ok((Occur::Should, self.compose_field_asts(asts)?))
+            }
+        }
+    }
+
+    /// Composes the occur of each of the subqueries with `occur`.
+    fn compute_logical_ast_for_clause(
+        &self,
+        occur: Occur,
+        sub_queries: Vec<Box<UserInputAST>>,
+    ) -> Result<(Occur, LogicalAST), QueryParserError> {
+        let mut logical_sub_queries: Vec<(Occur, LogicalAST)> = Vec::new();
+        for sub_query in sub_queries {
+            let (sub_occur, sub_ast) = self.compute_logical_ast_with_occur(*sub_query)?;
+            logical_sub_queries.push((compose_occur(occur, sub_occur), sub_ast));
+        }
+        Ok((Occur::Should, LogicalAST::Clause(logical_sub_queries)))
+    }
+
+    /// Returns the field with the given name, or the
+    /// default fields if no field name is specified.
+    fn resolve_field_name_opt(
+        &self,
+        field_name_opt: &Option<String>,
+    ) -> Result<Vec<Field>, QueryParserError> {
+        match *field_name_opt {
+            Some(ref field_name) => Ok(vec![self.resolve_field_name(field_name)?]),
+            None => {
+                if self.default_fields.is_empty() {
+                    Err(QueryParserError::NoDefaultFieldDeclared)
                 } else {
-                    LogicalAST::Clause(asts.into_iter().map(|ast| (Occur::Should, ast)).collect())
-                };
-                Ok((Occur::Should, result_ast))
+                    Ok(self.default_fields.clone())
+                }
             }
         }
     }
+
+    /// Combines the asts of the same user input over different fields.
+    fn compose_field_asts(&self, asts: Vec<LogicalAST>) -> Result<LogicalAST, QueryParserError> {
+        if asts.is_empty() {
+            // this should never happen
+            Err(QueryParserError::SyntaxError)
+        } else if asts.len() == 1 {
+            Ok(asts.into_iter().next().unwrap())
+        } else if let Some(tie_breaker) = self.default_fields_tie_breaker {
+            Ok(LogicalAST::DisjunctionMax(asts, tie_breaker))
+        } else {
+            Ok(LogicalAST::Clause(
+                asts.into_iter().map(|ast| (Occur::Should, ast)).collect(),
+            ))
+        }
+    }
 }
 
 /// Compose two occur values.
 fn compose_occur(left: Occur, right: Occur) -> Occur {
     match left {
         Occur::Should => right,
-        Occur::Must => {
+        Occur::Must => match right {
+            Occur::MustNot => Occur::MustNot,
+            Occur::Filter => Occur::Filter,
+            _ => Occur::Must,
+        },
+        Occur::MustNot => {
             if right == Occur::MustNot {
-                Occur::MustNot
-            } else {
                 Occur::Must
+            } else {
+                Occur::MustNot
             }
         }
-        Occur::MustNot => {
+        Occur::Filter => {
             if right == Occur::MustNot {
-                Occur::Must
-            } else {
                 Occur::MustNot
+            } else {
+                Occur::Filter
             }
         }
     }
 }
 
+fn map_bound<'a, TFrom, TTo, TTransform: Fn(&'a TFrom) -> TTo>(
+    bound: &'a Bound<TFrom>,
+    transform: TTransform,
+) -> Bound<TTo> {
+    match *bound {
+        Bound::Included(ref from_val) => Bound::Included(transform(from_val)),
+        Bound::Excluded(ref from_val) => Bound::Excluded(transform(from_val)),
+        Bound::Unbounded => Bound::Unbounded,
+    }
+}
+
 fn convert_literal_to_query(logical_literal: LogicalLiteral) -> Box<Query> {
     match logical_literal {
         LogicalLiteral::Term(term) => Box::new(TermQuery::new(term, IndexRecordOption::WithFreqs)),
-        LogicalLiteral::Phrase(terms) => Box::new(PhraseQuery::new(terms)),
+        LogicalLiteral::Phrase(terms, slop) => {
+            let mut phrase_query = PhraseQuery::new(terms);
+            phrase_query.set_slop(slop);
+            Box::new(phrase_query)
+        }
+        LogicalLiteral::Range(field, value_type, lower, upper) => match value_type {
+            Type::I64 => Box::new(RangeQuery::new_i64_bounds(
+                field,
+                map_bound(&lower, Term::get_i64),
+                map_bound(&upper, Term::get_i64),
+            )),
+            Type::U64 => Box::new(RangeQuery::new_u64_bounds(
+                field,
+                map_bound(&lower, Term::get_u64),
+                map_bound(&upper, Term::get_u64),
+            )),
+            _ => Box::new(RangeQuery::new_str_bounds(
+                field,
+                map_bound(&lower, Term::text),
+                map_bound(&upper, Term::text),
+            )),
+        },
     }
 }
 
+fn convert_clause_to_boolean_query(clause: Vec<(Occur, LogicalAST)>) -> BooleanQuery {
+    let occur_subqueries = clause
+        .into_iter()
+        .map(|(occur, subquery)| (occur, convert_to_query(subquery)))
+        .collect::<Vec<_>>();
+    BooleanQuery::from(occur_subqueries)
+}
+
 fn convert_to_query(logical_ast: LogicalAST) -> Box<Query> {
     match logical_ast {
-        LogicalAST::Clause(clause) => {
-            let occur_subqueries = clause
-                .into_iter()
-                .map(|(occur, subquery)| (occur, convert_to_query(subquery)))
-                .collect::<Vec<_>>();
-            Box::new(BooleanQuery::from(occur_subqueries))
+        LogicalAST::Clause(clause) => Box::new(convert_clause_to_boolean_query(clause)),
+        LogicalAST::DisjunctionMax(disjuncts, tie_breaker) => {
+            let disjunct_queries = disjuncts.into_iter().map(convert_to_query).collect();
+            Box::new(DisjunctionMaxQuery::new(disjunct_queries, tie_breaker))
         }
         LogicalAST::Leaf(logical_literal) => convert_literal_to_query(*logical_literal),
     }
@@ -346,9 +599,11 @@ fn convert_to_query(logical_ast: LogicalAST) -> Box<Query> {
 #[cfg(test)]
 mod test {
     use super::super::logical_ast::*;
+    use super::GrammarMode;
     use super::QueryParser;
     use super::QueryParserError;
-    use query::Query;
+    use collector::tests::matching_docs;
+    use query::{MinimumShouldMatch, Query};
     use schema::Field;
     use schema::{IndexRecordOption, TextFieldIndexing, TextOptions};
     use schema::{SchemaBuilder, Term, INT_INDEXED, STORED, STRING, TEXT};
@@ -366,6 +621,7 @@ mod test {
         schema_builder.add_text_field("notindexed_u64", STORED);
         schema_builder.add_text_field("notindexed_i64", STORED);
         schema_builder.add_text_field("nottokenized", STRING);
+        schema_builder.add_facet_field("facet");
         let schema = schema_builder.build();
         let default_fields = vec![title, text];
         let tokenizer_manager = TokenizerManager::default();
@@ -511,6 +767,12 @@ mod test {
              Term([0, 0, 0, 0, 98])]\"",
             false,
         );
+        test_parse_query_to_logical_ast_helper(
+            "title:\"a b\"~4",
+            "\"[Term([0, 0, 0, 0, 97]), \
+             Term([0, 0, 0, 0, 98])]\"~4",
+            false,
+        );
     }
 
     #[test]
@@ -583,6 +845,299 @@ mod test {
         );
     }
 
+    #[test]
+    pub fn test_parse_query_default_fields_disjunction_max() {
+        let mut query_parser = make_query_parser();
+        query_parser.set_default_fields_disjunction_max(0.1f32);
+        let logical_ast = query_parser.parse_query_to_logical_ast("toto").unwrap();
+        assert_eq!(
+            format!("{:?}", logical_ast),
+            "dismax(Term([0, 0, 0, 0, 116, 111, 116, 111]) \
+             Term([0, 0, 0, 1, 116, 111, 116, 111]))"
+        );
+        let logical_ast = query_parser
+            .parse_query_to_logical_ast("title:toto")
+            .unwrap();
+        assert_eq!(
+            format!("{:?}", logical_ast),
+            "Term([0, 0, 0, 0, 116, 111, 116, 111])"
+        );
+        assert!(query_parser.parse_query("toto +titi").is_ok());
+    }
+
+    #[test]
+    pub fn test_parse_query_range() {
+        let query_parser = make_query_parser();
+        let field = |field_name: &str| query_parser.schema.get_field(field_name).unwrap();
+        let (signed, unsigned) = (field("signed"), field("unsigned"));
+        let (title, text, nottokenized) = (field("title"), field("text"), field("nottokenized"));
+        let logical_ast_str = |query: &str| {
+            let logical_ast = query_parser.parse_query_to_logical_ast(query).unwrap();
+            format!("{:?}", logical_ast)
+        };
+        assert_eq!(
+            logical_ast_str("unsigned:[10 TO 100]"),
+            format!(
+                "[{:?} TO {:?}]",
+                Term::from_field_u64(unsigned, 10),
+                Term::from_field_u64(unsigned, 100)
+            )
+        );
+        assert_eq!(
+            logical_ast_str("signed:{-5 TO *}"),
+            format!("{{{:?} TO *}}", Term::from_field_i64(signed, -5))
+        );
+        assert_eq!(
+            logical_ast_str("signed:<=-5"),
+            format!("{{* TO {:?}]", Term::from_field_i64(signed, -5))
+        );
+        assert_eq!(
+            logical_ast_str("nottokenized:>Abc"),
+            format!("{{{:?} TO *}}", Term::from_field_text(nottokenized, "Abc"))
+        );
+        assert_eq!(
+            logical_ast_str("[a TO b}"),
+            format!(
+                "([{:?} TO {:?}}} [{:?} TO {:?}}})",
+                Term::from_field_text(title, "a"),
+                Term::from_field_text(title, "b"),
+                Term::from_field_text(text, "a"),
+                Term::from_field_text(text, "b")
+            )
+        );
+        assert_matches!(
+            query_parser.parse_query("unsigned:[a TO 10]"),
+            Err(QueryParserError::ExpectedInt(_))
+        );
+        assert_matches!(
+            query_parser.parse_query("notindexed_u64:>=10"),
+            Err(QueryParserError::FieldNotIndexed(_))
+        );
+        assert_eq!(
+            query_parser.parse_query("facet:[* TO *]").err().unwrap(),
+            QueryParserError::RangeNotSupported("facet".to_string())
+        );
+    }
+
+    #[test]
+    pub fn test_parse_query_range_search() {
+        let mut schema_builder = SchemaBuilder::default();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let price = schema_builder.add_u64_field("price", INT_INDEXED);
+        let temperature = schema_builder.add_i64_field("temperature", INT_INDEXED);
+        let index = Index::create_in_ram(schema_builder.build());
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 40_000_000).unwrap();
+            for i in 0u64..20u64 {
+                index_writer.add_document(doc!(
+                    title => if i % 2 == 0 { "apple" } else { "banana" },
+                    price => i * 10,
+                    temperature => i as i64 - 10
+                ));
+            }
+            index_writer.commit().unwrap();
+        }
+        index.load_searchers().unwrap();
+        let searcher = index.searcher();
+        let query_parser = QueryParser::for_index(&index, vec![title]);
+        let parse_matching_docs = |query: &str| {
+            let query = query_parser.parse_query(query).unwrap();
+            matching_docs(&searcher, &*query)
+        };
+        assert_eq!(parse_matching_docs("price:[10 TO 40]"), vec![1, 2, 3, 4]);
+        assert_eq!(parse_matching_docs("price:{10 TO 40}"), vec![2, 3]);
+        assert_eq!(parse_matching_docs("price:[170 TO *]"), vec![17, 18, 19]);
+        assert_eq!(parse_matching_docs("price:<20"), vec![0, 1]);
+        assert_eq!(parse_matching_docs("temperature:>=8"), vec![18, 19]);
+        assert_eq!(
+            parse_matching_docs("temperature:{-12 TO -8]"),
+            vec![0, 1, 2]
+        );
+        assert_eq!(parse_matching_docs("+apple +price:>=150"), vec![16, 18]);
+        assert_eq!(
+            parse_matching_docs("title:{apple TO *}"),
+            (1..20).step_by(2).collect::<Vec<u32>>()
+        );
+        assert_eq!(
+            parse_matching_docs("title:[Apple TO Cherry]"),
+            (0..20).collect::<Vec<u32>>()
+        );
+        assert_eq!(
+            parse_matching_docs("title:{APPLE TO Cherry}"),
+            (1..20).step_by(2).collect::<Vec<u32>>()
+        );
+        assert_eq!(
+            parse_matching_docs("title:[* TO Banana}"),
+            (0..20).step_by(2).collect::<Vec<u32>>()
+        );
+    }
+
+    #[test]
+    pub fn test_parse_query_boolean_operators() {
+        let mut query_parser = make_query_parser();
+        query_parser.set_conjunction_by_default();
+        let logical_ast_str = |query_parser: &QueryParser, query: &str| {
+            format!(
+                "{:?}",
+                query_parser.parse_query_to_logical_ast(query).unwrap()
+            )
+        };
+        assert_eq!(
+            logical_ast_str(&query_parser, "title:a OR title:b"),
+            "(+Term([0, 0, 0, 0, 97]) \
+             +(Term([0, 0, 0, 0, 111, 114]) Term([0, 0, 0, 1, 111, 114])) \
+             +Term([0, 0, 0, 0, 98]))"
+        );
+        query_parser.set_grammar_mode(GrammarMode::BooleanOperators);
+        assert_eq!(
+            logical_ast_str(&query_parser, "title:a OR title:b"),
+            "(Term([0, 0, 0, 0, 97]) Term([0, 0, 0, 0, 98]))"
+        );
+        assert_eq!(
+            logical_ast_str(&query_parser, "title:a || title:b AND NOT title:c"),
+            "(Term([0, 0, 0, 0, 97]) \
+             (+Term([0, 0, 0, 0, 98]) -Term([0, 0, 0, 0, 99])))"
+        );
+        assert_eq!(
+            logical_ast_str(&query_parser, "(title:a OR title:b) title:c"),
+            "(+(Term([0, 0, 0, 0, 97]) Term([0, 0, 0, 0, 98])) +Term([0, 0, 0, 0, 99]))"
+        );
+        assert_eq!(
+            query_parser
+                .parse_query_to_logical_ast("NOT title:a")
+                .err()
+                .unwrap(),
+            QueryParserError::AllButQueryForbidden
+        );
+        assert_eq!(
+            query_parser
+                .parse_query_to_logical_ast("title:a AND")
+                .err()
+                .unwrap(),
+            QueryParserError::SyntaxError
+        );
+    }
+
+    #[test]
+    pub fn test_parse_query_boolean_operators_search() {
+        let mut schema_builder = SchemaBuilder::default();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 40_000_000).unwrap();
+            index_writer.add_document(doc!(title => "rust tantivy"));
+            index_writer.add_document(doc!(title => "rust lucene java"));
+            index_writer.add_document(doc!(title => "java lucene"));
+            index_writer.add_document(doc!(title => "rust and java"));
+            index_writer.commit().unwrap();
+        }
+        index.load_searchers().unwrap();
+        let searcher = index.searcher();
+        let mut query_parser = QueryParser::for_index(&index, vec![title]);
+        let parse_matching_docs = |query_parser: &QueryParser, query: &str| {
+            let query = query_parser.parse_query(query).unwrap();
+            matching_docs(&searcher, &*query)
+        };
+        assert_eq!(
+            parse_matching_docs(&query_parser, "tantivy AND java"),
+            vec![0, 1, 2, 3]
+        );
+        query_parser.set_grammar_mode(GrammarMode::BooleanOperators);
+        assert_eq!(
+            parse_matching_docs(&query_parser, "tantivy AND java"),
+            Vec::<u32>::new()
+        );
+        assert_eq!(
+            parse_matching_docs(&query_parser, "rust && java"),
+            vec![1, 3]
+        );
+        assert_eq!(
+            parse_matching_docs(&query_parser, "rust AND (tantivy OR lucene) NOT java"),
+            vec![0]
+        );
+        assert_eq!(
+            parse_matching_docs(&query_parser, "tantivy OR java AND lucene"),
+            vec![0, 1, 2]
+        );
+        assert_eq!(parse_matching_docs(&query_parser, "java NOT rust"), vec![2]);
+    }
+
+    #[test]
+    pub fn test_parse_query_minimum_should_match() {
+        let mut schema_builder = SchemaBuilder::default();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 40_000_000).unwrap();
+            index_writer.add_document(doc!(title => "happy tax payer"));
+            index_writer.add_document(doc!(title => "happy payer"));
+            index_writer.add_document(doc!(title => "sad tax"));
+            index_writer.add_document(doc!(title => "happy"));
+            index_writer.commit().unwrap();
+        }
+        index.load_searchers().unwrap();
+        let searcher = index.searcher();
+        let mut query_parser = QueryParser::for_index(&index, vec![title]);
+        let parse_matching_docs = |query_parser: &QueryParser, query: &str| {
+            let query = query_parser.parse_query(query).unwrap();
+            matching_docs(&searcher, &*query)
+        };
+        assert_eq!(
+            parse_matching_docs(&query_parser, "happy tax payer"),
+            vec![0, 1, 2, 3]
+        );
+        query_parser.set_minimum_should_match(MinimumShouldMatch::Absolute(2));
+        assert_eq!(
+            parse_matching_docs(&query_parser, "happy tax payer"),
+            vec![0, 1]
+        );
+        assert_eq!(parse_matching_docs(&query_parser, "happy"), vec![0, 1, 3]);
+        query_parser.set_minimum_should_match(MinimumShouldMatch::Percentage(100));
+        assert_eq!(
+            parse_matching_docs(&query_parser, "happy tax payer"),
+            vec![0]
+        );
+        query_parser.set_minimum_should_match(MinimumShouldMatch::Percentage(50));
+        assert_eq!(
+            parse_matching_docs(&query_parser, "+happy tax payer"),
+            vec![0, 1]
+        );
+    }
+
+    #[test]
+    pub fn test_parse_query_minimum_should_match_default_fields() {
+        let mut schema_builder = SchemaBuilder::default();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let body = schema_builder.add_text_field("body", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 40_000_000).unwrap();
+            index_writer.add_document(doc!(title => "happy tax", body => "tax"));
+            index_writer.add_document(doc!(title => "happy", body => "payer"));
+            index_writer.add_document(doc!(title => "sad", body => "happy"));
+            index_writer.commit().unwrap();
+        }
+        index.load_searchers().unwrap();
+        let searcher = index.searcher();
+        let mut query_parser = QueryParser::for_index(&index, vec![title, body]);
+        query_parser.set_minimum_should_match(MinimumShouldMatch::Absolute(2));
+        let parse_matching_docs = |query_parser: &QueryParser, query: &str| {
+            let query = query_parser.parse_query(query).unwrap();
+            matching_docs(&searcher, &*query)
+        };
+        assert_eq!(parse_matching_docs(&query_parser, "happy"), vec![0, 1, 2]);
+        assert_eq!(
+            parse_matching_docs(&query_parser, "happy tax payer"),
+            vec![0, 1]
+        );
+        query_parser.set_default_fields_disjunction_max(0.1f32);
+        assert_eq!(parse_matching_docs(&query_parser, "happy"), vec![0, 1, 2]);
+        assert_eq!(
+            parse_matching_docs(&query_parser, "happy tax payer"),
+            vec![0, 1]
+        );
+    }
+
     #[test]
     pub fn test_parse_query_to_ast_conjunction() {
         test_parse_query_to_logical_ast_helper(
@@ -3,21 +3,57 @@ use std::fmt;
 pub struct UserInputLiteral {
     pub field_name: Option<String>,
     pub phrase: String,
+    pub slop: u32,
 }
 
 impl fmt::Debug for UserInputLiteral {
     fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
         match self.field_name {
-            Some(ref field_name) => write!(formatter, "{}:\"{}\"", field_name, self.phrase),
-            None => write!(formatter, "\"{}\"", self.phrase),
+            Some(ref field_name) => write!(formatter, "{}:\"{}\"", field_name, self.phrase)?,
+            None => write!(formatter, "\"{}\"", self.phrase)?,
+        }
+        if self.slop > 0 {
+            write!(formatter, "~{}", self.slop)?;
+        }
+        Ok(())
+    }
+}
+
+pub enum UserInputBound {
+    Inclusive(String),
+    Exclusive(String),
+    Unbounded,
+}
+
+impl UserInputBound {
+    fn display_lower(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            UserInputBound::Inclusive(ref word) => write!(formatter, "[\"{}\"", word),
+            UserInputBound::Exclusive(ref word) => write!(formatter, "{{\"{}\"", word),
+            UserInputBound::Unbounded => write!(formatter, "{{*"),
+        }
+    }
+
+    fn display_upper(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            UserInputBound::Inclusive(ref word) => write!(formatter, "\"{}\"]", word),
+            UserInputBound::Exclusive(ref word) => write!(formatter, "\"{}\"}}", word),
+            UserInputBound::Unbounded => write!(formatter, "*}}"),
         }
     }
 }
 
 pub enum UserInputAST {
     Clause(Vec<Box<UserInputAST>>),
+    And(Vec<Box<UserInputAST>>),
+    Or(Vec<Box<UserInputAST>>),
     Not(Box<UserInputAST>),
     Must(Box<UserInputAST>),
+    Range {
+        field_name: Option<String>,
+        lower: UserInputBound,
+        upper: UserInputBound,
+    },
     Leaf(Box<UserInputLiteral>),
 }
 
@@ -44,8 +80,34 @@ impl fmt::Debug for UserInputAST {
                 }
                 Ok(())
             }
+            UserInputAST::And(ref subqueries) => display_operands(formatter, subqueries, "AND"),
+            UserInputAST::Or(ref subqueries) => display_operands(formatter, subqueries, "OR"),
             UserInputAST::Not(ref subquery) => write!(formatter, "-({:?})", subquery),
+            UserInputAST::Range {
+                ref field_name,
+                ref lower,
+                ref upper,
+            } => {
+                if let Some(ref field_name) = *field_name {
+                    write!(formatter, "{}:", field_name)?;
+                }
+                lower.display_lower(formatter)?;
+                write!(formatter, " TO ")?;
+                upper.display_upper(formatter)
+            }
             UserInputAST::Leaf(ref subquery) => write!(formatter, "{:?}", subquery),
         }
     }
 }
+
+fn display_operands(
+    formatter: &mut fmt::Formatter,
+    operands: &[Box<UserInputAST>],
+    operator: &str,
+) -> Result<(), fmt::Error> {
+    write!(formatter, "({:?}", &operands[0])?;
+    for operand in &operands[1..] {
+        write!(formatter, " {} {:?}", operator, operand)?;
+    }
+    write!(formatter, ")")
+}
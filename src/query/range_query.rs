@@ -1,17 +1,29 @@
+use byteorder::{BigEndian, ByteOrder};
 use common::BitSet;
+use core::InvertedIndexReader;
 use core::Searcher;
 use core::SegmentReader;
 use error::ErrorKind;
+use fastfield::FastValue;
+use postings::TermInfo;
+use query::automaton_weight::{term_stream_to_terms, DEFAULT_MAX_EXPANSIONS};
+use query::query_dsl::{field_name, max_expansions_to_dsl, term_bytes_to_json};
 use query::BitSetDocSet;
 use query::ConstScorer;
-use query::{Query, Scorer, Weight};
-use schema::Type;
+use query::TermSetQuery;
+use query::{Query, QueryDsl, Scorer, Weight};
+use schema::{Cardinality, FieldType, Schema, Type};
 use schema::{Field, IndexRecordOption, Term};
-use std::collections::Bound;
+use std::collections::{BTreeSet, Bound};
 use std::ops::Range;
 use termdict::{TermDictionary, TermStreamer};
 use Result;
 
+/// When both strategies are available, the fast field is scanned
+/// rather than the postings as soon as the terms within the range
+/// account for more than `max_doc / FAST_FIELD_SCAN_RATIO` postings.
+const FAST_FIELD_SCAN_RATIO: u64 = 4;
+
 fn map_bound<TFrom, Transform: Fn(TFrom) -> Vec<u8>>(
     bound: Bound<TFrom>,
     transform: &Transform,
@@ -24,14 +36,59 @@ fn map_bound<TFrom, Transform: Fn(TFrom) -> Vec<u8>>(
     }
 }
 
+/// Decodes the bound of an int range back into the `u64`
+/// representation of the values used by fast fields.
+fn fast_field_bound(bound: &Bound<Vec<u8>>) -> Bound<u64> {
+    use self::Bound::*;
+    match *bound {
+        Excluded(ref term_val) => Excluded(BigEndian::read_u64(term_val)),
+        Included(ref term_val) => Included(BigEndian::read_u64(term_val)),
+        Unbounded => Unbounded,
+    }
+}
+
+/// Defines how a `RangeQuery` finds the documents within its range.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RangeStrategy {
+    /// Picks the strategy given the options of the field.
+    ///
+    /// If the field is both indexed and a fast field, the choice is made
+    /// for each segment, depending on the number of postings of the
+    /// terms within the range.
+    Auto,
+    /// Iterates over the terms within the range, and reads their postings.
+    ///
+    /// The field is required to be indexed.
+    Terms,
+    /// Scans the fast field values of all of the documents of the segment.
+    ///
+    /// The field is required to be a `u64` or `i64` fast field.
+    FastField,
+}
+
+impl Default for RangeStrategy {
+    fn default() -> RangeStrategy {
+        RangeStrategy::Auto
+    }
+}
+
 /// `RangeQuery` match all documents that have at least one term within a defined range.
 ///
 /// Matched document will all get a constant `Score` of one.
 ///
 /// # Implementation
 ///
-/// The current implement will iterate over the terms within the range
-/// and append all of the document cross into a `BitSet`.
+/// The `RangeQuery` can either iterate over the terms within the range
+/// and append all of the documents crossed into a `BitSet`, or scan the
+/// values of the fast field of `u64` and `i64` fields.
+/// (See [`RangeStrategy`](./enum.RangeStrategy.html))
+///
+/// When [rewritten](./trait.Query.html#method.rewrite), the range is expanded
+/// into the terms of the index it contains. The number of terms the range may
+/// expand to within a segment is then capped.
+/// (1,024 by default. See [`.set_max_expansions(...)`](#method.set_max_expansions).)
+/// If the cap is exceeded, the rewrite fails with a `TooManyTerms` error.
 ///
 /// # Example
 ///
@@ -86,6 +143,8 @@ pub struct RangeQuery {
     value_type: Type,
     left_bound: Bound<Vec<u8>>,
     right_bound: Bound<Vec<u8>>,
+    strategy: RangeStrategy,
+    max_expansions: usize,
 }
 
 impl RangeQuery {
@@ -119,6 +178,8 @@ impl RangeQuery {
             value_type: Type::I64,
             left_bound: map_bound(left_bound, &make_term_val),
             right_bound: map_bound(right_bound, &make_term_val),
+            strategy: RangeStrategy::default(),
+            max_expansions: DEFAULT_MAX_EXPANSIONS,
         }
     }
 
@@ -140,6 +201,8 @@ impl RangeQuery {
             value_type: Type::U64,
             left_bound: map_bound(left_bound, &make_term_val),
             right_bound: map_bound(right_bound, &make_term_val),
+            strategy: RangeStrategy::default(),
+            max_expansions: DEFAULT_MAX_EXPANSIONS,
         }
     }
 
@@ -173,6 +236,8 @@ impl RangeQuery {
             value_type: Type::Str,
             left_bound: map_bound(left, &make_term_val),
             right_bound: map_bound(right, &make_term_val),
+            strategy: RangeStrategy::default(),
+            max_expansions: DEFAULT_MAX_EXPANSIONS,
         }
     }
 
@@ -187,6 +252,35 @@ impl RangeQuery {
             Bound::Excluded(range.end),
         )
     }
+
+    /// Sets the strategy used to find the documents within the range.
+    ///
+    /// By default, the strategy is `RangeStrategy::Auto`.
+    pub fn set_strategy(&mut self, strategy: RangeStrategy) {
+        self.strategy = strategy;
+    }
+
+    /// Sets the maximum number of terms the range is allowed
+    /// to expand to within a segment, when the query is rewritten.
+    pub fn set_max_expansions(&mut self, max_expansions: usize) {
+        self.max_expansions = max_expansions;
+    }
+
+    fn fast_field_weight(&self, field_type: &FieldType) -> Option<FastFieldRangeWeight> {
+        let cardinality_opt = match *field_type {
+            FieldType::U64(ref int_options) | FieldType::I64(ref int_options) => {
+                int_options.get_fastfield_cardinality()
+            }
+            _ => None,
+        };
+        cardinality_opt.map(|cardinality| FastFieldRangeWeight {
+            field: self.field,
+            value_type: self.value_type,
+            cardinality,
+            left_bound: fast_field_bound(&self.left_bound),
+            right_bound: fast_field_bound(&self.right_bound),
+        })
+    }
 }
 
 impl Query for RangeQuery {
@@ -200,18 +294,138 @@ impl Query for RangeQuery {
             );
             bail!(ErrorKind::SchemaError(err_msg))
         }
+        let field_entry = schema.get_field_entry(self.field);
+        let fast_field_weight_opt = self.fast_field_weight(field_entry.field_type());
+        match self.strategy {
+            RangeStrategy::Auto => {}
+            RangeStrategy::Terms => {
+                if !field_entry.is_indexed() {
+                    bail!(ErrorKind::SchemaError(format!(
+                        "Range query on field {:?} requires the field to be indexed",
+                        field_entry.name()
+                    )))
+                }
+                return Ok(Box::new(RangeWeight {
+                    field: self.field,
+                    left_bound: self.left_bound.clone(),
+                    right_bound: self.right_bound.clone(),
+                    fast_field_weight_opt: None,
+                }));
+            }
+            RangeStrategy::FastField => {
+                return match fast_field_weight_opt {
+                    Some(fast_field_weight) => Ok(Box::new(fast_field_weight)),
+                    None => bail!(ErrorKind::SchemaError(format!(
+                        "Range query on field {:?} requires the field to be an int fast field",
+                        field_entry.name()
+                    ))),
+                };
+            }
+        }
+        if !field_entry.is_indexed() {
+            if let Some(fast_field_weight) = fast_field_weight_opt {
+                return Ok(Box::new(fast_field_weight));
+            }
+        }
         Ok(Box::new(RangeWeight {
             field: self.field,
             left_bound: self.left_bound.clone(),
             right_bound: self.right_bound.clone(),
+            fast_field_weight_opt,
         }))
     }
+
+    fn rewrite(self: Box<Self>, searcher: &Searcher) -> Result<Box<Query>> {
+        let field_entry = searcher.schema().get_field_entry(self.field);
+        if field_entry.field_type().value_type() != self.value_type
+            || !field_entry.is_indexed()
+            || self.strategy == RangeStrategy::FastField
+        {
+            // the range is not rewritten, errors are reported by the weight.
+            return Ok(self);
+        }
+        let range_weight = RangeWeight {
+            field: self.field,
+            left_bound: self.left_bound.clone(),
+            right_bound: self.right_bound.clone(),
+            fast_field_weight_opt: None,
+        };
+        let mut terms = BTreeSet::new();
+        for reader in searcher.segment_readers() {
+            let inverted_index = reader.inverted_index(self.field);
+            let term_range = range_weight.term_range(inverted_index.terms());
+            term_stream_to_terms(
+                self.field,
+                term_range,
+                Some(self.max_expansions),
+                &mut terms,
+            )?;
+        }
+        Ok(Box::new(TermSetQuery::new(terms.into_iter().collect())))
+    }
+
+    fn to_dsl(&self, schema: &Schema) -> Result<QueryDsl> {
+        let bound_to_json = |bound: &Bound<Vec<u8>>| match *bound {
+            Bound::Included(ref value_bytes) | Bound::Excluded(ref value_bytes) => {
+                Some(term_bytes_to_json(schema, self.field, value_bytes))
+            }
+            Bound::Unbounded => None,
+        };
+        let is_excluded = |bound: &Bound<Vec<u8>>| match *bound {
+            Bound::Excluded(_) => true,
+            _ => false,
+        };
+        let left = bound_to_json(&self.left_bound);
+        let right = bound_to_json(&self.right_bound);
+        let (gt, gte) = if is_excluded(&self.left_bound) {
+            (left, None)
+        } else {
+            (None, left)
+        };
+        let (lt, lte) = if is_excluded(&self.right_bound) {
+            (right, None)
+        } else {
+            (None, right)
+        };
+        Ok(QueryDsl::Range {
+            field: field_name(schema, self.field),
+            gt,
+            gte,
+            lt,
+            lte,
+            strategy: if self.strategy == RangeStrategy::Auto {
+                None
+            } else {
+                Some(self.strategy)
+            },
+            max_expansions: max_expansions_to_dsl(self.max_expansions),
+        })
+    }
+}
+
+/// Appends all of the documents of the posting list
+/// associated to `term_info` into a `BitSet`.
+fn insert_postings(
+    inverted_index: &InvertedIndexReader,
+    term_info: &TermInfo,
+    doc_bitset: &mut BitSet,
+) {
+    let mut block_segment_postings =
+        inverted_index.read_block_postings_from_terminfo(term_info, IndexRecordOption::Basic);
+    while block_segment_postings.advance() {
+        for &doc in block_segment_postings.docs() {
+            doc_bitset.insert(doc);
+        }
+    }
 }
 
 pub struct RangeWeight {
     field: Field,
     left_bound: Bound<Vec<u8>>,
     right_bound: Bound<Vec<u8>>,
+    // if set, the fast field is used for the segments
+    // in which the range matches too many postings.
+    fast_field_weight_opt: Option<FastFieldRangeWeight>,
 }
 
 impl RangeWeight {
@@ -240,16 +454,91 @@ impl Weight for RangeWeight {
         let inverted_index = reader.inverted_index(self.field);
         let term_dict = inverted_index.terms();
         let mut term_range = self.term_range(term_dict);
-        while term_range.advance() {
-            let term_info = term_range.value();
-            let mut block_segment_postings = inverted_index
-                .read_block_postings_from_terminfo(term_info, IndexRecordOption::Basic);
-            while block_segment_postings.advance() {
-                for &doc in block_segment_postings.docs() {
-                    doc_bitset.insert(doc);
+        if let Some(ref fast_field_weight) = self.fast_field_weight_opt {
+            let max_num_postings = u64::from(max_doc) / FAST_FIELD_SCAN_RATIO;
+            let mut num_postings = 0u64;
+            let mut term_infos = Vec::new();
+            while term_range.advance() {
+                let term_info = term_range.value();
+                num_postings += u64::from(term_info.doc_freq);
+                if num_postings > max_num_postings {
+                    return fast_field_weight.scorer(reader);
+                }
+                term_infos.push(term_info.clone());
+            }
+            for term_info in &term_infos {
+                insert_postings(&*inverted_index, term_info, &mut doc_bitset);
+            }
+        } else {
+            while term_range.advance() {
+                insert_postings(&*inverted_index, term_range.value(), &mut doc_bitset);
+            }
+        }
+        let doc_bitset = BitSetDocSet::from(doc_bitset);
+        Ok(Box::new(ConstScorer::new(doc_bitset)))
+    }
+}
+
+/// `Weight` of a `RangeQuery` scanning the fast field
+/// values of all of the documents of each segment.
+struct FastFieldRangeWeight {
+    field: Field,
+    value_type: Type,
+    cardinality: Cardinality,
+    left_bound: Bound<u64>,
+    right_bound: Bound<u64>,
+}
+
+impl FastFieldRangeWeight {
+    fn contains(&self, val: u64) -> bool {
+        use std::collections::Bound::*;
+        let after_left = match self.left_bound {
+            Included(left) => val >= left,
+            Excluded(left) => val > left,
+            Unbounded => true,
+        };
+        let before_right = match self.right_bound {
+            Included(right) => val <= right,
+            Excluded(right) => val < right,
+            Unbounded => true,
+        };
+        after_left && before_right
+    }
+
+    fn scan<Item: FastValue>(&self, reader: &SegmentReader, doc_bitset: &mut BitSet) -> Result<()> {
+        let max_doc = reader.max_doc();
+        match self.cardinality {
+            Cardinality::SingleValue => {
+                let ff_reader = reader.fast_field_reader::<Item>(self.field)?;
+                for doc in 0..max_doc {
+                    if self.contains(ff_reader.get(doc).to_u64()) {
+                        doc_bitset.insert(doc);
+                    }
+                }
+            }
+            Cardinality::MultiValues => {
+                let ff_reader = reader.multi_fast_field_reader::<Item>(self.field)?;
+                let mut vals = Vec::new();
+                for doc in 0..max_doc {
+                    ff_reader.get_vals(doc, &mut vals);
+                    if vals.iter().any(|val| self.contains(val.to_u64())) {
+                        doc_bitset.insert(doc);
+                    }
                 }
             }
         }
+        Ok(())
+    }
+}
+
+impl Weight for FastFieldRangeWeight {
+    fn scorer(&self, reader: &SegmentReader) -> Result<Box<Scorer>> {
+        let mut doc_bitset = BitSet::with_max_value(reader.max_doc());
+        match self.value_type {
+            Type::U64 => self.scan::<u64>(reader, &mut doc_bitset)?,
+            Type::I64 => self.scan::<i64>(reader, &mut doc_bitset)?,
+            _ => unreachable!("Only int fields can be fast fields"),
+        }
         let doc_bitset = BitSetDocSet::from(doc_bitset);
         Ok(Box::new(ConstScorer::new(doc_bitset)))
     }
@@ -258,11 +547,13 @@ impl Weight for RangeWeight {
 #[cfg(test)]
 mod tests {
 
-    use super::RangeQuery;
+    use super::{RangeQuery, RangeStrategy};
     use collector::CountCollector;
+    use error::ErrorKind;
     use query::Query;
-    use schema::{Document, Field, SchemaBuilder, INT_INDEXED};
-    use std::collections::Bound;
+    use schema::{Cardinality, Document, Field, IntOptions, SchemaBuilder, FAST, INT_INDEXED};
+    use schema::STRING;
+    use std::collections::{BTreeSet, Bound};
     use Index;
     use Result;
 
@@ -361,4 +652,134 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_range_query_strategies() {
+        let mut schema_builder = SchemaBuilder::new();
+        let indexed_fast_field = schema_builder.add_u64_field("indexed_fast", INT_INDEXED | FAST);
+        let fast_field = schema_builder.add_u64_field("fast", FAST);
+        let multi_fast_field = schema_builder.add_i64_field(
+            "multi_fast",
+            IntOptions::default().set_fast(Cardinality::MultiValues),
+        );
+        let schema = schema_builder.build();
+        let index = Index::create_in_ram(schema);
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 40_000_000).unwrap();
+            for i in 0u64..100u64 {
+                let mut doc = Document::new();
+                doc.add_u64(indexed_fast_field, i);
+                doc.add_u64(fast_field, i);
+                doc.add_i64(multi_fast_field, i as i64 - 50);
+                doc.add_i64(multi_fast_field, i as i64 * 2);
+                index_writer.add_document(doc);
+            }
+            index_writer.commit().unwrap();
+        }
+        index.load_searchers().unwrap();
+        let searcher = index.searcher();
+        let count = |range_query: &RangeQuery| {
+            let mut count_collector = CountCollector::default();
+            range_query
+                .search(&*searcher, &mut count_collector)
+                .unwrap();
+            count_collector.count()
+        };
+        let strategies = [
+            RangeStrategy::Auto,
+            RangeStrategy::Terms,
+            RangeStrategy::FastField,
+        ];
+        for &strategy in &strategies {
+            // few postings
+            let mut range_query = RangeQuery::new_u64(indexed_fast_field, 10..13);
+            range_query.set_strategy(strategy);
+            assert_eq!(count(&range_query), 3);
+            // many postings
+            let mut range_query = RangeQuery::new_u64_bounds(
+                indexed_fast_field,
+                Bound::Excluded(10),
+                Bound::Unbounded,
+            );
+            range_query.set_strategy(strategy);
+            assert_eq!(count(&range_query), 89);
+        }
+        {
+            let range_query = RangeQuery::new_u64(fast_field, 10..13);
+            assert_eq!(count(&range_query), 3);
+        }
+        {
+            // either i - 50 or 2 * i are within [-3, 4]
+            let range_query = RangeQuery::new_i64_bounds(
+                multi_fast_field,
+                Bound::Included(-3),
+                Bound::Included(4),
+            );
+            assert_eq!(count(&range_query), 11);
+        }
+        {
+            let mut range_query = RangeQuery::new_u64(fast_field, 10..13);
+            range_query.set_strategy(RangeStrategy::Terms);
+            let err = range_query.weight(&*searcher, false).err().unwrap();
+            assert_matches!(err.kind(), &ErrorKind::SchemaError(_));
+        }
+    }
+
+    #[test]
+    fn test_range_query_fast_field_strategy_requires_fast_field() {
+        let mut schema_builder = SchemaBuilder::new();
+        let int_field = schema_builder.add_u64_field("int", INT_INDEXED);
+        let schema = schema_builder.build();
+        let index = Index::create_in_ram(schema);
+        index.load_searchers().unwrap();
+        let searcher = index.searcher();
+        let mut range_query = RangeQuery::new_u64(int_field, 10..13);
+        range_query.set_strategy(RangeStrategy::FastField);
+        let err = range_query.weight(&*searcher, false).err().unwrap();
+        assert_matches!(err.kind(), &ErrorKind::SchemaError(_));
+    }
+
+    #[test]
+    fn test_range_query_rewrite() {
+        let mut schema_builder = SchemaBuilder::new();
+        let name_field = schema_builder.add_text_field("name", STRING);
+        let year_field = schema_builder.add_u64_field("year", FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 6_000_000).unwrap();
+            for &name in &["a", "b", "c", "d"] {
+                index_writer.add_document(doc!(name_field => name, year_field => 1984u64));
+            }
+            index_writer.commit().unwrap();
+            for &name in &["b", "e"] {
+                index_writer.add_document(doc!(name_field => name, year_field => 1984u64));
+            }
+            index_writer.commit().unwrap();
+        }
+        index.load_searchers().unwrap();
+        let searcher = index.searcher();
+        assert_eq!(searcher.segment_readers().len(), 2);
+        let range_query = Box::new(RangeQuery::new_str(name_field, "b".."e"));
+        let rewritten_query = range_query.rewrite(&*searcher).unwrap();
+        let mut query_terms = BTreeSet::new();
+        rewritten_query.query_terms(&mut query_terms);
+        let terms: Vec<&str> = query_terms.iter().map(|term| term.text()).collect();
+        assert_eq!(terms, vec!["b", "c", "d"]);
+        assert_eq!(rewritten_query.count(&*searcher).unwrap(), 4);
+        // the fast field is not indexed.
+        let range_query = Box::new(RangeQuery::new_u64(year_field, 1980..1990));
+        let rewritten_query = range_query.rewrite(&*searcher).unwrap();
+        let mut query_terms = BTreeSet::new();
+        rewritten_query.query_terms(&mut query_terms);
+        assert!(query_terms.is_empty());
+        assert_eq!(rewritten_query.count(&*searcher).unwrap(), 6);
+        // the cap applies to the terms of each segment.
+        let mut range_query = RangeQuery::new_str(name_field, "b".."f");
+        range_query.set_max_expansions(3);
+        let rewritten_query = Box::new(range_query).rewrite(&*searcher).unwrap();
+        assert_eq!(rewritten_query.count(&*searcher).unwrap(), 5);
+        let mut range_query = RangeQuery::new_str(name_field, "a".."f");
+        range_query.set_max_expansions(3);
+        let err = Box::new(range_query).rewrite(&*searcher).err().unwrap();
+        assert_matches!(err.kind(), &ErrorKind::TooManyTerms(3));
+    }
 }
@@ -0,0 +1,193 @@
+use error::ErrorKind;
+use fst_regex::Regex;
+use query::query_dsl::field_name;
+use query::{AutomatonWeight, Query, QueryDsl, Weight};
+use schema::{Field, Schema, Type};
+use Result;
+use Searcher;
+
+/// A Regex Query matches all of the documents
+/// containing a specific term that matches
+/// a regex pattern.
+///
+/// The regular expression must match the entire term.
+/// For instance, `inv[oi]ce` matches `invoice` but
+/// does not match `invoices`.
+///
+/// Matched documents all get a constant `Score` of one.
+///
+/// # Implementation
+///
+/// The regular expression is compiled into an automaton,
+/// which is then intersected with the term dictionary of each segment.
+/// Wildcard queries (e.g. `.*foo`) are expensive as they force
+/// the visit of a large part of the dictionary.
+///
+/// # Example
+///
+/// ```rust
+/// #[macro_use]
+/// extern crate tantivy;
+/// use tantivy::schema::{SchemaBuilder, TEXT};
+/// use tantivy::{Index, Result};
+/// use tantivy::collector::CountCollector;
+/// use tantivy::query::{Query, RegexQuery};
+///
+/// # fn main() { example().unwrap(); }
+/// fn example() -> Result<()> {
+///     let mut schema_builder = SchemaBuilder::new();
+///     let title = schema_builder.add_text_field("title", TEXT);
+///     let schema = schema_builder.build();
+///     let index = Index::create_in_ram(schema);
+///     {
+///         let mut index_writer = index.writer(3_000_000)?;
+///         index_writer.add_document(doc!(
+///             title => "The Name of the Wind",
+///         ));
+///         index_writer.add_document(doc!(
+///             title => "The Diary of Muadib",
+///         ));
+///         index_writer.add_document(doc!(
+///             title => "A Dairy Cow",
+///         ));
+///         index_writer.add_document(doc!(
+///             title => "The Diary of a Young Girl",
+///         ));
+///         index_writer.commit().unwrap();
+///     }
+///
+///     index.load_searchers()?;
+///     let searcher = index.searcher();
+///
+///     let query = RegexQuery::new(title, "d[ai]{2}ry");
+///     let mut count_collector = CountCollector::default();
+///     query.search(&*searcher, &mut count_collector)?;
+///     assert_eq!(count_collector.count(), 3);
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct RegexQuery {
+    field: Field,
+    regex_pattern: String,
+}
+
+impl RegexQuery {
+    /// Creates a new `RegexQuery` for the given field and pattern.
+    ///
+    /// The pattern is only compiled when the `Weight` object is created.
+    /// If it is invalid, tantivy will return an error at that point.
+    pub fn new(field: Field, regex_pattern: &str) -> RegexQuery {
+        RegexQuery {
+            field,
+            regex_pattern: regex_pattern.to_string(),
+        }
+    }
+
+    fn specialized_weight(&self, searcher: &Searcher) -> Result<AutomatonWeight<Regex>> {
+        let value_type = searcher
+            .schema()
+            .get_field_entry(self.field)
+            .field_type()
+            .value_type();
+        if value_type != Type::Str {
+            let err_msg = format!(
+                "Create a regex query on a field of type {:?}, \
+                 when only fields of type {:?} are supported",
+                value_type,
+                Type::Str
+            );
+            bail!(ErrorKind::SchemaError(err_msg))
+        }
+        let automaton = Regex::new(&self.regex_pattern).map_err(|err| {
+            ErrorKind::InvalidArgument(format!("Invalid regex {:?}: {}", self.regex_pattern, err))
+        })?;
+        Ok(AutomatonWeight::new(self.field, automaton))
+    }
+}
+
+impl Query for RegexQuery {
+    fn weight(&self, searcher: &Searcher, _scoring_enabled: bool) -> Result<Box<Weight>> {
+        Ok(Box::new(self.specialized_weight(searcher)?))
+    }
+
+    fn rewrite(self: Box<Self>, searcher: &Searcher) -> Result<Box<Query>> {
+        let term_set_query = self.specialized_weight(searcher)?.term_set_query(searcher)?;
+        Ok(Box::new(term_set_query))
+    }
+
+    fn to_dsl(&self, schema: &Schema) -> Result<QueryDsl> {
+        Ok(QueryDsl::Regex {
+            field: field_name(schema, self.field),
+            pattern: self.regex_pattern.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::RegexQuery;
+    use collector::tests::matching_docs;
+    use error::ErrorKind;
+    use query::Query;
+    use schema::{Field, SchemaBuilder, STRING};
+    use Index;
+
+    fn create_index(values: &[&'static str]) -> (Index, Field) {
+        let mut schema_builder = SchemaBuilder::new();
+        let reference_field = schema_builder.add_text_field("reference", STRING);
+        let schema = schema_builder.build();
+        let index = Index::create_in_ram(schema);
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 10_000_000).unwrap();
+            for &value in values {
+                index_writer.add_document(doc!(reference_field => value));
+            }
+            index_writer.commit().unwrap();
+        }
+        index.load_searchers().unwrap();
+        (index, reference_field)
+    }
+
+    #[test]
+    pub fn test_regex_query() {
+        let (index, reference_field) = create_index(&[
+            "invoice-123",
+            "invoce-4",
+            "invice-5",
+            "invoice-",
+            "invoices-77",
+        ]);
+        {
+            let regex_query = RegexQuery::new(reference_field, r"inv[oi]ce-\d+");
+            assert_eq!(matching_docs(&index.searcher(), &regex_query), vec![1, 2]);
+        }
+        {
+            let regex_query = RegexQuery::new(reference_field, r"inv[oi]+ce-\d*");
+            assert_eq!(
+                matching_docs(&index.searcher(), &regex_query),
+                vec![0, 1, 2, 3]
+            );
+        }
+        {
+            let regex_query = RegexQuery::new(reference_field, "invoice");
+            assert_eq!(
+                matching_docs(&index.searcher(), &regex_query),
+                Vec::<u32>::new()
+            );
+        }
+        {
+            let regex_query = RegexQuery::new(reference_field, ".*-77");
+            assert_eq!(matching_docs(&index.searcher(), &regex_query), vec![4]);
+        }
+    }
+
+    #[test]
+    pub fn test_regex_query_invalid_pattern() {
+        let (index, reference_field) = create_index(&["invoice-123"]);
+        let searcher = index.searcher();
+        let regex_query = RegexQuery::new(reference_field, "inv(oice");
+        let err = regex_query.weight(&*searcher, false).err().unwrap();
+        assert_matches!(err.kind(), &ErrorKind::InvalidArgument(_));
+    }
+}
@@ -78,3 +78,80 @@ impl ScoreCombiner for SumWithCoordsCombiner {
         self.score
     }
 }
+
+/// Keeps the maximum score of the different scorers,
+/// plus the sum of the other scores multiplied by a `tie_breaker`.
+///
+/// With a `tie_breaker` of `0`, the score is simply the maximum
+/// score. With a `tie_breaker` of `1`, it is the sum of the scores.
+#[derive(Default, Clone, Copy)]
+pub struct DisjunctionMaxCombiner {
+    tie_breaker: Score,
+    // `None` until a first score is combined, as scores may be negative.
+    max: Option<Score>,
+    sum: Score,
+}
+
+impl DisjunctionMaxCombiner {
+    /// Creates a `DisjunctionMaxCombiner` with the given `tie_breaker`.
+    pub fn with_tie_breaker(tie_breaker: Score) -> DisjunctionMaxCombiner {
+        DisjunctionMaxCombiner {
+            tie_breaker,
+            max: None,
+            sum: 0f32,
+        }
+    }
+}
+
+impl ScoreCombiner for DisjunctionMaxCombiner {
+    fn update<TScorer: Scorer>(&mut self, scorer: &mut TScorer) {
+        let score = scorer.score();
+        self.sum += score;
+        self.max = Some(match self.max {
+            Some(max) if max >= score => max,
+            _ => score,
+        });
+    }
+
+    fn clear(&mut self) {
+        self.max = None;
+        self.sum = 0f32;
+    }
+
+    fn score(&self) -> Score {
+        match self.max {
+            Some(max) => max + (self.sum - max) * self.tie_breaker,
+            None => 0f32,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{DisjunctionMaxCombiner, ScoreCombiner};
+    use query::{ConstScorer, VecDocSet};
+    use Score;
+
+    fn combine(score_combiner: &mut DisjunctionMaxCombiner, scores: &[Score]) -> Score {
+        score_combiner.clear();
+        for &score in scores {
+            let mut scorer = ConstScorer::new(VecDocSet::from(vec![0]));
+            scorer.set_score(score);
+            score_combiner.update(&mut scorer);
+        }
+        score_combiner.score()
+    }
+
+    #[test]
+    fn test_disjunction_max_combiner() {
+        let mut score_combiner = DisjunctionMaxCombiner::with_tie_breaker(0.5f32);
+        assert_eq!(combine(&mut score_combiner, &[]), 0f32);
+        assert_eq!(combine(&mut score_combiner, &[1f32, 3f32, 2f32]), 4.5f32);
+        assert_eq!(combine(&mut score_combiner, &[-3f32]), -3f32);
+        assert_eq!(
+            combine(&mut score_combiner, &[-3f32, -1f32, -2f32]),
+            -3.5f32
+        );
+    }
+}
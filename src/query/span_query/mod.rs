@@ -0,0 +1,519 @@
+/*!
+Span queries match intervals of positions within a document.
+
+Unlike other queries, span queries do not only define which documents
+are matched, but also where, in these documents, the matches are located.
+These locations, called spans, are exposed by the [`SpanScorer`](./trait.SpanScorer.html)
+trait, which makes it possible to nest span queries.
+
+For instance, `contract` within 5 positions of `termination`, in order,
+is expressed as a `SpanNearQuery` over two `SpanTermQuery`.
+Replacing the second `SpanTermQuery` by a `SpanPrefixQuery`
+makes it possible to also match `terminated` or `terminates`.
+
+All of the terms of a span query must belong to the same field,
+and this field must have its positions indexed.
+*/
+
+mod span_first_query;
+mod span_near_query;
+mod span_not_query;
+mod span_or_query;
+mod span_prefix_query;
+mod span_term_query;
+
+pub use self::span_first_query::SpanFirstQuery;
+pub use self::span_near_query::SpanNearQuery;
+pub use self::span_not_query::SpanNotQuery;
+pub use self::span_or_query::SpanOrQuery;
+pub use self::span_prefix_query::SpanPrefixQuery;
+pub use self::span_term_query::SpanTermQuery;
+
+use core::Searcher;
+use core::SegmentReader;
+use docset::{DocSet, SkipResult};
+use error::ErrorKind;
+use query::bm25::BM25Weight;
+use query::explanation::does_not_match;
+use query::{Explanation, Query, Scorer, Weight};
+use schema::{Field, Term};
+use std::ops::DerefMut;
+use DocId;
+use Result;
+use Score;
+
+/// Interval of positions `[start, end)` matched within a document.
+///
+/// For instance, a single term at position `3` is matched by the span `[3, 4)`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub struct Span {
+    /// Position of the first token of the span.
+    pub start: u32,
+    /// Position following the last token of the span.
+    pub end: u32,
+}
+
+impl Span {
+    /// Returns the number of positions covered by the span.
+    pub fn len(&self) -> u32 {
+        self.end - self.start
+    }
+
+    /// Returns true iff the span does not cover any position.
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// Returns true iff the two spans share at least one position.
+    pub fn overlaps(&self, other: &Span) -> bool {
+        self.start < other.end && other.start < self.end
+    }
+}
+
+/// A `SpanQuery` is a query that can expose the spans it matches.
+pub trait SpanQuery: Query {
+    /// Returns the field of the span query.
+    fn field(&self) -> Field;
+
+    /// Appends the terms contributing to the score of the span query.
+    fn span_terms(&self, terms: &mut Vec<Term>);
+
+    /// Creates the `SpanWeight` associated to the query.
+    fn span_weight(&self, searcher: &Searcher, scoring_enabled: bool) -> Result<Box<SpanWeight>>;
+
+    /// Rewrites the span query, like [`Query::rewrite`](./trait.Query.html#method.rewrite),
+    /// while keeping it a span query.
+    ///
+    /// Span queries rewrite their nested span queries, so that
+    /// a nested `SpanPrefixQuery` is expanded as well.
+    fn rewrite_span(self: Box<Self>, searcher: &Searcher) -> Result<Box<SpanQuery>>;
+}
+
+/// `Weight` associated to a `SpanQuery`.
+pub trait SpanWeight: Weight {
+    /// Returns the `SpanScorer` for the given segment.
+    fn span_scorer(&self, reader: &SegmentReader) -> Result<Box<SpanScorer>>;
+}
+
+/// `Scorer` exposing the spans matched in its current document.
+pub trait SpanScorer: Scorer {
+    /// Returns the spans matched in the current document,
+    /// sorted by start position, then by end position.
+    ///
+    /// The spans of a document matched by the scorer
+    /// are never empty.
+    fn spans(&self) -> &[Span];
+}
+
+impl Scorer for Box<SpanScorer> {
+    fn score(&mut self) -> Score {
+        self.deref_mut().score()
+    }
+}
+
+/// Returns the frequency used to score a document matching `spans`.
+///
+/// Each span contributes the inverse of its length, so that
+/// a single term counts for one, and that tight matches
+/// count more than loose ones.
+fn span_freq(spans: &[Span]) -> f32 {
+    spans.iter().map(|span| 1f32 / span.len() as f32).sum()
+}
+
+/// Explains the score of `doc`, as computed by the `SpanScorer`
+/// of `span_weight` from the spans matched in the document.
+fn explain_spans(
+    span_weight: &SpanWeight,
+    description: &str,
+    field: Field,
+    similarity_weight: &BM25Weight,
+    reader: &SegmentReader,
+    doc: DocId,
+) -> Result<Explanation> {
+    let mut span_scorer = span_weight.span_scorer(reader)?;
+    if span_scorer.skip_next(doc) != SkipResult::Reached {
+        return Err(does_not_match(doc));
+    }
+    let spans = span_scorer.spans();
+    let fieldnorm_id = reader.get_fieldnorms_reader(field).fieldnorm_id(doc);
+    let similarity_explanation = similarity_weight.explain(fieldnorm_id, span_freq(spans));
+    let span_descriptions = spans
+        .iter()
+        .map(|span| format!("[{}, {})", span.start, span.end))
+        .collect::<Vec<String>>();
+    let mut explanation = Explanation::new(
+        format!(
+            "{}, matching the spans {}, result of:",
+            description,
+            span_descriptions.join(" ")
+        ),
+        similarity_explanation.value(),
+    );
+    explanation.add_detail(similarity_explanation);
+    Ok(explanation)
+}
+
+fn span_similarity_weight(
+    span_query: &SpanQuery,
+    searcher: &Searcher,
+    scoring_enabled: bool,
+) -> BM25Weight {
+    let mut terms = Vec::new();
+    if scoring_enabled {
+        span_query.span_terms(&mut terms);
+    }
+    similarity_weight_for_terms(searcher, &terms)
+}
+
+fn similarity_weight_for_terms(searcher: &Searcher, terms: &[Term]) -> BM25Weight {
+    if terms.is_empty() {
+        BM25Weight::null()
+    } else {
+        BM25Weight::for_terms(searcher, terms)
+    }
+}
+
+fn check_has_positions(searcher: &Searcher, field: Field) -> Result<()> {
+    let schema = searcher.schema();
+    let field_entry = schema.get_field_entry(field);
+    let has_positions = field_entry
+        .field_type()
+        .get_index_record_option()
+        .map(|index_record_option| index_record_option.has_positions())
+        .unwrap_or(false);
+    if !has_positions {
+        bail!(ErrorKind::SchemaError(format!(
+            "Applied span query on field {:?}, which does not have positions indexed",
+            field_entry.name()
+        )))
+    }
+    Ok(())
+}
+
+fn assert_same_field(clauses: &[Box<SpanQuery>]) -> Field {
+    assert!(
+        !clauses.is_empty(),
+        "A span query is required to have at least one clause."
+    );
+    let field = clauses[0].field();
+    assert!(
+        clauses[1..].iter().all(|clause| clause.field() == field),
+        "All clauses from a span query must belong to the same field"
+    );
+    field
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use collector::tests::TestCollector;
+    use core::Index;
+    use error::ErrorKind;
+    use schema::{SchemaBuilder, Term, STRING, TEXT};
+    use std::collections::BTreeSet;
+    use tests::assert_nearly_equals;
+    use DocAddress;
+
+    fn create_index(texts: &[&'static str]) -> (Index, Field) {
+        let mut schema_builder = SchemaBuilder::default();
+        let text_field = schema_builder.add_text_field("text", TEXT);
+        let schema = schema_builder.build();
+        let index = Index::create_in_ram(schema);
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 40_000_000).unwrap();
+            for &text in texts {
+                index_writer.add_document(doc!(text_field=>text));
+            }
+            assert!(index_writer.commit().is_ok());
+        }
+        index.load_searchers().unwrap();
+        (index, text_field)
+    }
+
+    fn span_term(field: Field, text: &str) -> Box<SpanQuery> {
+        Box::new(SpanTermQuery::new(Term::from_field_text(field, text)))
+    }
+
+    fn matching_spans(index: &Index, span_query: &SpanQuery) -> Vec<(u32, Vec<(u32, u32)>)> {
+        let searcher = index.searcher();
+        let span_weight = span_query.span_weight(&*searcher, false).unwrap();
+        let mut span_scorer = span_weight.span_scorer(searcher.segment_reader(0)).unwrap();
+        let mut matches = Vec::new();
+        while span_scorer.advance() {
+            let spans = span_scorer
+                .spans()
+                .iter()
+                .map(|span| (span.start, span.end))
+                .collect();
+            matches.push((span_scorer.doc(), spans));
+        }
+        matches
+    }
+
+    #[test]
+    pub fn test_span_term_query() {
+        let (index, text_field) = create_index(&["a b a", "b", "c a"]);
+        assert_eq!(
+            matching_spans(&index, &*span_term(text_field, "a")),
+            vec![(0, vec![(0, 1), (2, 3)]), (2, vec![(1, 2)])]
+        );
+        assert!(matching_spans(&index, &*span_term(text_field, "d")).is_empty());
+    }
+
+    #[test]
+    pub fn test_span_near_query() {
+        let (index, text_field) = create_index(&[
+            "contract terminated",
+            "contract was finally terminated",
+            "terminated contract",
+            "contract and the agreement were all terminated",
+            "contract",
+        ]);
+        let near = |slop: u32, in_order: bool| {
+            SpanNearQuery::new(
+                vec![
+                    span_term(text_field, "contract"),
+                    span_term(text_field, "terminated"),
+                ],
+                slop,
+                in_order,
+            )
+        };
+        assert_eq!(
+            matching_spans(&index, &near(0, true)),
+            vec![(0, vec![(0, 2)])]
+        );
+        assert_eq!(
+            matching_spans(&index, &near(2, true)),
+            vec![(0, vec![(0, 2)]), (1, vec![(0, 4)])]
+        );
+        assert_eq!(
+            matching_spans(&index, &near(2, false)),
+            vec![(0, vec![(0, 2)]), (1, vec![(0, 4)]), (2, vec![(0, 2)])]
+        );
+        assert_eq!(matching_spans(&index, &near(5, true)).len(), 3);
+        {
+            // spans can nest.
+            let nested_near = SpanNearQuery::new(
+                vec![Box::new(near(0, false)), span_term(text_field, "contract")],
+                0,
+                true,
+            );
+            assert!(matching_spans(&index, &nested_near).is_empty());
+            let nested_near = SpanNearQuery::new(
+                vec![span_term(text_field, "was"), Box::new(near(2, false))],
+                3,
+                false,
+            );
+            assert_eq!(
+                matching_spans(&index, &nested_near),
+                vec![(1, vec![(0, 4)])]
+            );
+        }
+        {
+            let searcher = index.searcher();
+            let mut test_collector = TestCollector::default();
+            searcher
+                .search(&near(5, true), &mut test_collector)
+                .unwrap();
+            let scores = test_collector.scores();
+            assert!(scores[0] > scores[1]);
+            let explanation = near(5, true).explain(&*searcher, DocAddress(0, 1)).unwrap();
+            assert_eq!(
+                explanation.description(),
+                "SpanNearQuery with a slop of 5, in order, matching the spans [0, 4), result of:"
+            );
+            assert_nearly_equals(scores[1], explanation.value());
+            assert!(near(5, true).explain(&*searcher, DocAddress(0, 4)).is_err());
+        }
+    }
+
+    #[test]
+    pub fn test_span_near_query_nested_ordered() {
+        let (index, text_field) = create_index(&["a b c d", "a b d"]);
+        let b_c = SpanNearQuery::new(
+            vec![span_term(text_field, "b"), span_term(text_field, "c")],
+            0,
+            true,
+        );
+        let b_or_b_c = SpanOrQuery::new(vec![Box::new(b_c), span_term(text_field, "b")]);
+        let near = SpanNearQuery::new(
+            vec![
+                span_term(text_field, "a"),
+                Box::new(b_or_b_c),
+                span_term(text_field, "d"),
+            ],
+            0,
+            true,
+        );
+        assert_eq!(
+            matching_spans(&index, &near),
+            vec![(0, vec![(0, 4)]), (1, vec![(0, 3)])]
+        );
+    }
+
+    #[test]
+    pub fn test_span_near_query_same_term() {
+        let (index, text_field) = create_index(&["a", "a b a", "a a"]);
+        let near = SpanNearQuery::new(
+            vec![span_term(text_field, "a"), span_term(text_field, "a")],
+            1,
+            false,
+        );
+        assert_eq!(
+            matching_spans(&index, &near),
+            vec![(1, vec![(0, 3)]), (2, vec![(0, 2)])]
+        );
+    }
+
+    #[test]
+    pub fn test_span_or_query() {
+        let (index, text_field) = create_index(&["a b", "c", "b d", "e"]);
+        let span_or = SpanOrQuery::new(vec![
+            span_term(text_field, "a"),
+            span_term(text_field, "b"),
+            span_term(text_field, "d"),
+        ]);
+        assert_eq!(
+            matching_spans(&index, &span_or),
+            vec![(0, vec![(0, 1), (1, 2)]), (2, vec![(0, 1), (1, 2)]),]
+        );
+        let near = SpanNearQuery::new(vec![Box::new(span_or), span_term(text_field, "d")], 0, true);
+        assert_eq!(matching_spans(&index, &near), vec![(2, vec![(0, 2)])]);
+    }
+
+    #[test]
+    pub fn test_span_not_query() {
+        let (index, text_field) = create_index(&["a b c", "a c", "d", "a d a b"]);
+        let near = SpanNearQuery::new(
+            vec![span_term(text_field, "a"), span_term(text_field, "c")],
+            1,
+            true,
+        );
+        let span_not = SpanNotQuery::new(Box::new(near), span_term(text_field, "b"));
+        assert_eq!(matching_spans(&index, &span_not), vec![(1, vec![(0, 2)])]);
+        let span_not = SpanNotQuery::new(span_term(text_field, "a"), span_term(text_field, "d"));
+        assert_eq!(
+            matching_spans(&index, &span_not),
+            vec![
+                (0, vec![(0, 1)]),
+                (1, vec![(0, 1)]),
+                (3, vec![(0, 1), (2, 3)])
+            ]
+        );
+        let mut query_terms = BTreeSet::new();
+        span_not.query_terms(&mut query_terms);
+        assert_eq!(
+            query_terms.into_iter().collect::<Vec<Term>>(),
+            vec![Term::from_field_text(text_field, "a")]
+        );
+    }
+
+    #[test]
+    pub fn test_span_first_query() {
+        let (index, text_field) = create_index(&["a b c", "c b a", "b c a c"]);
+        let span_first = SpanFirstQuery::new(span_term(text_field, "c"), 2);
+        assert_eq!(
+            matching_spans(&index, &span_first),
+            vec![(1, vec![(0, 1)]), (2, vec![(1, 2)])]
+        );
+        assert_eq!(span_first.count(&*index.searcher()).unwrap(), 2);
+    }
+
+    #[test]
+    pub fn test_span_prefix_query() {
+        let (index, text_field) = create_index(&[
+            "contract terminated",
+            "contract was finally terminates",
+            "terminal contract",
+            "contract and terms",
+        ]);
+        let span_prefix =
+            |prefix: &str| SpanPrefixQuery::new(Term::from_field_text(text_field, prefix));
+        assert_eq!(
+            matching_spans(&index, &span_prefix("termina")),
+            vec![(0, vec![(1, 2)]), (1, vec![(3, 4)]), (2, vec![(0, 1)])]
+        );
+        assert!(matching_spans(&index, &span_prefix("x")).is_empty());
+        let near = SpanNearQuery::new(
+            vec![
+                span_term(text_field, "contract"),
+                Box::new(span_prefix("terminat")),
+            ],
+            5,
+            true,
+        );
+        assert_eq!(
+            matching_spans(&index, &near),
+            vec![(0, vec![(0, 2)]), (1, vec![(0, 4)])]
+        );
+        assert_eq!(near.count(&*index.searcher()).unwrap(), 2);
+        {
+            let searcher = index.searcher();
+            let mut test_collector = TestCollector::default();
+            searcher
+                .search(&span_prefix("term"), &mut test_collector)
+                .unwrap();
+            assert_eq!(test_collector.docs(), vec![0, 1, 2, 3]);
+            let rewritten_query = Box::new(span_prefix("terminat"))
+                .rewrite(&*searcher)
+                .unwrap();
+            let mut query_terms = BTreeSet::new();
+            rewritten_query.query_terms(&mut query_terms);
+            assert_eq!(
+                query_terms.into_iter().collect::<Vec<Term>>(),
+                vec![
+                    Term::from_field_text(text_field, "terminated"),
+                    Term::from_field_text(text_field, "terminates"),
+                ]
+            );
+            // nested prefixes are expanded as well.
+            let nested_prefix = SpanFirstQuery::new(
+                Box::new(SpanNearQuery::new(
+                    vec![
+                        span_term(text_field, "contract"),
+                        Box::new(SpanOrQuery::new(vec![Box::new(span_prefix("terminat"))])),
+                    ],
+                    5,
+                    true,
+                )),
+                4,
+            );
+            let rewritten_query = Box::new(nested_prefix).rewrite(&*searcher).unwrap();
+            let mut query_terms = BTreeSet::new();
+            rewritten_query.query_terms(&mut query_terms);
+            assert_eq!(
+                query_terms.into_iter().collect::<Vec<Term>>(),
+                vec![
+                    Term::from_field_text(text_field, "contract"),
+                    Term::from_field_text(text_field, "terminated"),
+                    Term::from_field_text(text_field, "terminates"),
+                ]
+            );
+            assert_eq!(rewritten_query.count(&*searcher).unwrap(), 2);
+        }
+        let mut span_prefix_query = span_prefix("t");
+        span_prefix_query.set_max_expansions(2);
+        let err = span_prefix_query
+            .weight(&*index.searcher(), false)
+            .err()
+            .unwrap();
+        assert_matches!(err.kind(), &ErrorKind::TooManyTerms(2));
+    }
+
+    #[test]
+    pub fn test_span_query_no_positions() {
+        let mut schema_builder = SchemaBuilder::default();
+        let text_field = schema_builder.add_text_field("text", STRING);
+        let index = Index::create_in_ram(schema_builder.build());
+        index.load_searchers().unwrap();
+        let span_near = SpanNearQuery::new(
+            vec![span_term(text_field, "a"), span_term(text_field, "b")],
+            1,
+            true,
+        );
+        let err = span_near.weight(&*index.searcher(), true).err().unwrap();
+        assert_matches!(err.kind(), &ErrorKind::SchemaError(_));
+    }
+}
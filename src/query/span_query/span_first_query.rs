@@ -0,0 +1,183 @@
+use super::{explain_spans, span_freq, span_similarity_weight};
+use super::{Span, SpanQuery, SpanScorer, SpanWeight};
+use core::Searcher;
+use core::SegmentReader;
+use docset::DocSet;
+use fieldnorm::FieldNormReader;
+use query::bm25::BM25Weight;
+use query::{Explanation, Query, QueryDsl, Scorer, Weight};
+use schema::{Field, Schema, Term};
+use std::collections::BTreeSet;
+use DocId;
+use Result;
+use Score;
+
+/// `SpanFirstQuery` matches the spans of a span query
+/// that end before a given position.
+///
+/// For instance, it makes it possible to search for documents
+/// whose first three words contain `confidential`.
+#[derive(Debug)]
+pub struct SpanFirstQuery {
+    span_query: Box<SpanQuery>,
+    end: u32,
+}
+
+impl SpanFirstQuery {
+    /// Creates a new `SpanFirstQuery`, matching the spans
+    /// of `span_query` ending at most at position `end`.
+    pub fn new(span_query: Box<SpanQuery>, end: u32) -> SpanFirstQuery {
+        SpanFirstQuery { span_query, end }
+    }
+
+    /// Returns the query, with its nested span queries rewritten.
+    fn rewrite_clauses(self, searcher: &Searcher) -> Result<SpanFirstQuery> {
+        Ok(SpanFirstQuery::new(
+            self.span_query.rewrite_span(searcher)?,
+            self.end,
+        ))
+    }
+
+    fn specialized_weight(
+        &self,
+        searcher: &Searcher,
+        scoring_enabled: bool,
+    ) -> Result<SpanFirstWeight> {
+        Ok(SpanFirstWeight {
+            field: self.field(),
+            span_weight: self.span_query.span_weight(searcher, false)?,
+            end: self.end,
+            similarity_weight: span_similarity_weight(self, searcher, scoring_enabled),
+        })
+    }
+}
+
+impl Query for SpanFirstQuery {
+    fn weight(&self, searcher: &Searcher, scoring_enabled: bool) -> Result<Box<Weight>> {
+        Ok(Box::new(
+            self.specialized_weight(searcher, scoring_enabled)?,
+        ))
+    }
+
+    fn rewrite(self: Box<Self>, searcher: &Searcher) -> Result<Box<Query>> {
+        Ok(Box::new(self.rewrite_clauses(searcher)?))
+    }
+
+    fn query_terms(&self, term_set: &mut BTreeSet<Term>) {
+        self.span_query.query_terms(term_set);
+    }
+
+    fn to_dsl(&self, schema: &Schema) -> Result<QueryDsl> {
+        Ok(QueryDsl::SpanFirst {
+            query: Box::new(self.span_query.to_dsl(schema)?),
+            end: self.end,
+        })
+    }
+}
+
+impl SpanQuery for SpanFirstQuery {
+    fn field(&self) -> Field {
+        self.span_query.field()
+    }
+
+    fn span_terms(&self, terms: &mut Vec<Term>) {
+        self.span_query.span_terms(terms);
+    }
+
+    fn span_weight(&self, searcher: &Searcher, scoring_enabled: bool) -> Result<Box<SpanWeight>> {
+        Ok(Box::new(
+            self.specialized_weight(searcher, scoring_enabled)?,
+        ))
+    }
+
+    fn rewrite_span(self: Box<Self>, searcher: &Searcher) -> Result<Box<SpanQuery>> {
+        Ok(Box::new(self.rewrite_clauses(searcher)?))
+    }
+}
+
+struct SpanFirstWeight {
+    field: Field,
+    span_weight: Box<SpanWeight>,
+    end: u32,
+    similarity_weight: BM25Weight,
+}
+
+impl Weight for SpanFirstWeight {
+    fn scorer(&self, reader: &SegmentReader) -> Result<Box<Scorer>> {
+        Ok(Box::new(self.span_scorer(reader)?))
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> Result<Explanation> {
+        let description = format!("SpanFirstQuery with an end of {}", self.end);
+        explain_spans(
+            self,
+            &description,
+            self.field,
+            &self.similarity_weight,
+            reader,
+            doc,
+        )
+    }
+}
+
+impl SpanWeight for SpanFirstWeight {
+    fn span_scorer(&self, reader: &SegmentReader) -> Result<Box<SpanScorer>> {
+        Ok(Box::new(SpanFirstScorer {
+            span_scorer: self.span_weight.span_scorer(reader)?,
+            end: self.end,
+            spans: Vec::new(),
+            fieldnorm_reader: reader.get_fieldnorms_reader(self.field),
+            similarity_weight: self.similarity_weight.clone(),
+        }))
+    }
+}
+
+struct SpanFirstScorer {
+    span_scorer: Box<SpanScorer>,
+    end: u32,
+    spans: Vec<Span>,
+    fieldnorm_reader: FieldNormReader,
+    similarity_weight: BM25Weight,
+}
+
+impl DocSet for SpanFirstScorer {
+    fn advance(&mut self) -> bool {
+        while self.span_scorer.advance() {
+            let end = self.end;
+            self.spans.clear();
+            self.spans.extend(
+                self.span_scorer
+                    .spans()
+                    .iter()
+                    .cloned()
+                    .filter(|span| span.end <= end),
+            );
+            if !self.spans.is_empty() {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn doc(&self) -> DocId {
+        self.span_scorer.doc()
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.span_scorer.size_hint()
+    }
+}
+
+impl Scorer for SpanFirstScorer {
+    fn score(&mut self) -> Score {
+        let fieldnorm_id = self.fieldnorm_reader.fieldnorm_id(self.doc());
+        self.similarity_weight
+            .score_freq(fieldnorm_id, span_freq(&self.spans))
+    }
+}
+
+impl SpanScorer for SpanFirstScorer {
+    fn spans(&self) -> &[Span] {
+        &self.spans
+    }
+}
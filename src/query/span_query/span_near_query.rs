@@ -0,0 +1,418 @@
+use super::{assert_same_field, explain_spans, span_freq, span_similarity_weight};
+use super::{Span, SpanQuery, SpanScorer, SpanWeight};
+use core::Searcher;
+use core::SegmentReader;
+use docset::{DocSet, SkipResult};
+use fieldnorm::FieldNormReader;
+use query::bm25::BM25Weight;
+use query::{Explanation, Query, QueryDsl, Scorer, Weight};
+use schema::{Field, Schema, Term};
+use std::collections::BTreeSet;
+use std::mem;
+use DocId;
+use Result;
+use Score;
+
+/// `SpanNearQuery` matches spans of its clauses that are near each other.
+///
+/// The slop is the maximum number of positions separating the spans
+/// of the different clauses. The span matched by the `SpanNearQuery`
+/// goes from the start of the first clause span to the end of the
+/// last clause span.
+///
+/// If `in_order` is true, the clause spans must appear in the order of the clauses,
+/// without overlapping. Otherwise, they may appear in any order, and even overlap,
+/// as long as no two clauses match the very same span.
+///
+/// For instance, with an empty slop, an ordered `SpanNearQuery`
+/// over the terms `part` and `time` is equivalent to the phrase `"part time"`.
+#[derive(Debug)]
+pub struct SpanNearQuery {
+    clauses: Vec<Box<SpanQuery>>,
+    slop: u32,
+    in_order: bool,
+}
+
+impl SpanNearQuery {
+    /// Creates a new `SpanNearQuery`.
+    ///
+    /// There must be at least one clause, and all clauses
+    /// must belong to the same field.
+    pub fn new(clauses: Vec<Box<SpanQuery>>, slop: u32, in_order: bool) -> SpanNearQuery {
+        assert_same_field(&clauses[..]);
+        SpanNearQuery {
+            clauses,
+            slop,
+            in_order,
+        }
+    }
+
+    /// Returns the query, with its nested span queries rewritten.
+    fn rewrite_clauses(self, searcher: &Searcher) -> Result<SpanNearQuery> {
+        let clauses = self
+            .clauses
+            .into_iter()
+            .map(|clause| clause.rewrite_span(searcher))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(SpanNearQuery::new(clauses, self.slop, self.in_order))
+    }
+
+    fn specialized_weight(
+        &self,
+        searcher: &Searcher,
+        scoring_enabled: bool,
+    ) -> Result<SpanNearWeight> {
+        let clause_weights = self
+            .clauses
+            .iter()
+            .map(|clause| clause.span_weight(searcher, false))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(SpanNearWeight {
+            field: self.field(),
+            clause_weights,
+            slop: self.slop,
+            in_order: self.in_order,
+            similarity_weight: span_similarity_weight(self, searcher, scoring_enabled),
+        })
+    }
+}
+
+impl Query for SpanNearQuery {
+    fn weight(&self, searcher: &Searcher, scoring_enabled: bool) -> Result<Box<Weight>> {
+        Ok(Box::new(
+            self.specialized_weight(searcher, scoring_enabled)?,
+        ))
+    }
+
+    fn rewrite(self: Box<Self>, searcher: &Searcher) -> Result<Box<Query>> {
+        Ok(Box::new(self.rewrite_clauses(searcher)?))
+    }
+
+    fn query_terms(&self, term_set: &mut BTreeSet<Term>) {
+        for clause in &self.clauses {
+            clause.query_terms(term_set);
+        }
+    }
+
+    fn to_dsl(&self, schema: &Schema) -> Result<QueryDsl> {
+        Ok(QueryDsl::SpanNear {
+            clauses: self
+                .clauses
+                .iter()
+                .map(|clause| clause.to_dsl(schema))
+                .collect::<Result<_>>()?,
+            slop: self.slop,
+            in_order: self.in_order,
+        })
+    }
+}
+
+impl SpanQuery for SpanNearQuery {
+    fn field(&self) -> Field {
+        self.clauses[0].field()
+    }
+
+    fn span_terms(&self, terms: &mut Vec<Term>) {
+        for clause in &self.clauses {
+            clause.span_terms(terms);
+        }
+    }
+
+    fn span_weight(&self, searcher: &Searcher, scoring_enabled: bool) -> Result<Box<SpanWeight>> {
+        Ok(Box::new(
+            self.specialized_weight(searcher, scoring_enabled)?,
+        ))
+    }
+
+    fn rewrite_span(self: Box<Self>, searcher: &Searcher) -> Result<Box<SpanQuery>> {
+        Ok(Box::new(self.rewrite_clauses(searcher)?))
+    }
+}
+
+struct SpanNearWeight {
+    field: Field,
+    clause_weights: Vec<Box<SpanWeight>>,
+    slop: u32,
+    in_order: bool,
+    similarity_weight: BM25Weight,
+}
+
+impl Weight for SpanNearWeight {
+    fn scorer(&self, reader: &SegmentReader) -> Result<Box<Scorer>> {
+        Ok(Box::new(self.span_scorer(reader)?))
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> Result<Explanation> {
+        let description = format!(
+            "SpanNearQuery with a slop of {}{}",
+            self.slop,
+            if self.in_order { ", in order" } else { "" }
+        );
+        explain_spans(
+            self,
+            &description,
+            self.field,
+            &self.similarity_weight,
+            reader,
+            doc,
+        )
+    }
+}
+
+impl SpanWeight for SpanNearWeight {
+    fn span_scorer(&self, reader: &SegmentReader) -> Result<Box<SpanScorer>> {
+        let clauses = self
+            .clause_weights
+            .iter()
+            .map(|clause_weight| clause_weight.span_scorer(reader))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Box::new(SpanNearScorer {
+            clauses,
+            started: false,
+            slop: self.slop,
+            in_order: self.in_order,
+            spans: Vec::new(),
+            fieldnorm_reader: reader.get_fieldnorms_reader(self.field),
+            similarity_weight: self.similarity_weight.clone(),
+        }))
+    }
+}
+
+/// Appends the spans in which the clause spans appear in order,
+/// with at most `slop` positions between them.
+///
+/// For each span of the first clause, every partial match is kept
+/// as its end position along with the smallest number of gaps
+/// required to reach it, as picking the span of the next clause
+/// that ends first may miss some matches.
+fn ordered_near_spans(clause_spans: &[&[Span]], slop: u32, output: &mut Vec<Span>) {
+    let mut candidates: Vec<(u32, u32)> = Vec::new();
+    let mut next_candidates: Vec<(u32, u32)> = Vec::new();
+    for first_span in clause_spans[0] {
+        candidates.clear();
+        candidates.push((first_span.end, 0u32));
+        for spans in &clause_spans[1..] {
+            next_candidates.clear();
+            for &(end, gaps) in &candidates {
+                for span in spans.iter().filter(|span| span.start >= end) {
+                    let next_gaps = gaps + span.start - end;
+                    if next_gaps <= slop {
+                        next_candidates.push((span.end, next_gaps));
+                    }
+                }
+            }
+            next_candidates.sort();
+            next_candidates.dedup_by_key(|&mut (end, _)| end);
+            mem::swap(&mut candidates, &mut next_candidates);
+            if candidates.is_empty() {
+                break;
+            }
+        }
+        if let Some(&(end, _)) = candidates.first() {
+            output.push(Span {
+                start: first_span.start,
+                end,
+            });
+        }
+    }
+}
+
+/// Appends the spans in which the clause spans appear in any order,
+/// with at most `slop` positions between them.
+///
+/// The spans are swept in increasing order, by repeatedly advancing
+/// the clause whose current span starts first.
+fn unordered_near_spans(clause_spans: &[&[Span]], slop: u32, output: &mut Vec<Span>) {
+    let num_clauses = clause_spans.len();
+    let mut cursors = vec![0usize; num_clauses];
+    let mut current_spans: Vec<Span> = Vec::with_capacity(num_clauses);
+    loop {
+        current_spans.clear();
+        for (ord, spans) in clause_spans.iter().enumerate() {
+            match spans.get(cursors[ord]) {
+                Some(&span) => current_spans.push(span),
+                None => {
+                    return;
+                }
+            }
+        }
+        let (min_ord, start) = current_spans
+            .iter()
+            .enumerate()
+            .map(|(ord, span)| (ord, span.start))
+            .min_by_key(|&(_, start)| start)
+            .unwrap();
+        let end = current_spans.iter().map(|span| span.end).max().unwrap();
+        let total_len: u32 = current_spans.iter().map(Span::len).sum();
+        let has_duplicates =
+            (1..num_clauses).any(|ord| current_spans[..ord].contains(&current_spans[ord]));
+        if !has_duplicates && (end - start).saturating_sub(total_len) <= slop {
+            output.push(Span { start, end });
+        }
+        cursors[min_ord] += 1;
+    }
+}
+
+struct SpanNearScorer {
+    clauses: Vec<Box<SpanScorer>>,
+    started: bool,
+    slop: u32,
+    in_order: bool,
+    spans: Vec<Span>,
+    fieldnorm_reader: FieldNormReader,
+    similarity_weight: BM25Weight,
+}
+
+impl SpanNearScorer {
+    /// Positions all of the clauses on the same document,
+    /// starting from the current document of the first clause.
+    ///
+    /// Returns false if one of the clauses is exhausted.
+    fn align_clauses(&mut self) -> bool {
+        let num_clauses = self.clauses.len();
+        let mut target = self.clauses[0].doc();
+        let mut num_aligned = 1;
+        let mut ord = 0;
+        while num_aligned < num_clauses {
+            ord = (ord + 1) % num_clauses;
+            let clause = &mut self.clauses[ord];
+            if clause.doc() < target {
+                match clause.skip_next(target) {
+                    SkipResult::Reached => {
+                        num_aligned += 1;
+                    }
+                    SkipResult::OverStep => {
+                        target = clause.doc();
+                        num_aligned = 1;
+                    }
+                    SkipResult::End => {
+                        return false;
+                    }
+                }
+            } else if clause.doc() == target {
+                num_aligned += 1;
+            } else {
+                target = clause.doc();
+                num_aligned = 1;
+            }
+        }
+        true
+    }
+
+    fn compute_spans(&mut self) -> bool {
+        self.spans.clear();
+        {
+            let clause_spans: Vec<&[Span]> =
+                self.clauses.iter().map(|clause| clause.spans()).collect();
+            if self.in_order {
+                ordered_near_spans(&clause_spans, self.slop, &mut self.spans);
+            } else {
+                unordered_near_spans(&clause_spans, self.slop, &mut self.spans);
+            }
+        }
+        self.spans.sort();
+        self.spans.dedup();
+        !self.spans.is_empty()
+    }
+}
+
+impl DocSet for SpanNearScorer {
+    fn advance(&mut self) -> bool {
+        if !self.started {
+            self.started = true;
+            if !self.clauses.iter_mut().all(|clause| clause.advance()) {
+                return false;
+            }
+        } else if !self.clauses[0].advance() {
+            return false;
+        }
+        loop {
+            if !self.align_clauses() {
+                return false;
+            }
+            if self.compute_spans() {
+                return true;
+            }
+            if !self.clauses[0].advance() {
+                return false;
+            }
+        }
+    }
+
+    fn doc(&self) -> DocId {
+        self.clauses[0].doc()
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.clauses
+            .iter()
+            .map(|clause| clause.size_hint())
+            .min()
+            .unwrap_or(0u32)
+    }
+}
+
+impl Scorer for SpanNearScorer {
+    fn score(&mut self) -> Score {
+        let fieldnorm_id = self.fieldnorm_reader.fieldnorm_id(self.doc());
+        self.similarity_weight
+            .score_freq(fieldnorm_id, span_freq(&self.spans))
+    }
+}
+
+impl SpanScorer for SpanNearScorer {
+    fn spans(&self) -> &[Span] {
+        &self.spans
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{ordered_near_spans, unordered_near_spans};
+    use query::span_query::Span;
+
+    fn spans(intervals: &[(u32, u32)]) -> Vec<Span> {
+        intervals
+            .iter()
+            .map(|&(start, end)| Span { start, end })
+            .collect()
+    }
+
+    #[test]
+    fn test_ordered_near_spans() {
+        let left = spans(&[(0, 1), (4, 5)]);
+        let right = spans(&[(2, 3), (5, 7)]);
+        let mut output = Vec::new();
+        ordered_near_spans(&[&left, &right], 0, &mut output);
+        assert_eq!(output, spans(&[(4, 7)]));
+        output.clear();
+        ordered_near_spans(&[&left, &right], 1, &mut output);
+        assert_eq!(output, spans(&[(0, 3), (4, 7)]));
+        output.clear();
+        ordered_near_spans(&[&right, &left], 1, &mut output);
+        assert_eq!(output, spans(&[(2, 5)]));
+        output.clear();
+        // the span ending first does not always lead to a match.
+        let first = spans(&[(4, 5)]);
+        let second = spans(&[(5, 10), (7, 8)]);
+        let third = spans(&[(10, 11)]);
+        ordered_near_spans(&[&first, &second, &third], 1, &mut output);
+        assert_eq!(output, spans(&[(4, 11)]));
+    }
+
+    #[test]
+    fn test_unordered_near_spans() {
+        let left = spans(&[(0, 1), (4, 5)]);
+        let right = spans(&[(2, 3), (5, 7)]);
+        let mut output = Vec::new();
+        unordered_near_spans(&[&left, &right], 0, &mut output);
+        assert_eq!(output, spans(&[(4, 7)]));
+        output.clear();
+        unordered_near_spans(&[&left, &right], 1, &mut output);
+        assert_eq!(output, spans(&[(0, 3), (2, 5), (4, 7)]));
+        output.clear();
+        unordered_near_spans(&[&left, &left], 4, &mut output);
+        assert_eq!(output, spans(&[(0, 5)]));
+    }
+}
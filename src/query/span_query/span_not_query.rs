@@ -0,0 +1,225 @@
+use super::{explain_spans, span_freq, span_similarity_weight};
+use super::{Span, SpanQuery, SpanScorer, SpanWeight};
+use core::Searcher;
+use core::SegmentReader;
+use docset::{DocSet, SkipResult};
+use fieldnorm::FieldNormReader;
+use query::bm25::BM25Weight;
+use query::{Explanation, Query, QueryDsl, Scorer, Weight};
+use schema::{Field, Schema, Term};
+use std::collections::BTreeSet;
+use DocId;
+use Result;
+use Score;
+
+/// `SpanNotQuery` matches the spans of an `include` span query
+/// that do not overlap any of the spans of an `exclude` span query.
+///
+/// For instance, it makes it possible to search for `contract` near
+/// `terminated`, as long as the word `not` does not appear in between.
+#[derive(Debug)]
+pub struct SpanNotQuery {
+    include: Box<SpanQuery>,
+    exclude: Box<SpanQuery>,
+}
+
+impl SpanNotQuery {
+    /// Creates a new `SpanNotQuery`.
+    ///
+    /// Both span queries must belong to the same field.
+    pub fn new(include: Box<SpanQuery>, exclude: Box<SpanQuery>) -> SpanNotQuery {
+        assert_eq!(
+            include.field(),
+            exclude.field(),
+            "All clauses from a span query must belong to the same field"
+        );
+        SpanNotQuery { include, exclude }
+    }
+
+    /// Returns the query, with its nested span queries rewritten.
+    fn rewrite_clauses(self, searcher: &Searcher) -> Result<SpanNotQuery> {
+        Ok(SpanNotQuery::new(
+            self.include.rewrite_span(searcher)?,
+            self.exclude.rewrite_span(searcher)?,
+        ))
+    }
+
+    fn specialized_weight(
+        &self,
+        searcher: &Searcher,
+        scoring_enabled: bool,
+    ) -> Result<SpanNotWeight> {
+        Ok(SpanNotWeight {
+            field: self.field(),
+            include_weight: self.include.span_weight(searcher, false)?,
+            exclude_weight: self.exclude.span_weight(searcher, false)?,
+            similarity_weight: span_similarity_weight(self, searcher, scoring_enabled),
+        })
+    }
+}
+
+impl Query for SpanNotQuery {
+    fn weight(&self, searcher: &Searcher, scoring_enabled: bool) -> Result<Box<Weight>> {
+        Ok(Box::new(
+            self.specialized_weight(searcher, scoring_enabled)?,
+        ))
+    }
+
+    fn rewrite(self: Box<Self>, searcher: &Searcher) -> Result<Box<Query>> {
+        Ok(Box::new(self.rewrite_clauses(searcher)?))
+    }
+
+    /// Only the terms of the included spans are inserted, as
+    /// the terms of the excluded spans are never part of a match.
+    fn query_terms(&self, term_set: &mut BTreeSet<Term>) {
+        self.include.query_terms(term_set);
+    }
+
+    fn to_dsl(&self, schema: &Schema) -> Result<QueryDsl> {
+        Ok(QueryDsl::SpanNot {
+            include: Box::new(self.include.to_dsl(schema)?),
+            exclude: Box::new(self.exclude.to_dsl(schema)?),
+        })
+    }
+}
+
+impl SpanQuery for SpanNotQuery {
+    fn field(&self) -> Field {
+        self.include.field()
+    }
+
+    fn span_terms(&self, terms: &mut Vec<Term>) {
+        // the excluded terms do not contribute to the score.
+        self.include.span_terms(terms);
+    }
+
+    fn span_weight(&self, searcher: &Searcher, scoring_enabled: bool) -> Result<Box<SpanWeight>> {
+        Ok(Box::new(
+            self.specialized_weight(searcher, scoring_enabled)?,
+        ))
+    }
+
+    fn rewrite_span(self: Box<Self>, searcher: &Searcher) -> Result<Box<SpanQuery>> {
+        Ok(Box::new(self.rewrite_clauses(searcher)?))
+    }
+}
+
+struct SpanNotWeight {
+    field: Field,
+    include_weight: Box<SpanWeight>,
+    exclude_weight: Box<SpanWeight>,
+    similarity_weight: BM25Weight,
+}
+
+impl Weight for SpanNotWeight {
+    fn scorer(&self, reader: &SegmentReader) -> Result<Box<Scorer>> {
+        Ok(Box::new(self.span_scorer(reader)?))
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> Result<Explanation> {
+        explain_spans(
+            self,
+            "SpanNotQuery",
+            self.field,
+            &self.similarity_weight,
+            reader,
+            doc,
+        )
+    }
+}
+
+impl SpanWeight for SpanNotWeight {
+    fn span_scorer(&self, reader: &SegmentReader) -> Result<Box<SpanScorer>> {
+        Ok(Box::new(SpanNotScorer {
+            include: self.include_weight.span_scorer(reader)?,
+            exclude: self.exclude_weight.span_scorer(reader)?,
+            exclude_state: ExcludeState::Unstarted,
+            spans: Vec::new(),
+            fieldnorm_reader: reader.get_fieldnorms_reader(self.field),
+            similarity_weight: self.similarity_weight.clone(),
+        }))
+    }
+}
+
+#[derive(Clone, Copy, Eq, PartialEq)]
+enum ExcludeState {
+    Unstarted,
+    Positioned,
+    Exhausted,
+}
+
+struct SpanNotScorer {
+    include: Box<SpanScorer>,
+    exclude: Box<SpanScorer>,
+    exclude_state: ExcludeState,
+    spans: Vec<Span>,
+    fieldnorm_reader: FieldNormReader,
+    similarity_weight: BM25Weight,
+}
+
+impl SpanNotScorer {
+    /// Returns true iff the exclude scorer matches the given document.
+    fn exclude_matches(&mut self, doc: DocId) -> bool {
+        if self.exclude_state == ExcludeState::Unstarted {
+            self.exclude_state = if self.exclude.advance() {
+                ExcludeState::Positioned
+            } else {
+                ExcludeState::Exhausted
+            };
+        }
+        if self.exclude_state == ExcludeState::Exhausted {
+            return false;
+        }
+        if self.exclude.doc() < doc && self.exclude.skip_next(doc) == SkipResult::End {
+            self.exclude_state = ExcludeState::Exhausted;
+            return false;
+        }
+        self.exclude.doc() == doc
+    }
+}
+
+impl DocSet for SpanNotScorer {
+    fn advance(&mut self) -> bool {
+        while self.include.advance() {
+            let doc = self.include.doc();
+            self.spans.clear();
+            if self.exclude_matches(doc) {
+                let exclude_spans = self.exclude.spans();
+                self.spans
+                    .extend(self.include.spans().iter().cloned().filter(|span| {
+                        !exclude_spans
+                            .iter()
+                            .any(|exclude_span| span.overlaps(exclude_span))
+                    }));
+            } else {
+                self.spans.extend_from_slice(self.include.spans());
+            }
+            if !self.spans.is_empty() {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn doc(&self) -> DocId {
+        self.include.doc()
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.include.size_hint()
+    }
+}
+
+impl Scorer for SpanNotScorer {
+    fn score(&mut self) -> Score {
+        let fieldnorm_id = self.fieldnorm_reader.fieldnorm_id(self.doc());
+        self.similarity_weight
+            .score_freq(fieldnorm_id, span_freq(&self.spans))
+    }
+}
+
+impl SpanScorer for SpanNotScorer {
+    fn spans(&self) -> &[Span] {
+        &self.spans
+    }
+}
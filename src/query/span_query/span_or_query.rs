@@ -0,0 +1,233 @@
+use super::{assert_same_field, explain_spans, span_freq, span_similarity_weight};
+use super::{Span, SpanQuery, SpanScorer, SpanWeight};
+use core::Searcher;
+use core::SegmentReader;
+use docset::DocSet;
+use fieldnorm::FieldNormReader;
+use query::bm25::BM25Weight;
+use query::{Explanation, Query, QueryDsl, Scorer, Weight};
+use schema::{Field, Schema, Term};
+use std::collections::BTreeSet;
+use DocId;
+use Result;
+use Score;
+
+/// `SpanOrQuery` matches the union of the spans of its clauses.
+///
+/// It is typically useful to nest alternatives within a `SpanNearQuery`.
+#[derive(Debug)]
+pub struct SpanOrQuery {
+    clauses: Vec<Box<SpanQuery>>,
+}
+
+impl SpanOrQuery {
+    /// Creates a new `SpanOrQuery`.
+    ///
+    /// There must be at least one clause, and all clauses
+    /// must belong to the same field.
+    pub fn new(clauses: Vec<Box<SpanQuery>>) -> SpanOrQuery {
+        assert_same_field(&clauses[..]);
+        SpanOrQuery { clauses }
+    }
+
+    /// Returns the query, with its nested span queries rewritten.
+    fn rewrite_clauses(self, searcher: &Searcher) -> Result<SpanOrQuery> {
+        let clauses = self
+            .clauses
+            .into_iter()
+            .map(|clause| clause.rewrite_span(searcher))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(SpanOrQuery::new(clauses))
+    }
+
+    fn specialized_weight(
+        &self,
+        searcher: &Searcher,
+        scoring_enabled: bool,
+    ) -> Result<SpanOrWeight> {
+        let clause_weights = self
+            .clauses
+            .iter()
+            .map(|clause| clause.span_weight(searcher, false))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(SpanOrWeight::new(
+            self.field(),
+            clause_weights,
+            span_similarity_weight(self, searcher, scoring_enabled),
+        ))
+    }
+}
+
+impl Query for SpanOrQuery {
+    fn weight(&self, searcher: &Searcher, scoring_enabled: bool) -> Result<Box<Weight>> {
+        Ok(Box::new(
+            self.specialized_weight(searcher, scoring_enabled)?,
+        ))
+    }
+
+    fn rewrite(self: Box<Self>, searcher: &Searcher) -> Result<Box<Query>> {
+        Ok(Box::new(self.rewrite_clauses(searcher)?))
+    }
+
+    fn query_terms(&self, term_set: &mut BTreeSet<Term>) {
+        for clause in &self.clauses {
+            clause.query_terms(term_set);
+        }
+    }
+
+    fn to_dsl(&self, schema: &Schema) -> Result<QueryDsl> {
+        Ok(QueryDsl::SpanOr {
+            clauses: self
+                .clauses
+                .iter()
+                .map(|clause| clause.to_dsl(schema))
+                .collect::<Result<_>>()?,
+        })
+    }
+}
+
+impl SpanQuery for SpanOrQuery {
+    fn field(&self) -> Field {
+        self.clauses[0].field()
+    }
+
+    fn span_terms(&self, terms: &mut Vec<Term>) {
+        for clause in &self.clauses {
+            clause.span_terms(terms);
+        }
+    }
+
+    fn span_weight(&self, searcher: &Searcher, scoring_enabled: bool) -> Result<Box<SpanWeight>> {
+        Ok(Box::new(
+            self.specialized_weight(searcher, scoring_enabled)?,
+        ))
+    }
+
+    fn rewrite_span(self: Box<Self>, searcher: &Searcher) -> Result<Box<SpanQuery>> {
+        Ok(Box::new(self.rewrite_clauses(searcher)?))
+    }
+}
+
+pub(super) struct SpanOrWeight {
+    field: Field,
+    clause_weights: Vec<Box<SpanWeight>>,
+    similarity_weight: BM25Weight,
+}
+
+impl SpanOrWeight {
+    /// Creates a `SpanOrWeight`, which does not match
+    /// any document if there are no clauses.
+    pub(super) fn new(
+        field: Field,
+        clause_weights: Vec<Box<SpanWeight>>,
+        similarity_weight: BM25Weight,
+    ) -> SpanOrWeight {
+        SpanOrWeight {
+            field,
+            clause_weights,
+            similarity_weight,
+        }
+    }
+}
+
+impl Weight for SpanOrWeight {
+    fn scorer(&self, reader: &SegmentReader) -> Result<Box<Scorer>> {
+        Ok(Box::new(self.span_scorer(reader)?))
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> Result<Explanation> {
+        explain_spans(
+            self,
+            "SpanOrQuery",
+            self.field,
+            &self.similarity_weight,
+            reader,
+            doc,
+        )
+    }
+}
+
+impl SpanWeight for SpanOrWeight {
+    fn span_scorer(&self, reader: &SegmentReader) -> Result<Box<SpanScorer>> {
+        let clauses = self
+            .clause_weights
+            .iter()
+            .map(|clause_weight| clause_weight.span_scorer(reader))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Box::new(SpanOrScorer {
+            clauses,
+            started: false,
+            doc: 0,
+            spans: Vec::new(),
+            fieldnorm_reader: reader.get_fieldnorms_reader(self.field),
+            similarity_weight: self.similarity_weight.clone(),
+        }))
+    }
+}
+
+struct SpanOrScorer {
+    // clauses that are not exhausted yet.
+    clauses: Vec<Box<SpanScorer>>,
+    started: bool,
+    doc: DocId,
+    spans: Vec<Span>,
+    fieldnorm_reader: FieldNormReader,
+    similarity_weight: BM25Weight,
+}
+
+impl DocSet for SpanOrScorer {
+    fn advance(&mut self) -> bool {
+        let mut ord = 0;
+        while ord < self.clauses.len() {
+            let needs_advance = !self.started || self.clauses[ord].doc() == self.doc;
+            if needs_advance && !self.clauses[ord].advance() {
+                self.clauses.swap_remove(ord);
+            } else {
+                ord += 1;
+            }
+        }
+        self.started = true;
+        let doc = match self.clauses.iter().map(|clause| clause.doc()).min() {
+            Some(doc) => doc,
+            None => {
+                return false;
+            }
+        };
+        self.doc = doc;
+        self.spans.clear();
+        for clause in &self.clauses {
+            if clause.doc() == doc {
+                self.spans.extend_from_slice(clause.spans());
+            }
+        }
+        self.spans.sort();
+        self.spans.dedup();
+        true
+    }
+
+    fn doc(&self) -> DocId {
+        self.doc
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.clauses
+            .iter()
+            .map(|clause| clause.size_hint())
+            .max()
+            .unwrap_or(0u32)
+    }
+}
+
+impl Scorer for SpanOrScorer {
+    fn score(&mut self) -> Score {
+        let fieldnorm_id = self.fieldnorm_reader.fieldnorm_id(self.doc);
+        self.similarity_weight
+            .score_freq(fieldnorm_id, span_freq(&self.spans))
+    }
+}
+
+impl SpanScorer for SpanOrScorer {
+    fn spans(&self) -> &[Span] {
+        &self.spans
+    }
+}
@@ -0,0 +1,181 @@
+use super::span_or_query::SpanOrWeight;
+use super::{check_has_positions, similarity_weight_for_terms};
+use super::{SpanOrQuery, SpanQuery, SpanTermQuery, SpanWeight};
+use core::Searcher;
+use error::ErrorKind;
+use query::automaton_weight::{term_stream_to_terms, DEFAULT_MAX_EXPANSIONS};
+use query::bm25::BM25Weight;
+use query::prefix_query::prefix_range;
+use query::query_dsl::{field_name, max_expansions_to_dsl};
+use query::{Query, QueryDsl, Weight};
+use schema::{Field, FieldType, Schema, Term, Type};
+use std::collections::BTreeSet;
+use Result;
+
+/// `SpanPrefixQuery` matches the positions of all of the terms
+/// starting by a given prefix.
+///
+/// It makes it possible to use a prefix within other span queries,
+/// as in `contract` within 5 positions of `terminat*`.
+///
+/// # Implementation
+///
+/// The prefix is expanded, over all of the segments of the searcher,
+/// into a [`SpanOrQuery`](./struct.SpanOrQuery.html) over the
+/// `SpanTermQuery` of each of the terms starting by the prefix.
+///
+/// Like for the [`PrefixQuery`](./struct.PrefixQuery.html), the number of
+/// terms the prefix may expand to within a segment is capped.
+/// (1,024 by default. See [`.set_max_expansions(...)`](#method.set_max_expansions).)
+/// If the cap is exceeded, the search fails with a `TooManyTerms` error.
+///
+/// As the expanded terms are only known when searching, they do not
+/// contribute to the score of the span queries the `SpanPrefixQuery`
+/// is nested in.
+#[derive(Debug)]
+pub struct SpanPrefixQuery {
+    prefix: Term,
+    max_expansions: usize,
+}
+
+impl SpanPrefixQuery {
+    /// Creates a new `SpanPrefixQuery`.
+    ///
+    /// The text of the term given in argument is used as a prefix.
+    pub fn new(prefix: Term) -> SpanPrefixQuery {
+        SpanPrefixQuery {
+            prefix,
+            max_expansions: DEFAULT_MAX_EXPANSIONS,
+        }
+    }
+
+    /// Sets the maximum number of terms the prefix is allowed
+    /// to expand to within a segment.
+    pub fn set_max_expansions(&mut self, max_expansions: usize) {
+        self.max_expansions = max_expansions;
+    }
+
+    /// Returns the terms starting by the prefix, in any of
+    /// the segments of the searcher.
+    fn expanded_terms(&self, searcher: &Searcher) -> Result<Vec<Term>> {
+        let field = self.prefix.field();
+        let value_type = searcher
+            .schema()
+            .get_field_entry(field)
+            .field_type()
+            .value_type();
+        if value_type != Type::Str {
+            let err_msg = format!(
+                "Create a span prefix query on a field of type {:?}, \
+                 when only fields of type {:?} are supported",
+                value_type,
+                Type::Str
+            );
+            bail!(ErrorKind::SchemaError(err_msg))
+        }
+        check_has_positions(searcher, field)?;
+        let mut terms = BTreeSet::new();
+        for reader in searcher.segment_readers() {
+            let inverted_index = reader.inverted_index(field);
+            let term_stream = prefix_range(inverted_index.terms(), self.prefix.value_bytes());
+            term_stream_to_terms(field, term_stream, Some(self.max_expansions), &mut terms)?;
+        }
+        Ok(terms.into_iter().collect())
+    }
+
+    /// Returns the `SpanOrQuery` over the expanded terms,
+    /// or `None` if the prefix does not expand to any term.
+    fn expanded_query(&self, searcher: &Searcher) -> Result<Option<SpanOrQuery>> {
+        let terms = self.expanded_terms(searcher)?;
+        if terms.is_empty() {
+            return Ok(None);
+        }
+        let clauses = terms
+            .into_iter()
+            .map(|term| {
+                let clause: Box<SpanQuery> = Box::new(SpanTermQuery::new(term));
+                clause
+            })
+            .collect();
+        Ok(Some(SpanOrQuery::new(clauses)))
+    }
+
+    fn specialized_weight(
+        &self,
+        searcher: &Searcher,
+        scoring_enabled: bool,
+    ) -> Result<SpanOrWeight> {
+        let terms = self.expanded_terms(searcher)?;
+        let clause_weights = terms
+            .iter()
+            .map(|term| SpanTermQuery::new(term.clone()).span_weight(searcher, false))
+            .collect::<Result<Vec<_>>>()?;
+        let similarity_weight = if scoring_enabled {
+            similarity_weight_for_terms(searcher, &terms)
+        } else {
+            BM25Weight::null()
+        };
+        Ok(SpanOrWeight::new(
+            self.field(),
+            clause_weights,
+            similarity_weight,
+        ))
+    }
+}
+
+impl Query for SpanPrefixQuery {
+    fn weight(&self, searcher: &Searcher, scoring_enabled: bool) -> Result<Box<Weight>> {
+        Ok(Box::new(
+            self.specialized_weight(searcher, scoring_enabled)?,
+        ))
+    }
+
+    /// Rewrites the query into the `SpanOrQuery` over the expanded terms.
+    ///
+    /// The query is left unchanged if the prefix does not
+    /// expand to any term.
+    fn rewrite(self: Box<Self>, searcher: &Searcher) -> Result<Box<Query>> {
+        match self.expanded_query(searcher)? {
+            Some(span_or_query) => Ok(Box::new(span_or_query)),
+            None => Ok(self),
+        }
+    }
+
+    fn to_dsl(&self, schema: &Schema) -> Result<QueryDsl> {
+        let field_entry = schema.get_field_entry(self.field());
+        match *field_entry.field_type() {
+            FieldType::Str(_) => {}
+            _ => bail!(ErrorKind::SchemaError(format!(
+                "Span prefix query on field {:?}, which is not a text field, cannot be \
+                 converted to the query DSL",
+                field_entry.name()
+            ))),
+        }
+        Ok(QueryDsl::SpanPrefix {
+            field: field_name(schema, self.field()),
+            prefix: self.prefix.text().to_string(),
+            max_expansions: max_expansions_to_dsl(self.max_expansions),
+        })
+    }
+}
+
+impl SpanQuery for SpanPrefixQuery {
+    fn field(&self) -> Field {
+        self.prefix.field()
+    }
+
+    fn span_terms(&self, _terms: &mut Vec<Term>) {}
+
+    fn span_weight(&self, searcher: &Searcher, scoring_enabled: bool) -> Result<Box<SpanWeight>> {
+        Ok(Box::new(
+            self.specialized_weight(searcher, scoring_enabled)?,
+        ))
+    }
+
+    fn rewrite_span(self: Box<Self>, searcher: &Searcher) -> Result<Box<SpanQuery>> {
+        match self.expanded_query(searcher)? {
+            Some(span_or_query) => Ok(Box::new(span_or_query)),
+            None => Ok(self),
+        }
+    }
+}
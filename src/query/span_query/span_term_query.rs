@@ -0,0 +1,188 @@
+use super::{check_has_positions, explain_spans, span_freq, span_similarity_weight};
+use super::{Span, SpanQuery, SpanScorer, SpanWeight};
+use core::Searcher;
+use core::SegmentReader;
+use docset::{DocSet, SkipResult};
+use fieldnorm::FieldNormReader;
+use postings::{Postings, SegmentPostings};
+use query::bm25::BM25Weight;
+use query::explanation::term_description;
+use query::query_dsl::{field_name, term_value_to_json};
+use query::{Explanation, Query, QueryDsl, Scorer, Weight};
+use schema::{Field, IndexRecordOption, Schema, Term};
+use std::collections::BTreeSet;
+use DocId;
+use Result;
+use Score;
+
+/// `SpanTermQuery` matches the positions of a single term.
+///
+/// It is the building block of all of the other span queries.
+/// Each occurrence of the term at position `p` is matched
+/// by the span `[p, p + 1)`.
+#[derive(Debug)]
+pub struct SpanTermQuery {
+    term: Term,
+}
+
+impl SpanTermQuery {
+    /// Creates a new `SpanTermQuery`.
+    pub fn new(term: Term) -> SpanTermQuery {
+        SpanTermQuery { term }
+    }
+
+    fn specialized_weight(
+        &self,
+        searcher: &Searcher,
+        scoring_enabled: bool,
+    ) -> Result<SpanTermWeight> {
+        check_has_positions(searcher, self.term.field())?;
+        Ok(SpanTermWeight {
+            term: self.term.clone(),
+            similarity_weight: span_similarity_weight(self, searcher, scoring_enabled),
+        })
+    }
+}
+
+impl Query for SpanTermQuery {
+    fn weight(&self, searcher: &Searcher, scoring_enabled: bool) -> Result<Box<Weight>> {
+        Ok(Box::new(
+            self.specialized_weight(searcher, scoring_enabled)?,
+        ))
+    }
+
+    fn query_terms(&self, term_set: &mut BTreeSet<Term>) {
+        term_set.insert(self.term.clone());
+    }
+
+    fn to_dsl(&self, schema: &Schema) -> Result<QueryDsl> {
+        Ok(QueryDsl::SpanTerm {
+            field: field_name(schema, self.term.field()),
+            value: term_value_to_json(schema, &self.term),
+        })
+    }
+}
+
+impl SpanQuery for SpanTermQuery {
+    fn field(&self) -> Field {
+        self.term.field()
+    }
+
+    fn span_terms(&self, terms: &mut Vec<Term>) {
+        terms.push(self.term.clone());
+    }
+
+    fn span_weight(&self, searcher: &Searcher, scoring_enabled: bool) -> Result<Box<SpanWeight>> {
+        Ok(Box::new(
+            self.specialized_weight(searcher, scoring_enabled)?,
+        ))
+    }
+
+    fn rewrite_span(self: Box<Self>, _searcher: &Searcher) -> Result<Box<SpanQuery>> {
+        Ok(self)
+    }
+}
+
+struct SpanTermWeight {
+    term: Term,
+    similarity_weight: BM25Weight,
+}
+
+impl Weight for SpanTermWeight {
+    fn scorer(&self, reader: &SegmentReader) -> Result<Box<Scorer>> {
+        Ok(Box::new(self.span_scorer(reader)?))
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> Result<Explanation> {
+        let description = format!(
+            "SpanTermQuery({})",
+            term_description(reader.schema(), &self.term)
+        );
+        explain_spans(
+            self,
+            &description,
+            self.term.field(),
+            &self.similarity_weight,
+            reader,
+            doc,
+        )
+    }
+}
+
+impl SpanWeight for SpanTermWeight {
+    fn span_scorer(&self, reader: &SegmentReader) -> Result<Box<SpanScorer>> {
+        let field = self.term.field();
+        let postings = reader
+            .inverted_index(field)
+            .read_postings(&self.term, IndexRecordOption::WithFreqsAndPositions)
+            .unwrap_or_else(SegmentPostings::empty);
+        Ok(Box::new(SpanTermScorer {
+            postings,
+            positions: Vec::new(),
+            spans: Vec::new(),
+            fieldnorm_reader: reader.get_fieldnorms_reader(field),
+            similarity_weight: self.similarity_weight.clone(),
+        }))
+    }
+}
+
+struct SpanTermScorer {
+    postings: SegmentPostings,
+    positions: Vec<u32>,
+    spans: Vec<Span>,
+    fieldnorm_reader: FieldNormReader,
+    similarity_weight: BM25Weight,
+}
+
+impl SpanTermScorer {
+    fn load_spans(&mut self) {
+        self.postings.positions(&mut self.positions);
+        self.spans.clear();
+        self.spans
+            .extend(self.positions.iter().map(|&position| Span {
+                start: position,
+                end: position + 1,
+            }));
+    }
+}
+
+impl DocSet for SpanTermScorer {
+    fn advance(&mut self) -> bool {
+        if self.postings.advance() {
+            self.load_spans();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn skip_next(&mut self, target: DocId) -> SkipResult {
+        let skip_result = self.postings.skip_next(target);
+        if skip_result != SkipResult::End {
+            self.load_spans();
+        }
+        skip_result
+    }
+
+    fn doc(&self) -> DocId {
+        self.postings.doc()
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.postings.size_hint()
+    }
+}
+
+impl Scorer for SpanTermScorer {
+    fn score(&mut self) -> Score {
+        let fieldnorm_id = self.fieldnorm_reader.fieldnorm_id(self.doc());
+        self.similarity_weight
+            .score_freq(fieldnorm_id, span_freq(&self.spans))
+    }
+}
+
+impl SpanScorer for SpanTermScorer {
+    fn spans(&self) -> &[Span] {
+        &self.spans
+    }
+}
@@ -1,8 +1,12 @@
 use super::term_weight::TermWeight;
 use query::bm25::BM25Weight;
+use query::query_dsl::{field_name, index_record_option_to_dsl, term_value_to_json};
 use query::Query;
+use query::QueryDsl;
 use query::Weight;
 use schema::IndexRecordOption;
+use schema::Schema;
+use std::collections::BTreeSet;
 use Result;
 use Searcher;
 use Term;
@@ -52,4 +56,16 @@ impl Query for TermQuery {
     fn weight(&self, searcher: &Searcher, scoring_enabled: bool) -> Result<Box<Weight>> {
         Ok(Box::new(self.specialized_weight(searcher, scoring_enabled)))
     }
+
+    fn query_terms(&self, term_set: &mut BTreeSet<Term>) {
+        term_set.insert(self.term.clone());
+    }
+
+    fn to_dsl(&self, schema: &Schema) -> Result<QueryDsl> {
+        Ok(QueryDsl::Term {
+            field: field_name(schema, self.term.field()),
+            value: term_value_to_json(schema, &self.term),
+            index_record_option: index_record_option_to_dsl(self.index_record_option),
+        })
+    }
 }
@@ -26,6 +26,25 @@ impl TermScorer {
             similarity_weight,
         }
     }
+
+    /// Returns an upper bound of the score of all of the documents.
+    pub(crate) fn max_score(&self) -> Score {
+        self.similarity_weight.max_score()
+    }
+
+    /// Returns an upper bound of the score of the documents
+    /// of the current block of postings.
+    pub(crate) fn block_max_score(&self) -> Score {
+        self.similarity_weight.score(
+            self.postings.block_min_fieldnorm_id(),
+            self.postings.block_max_term_freq(),
+        )
+    }
+
+    /// Returns the last doc of the current block of postings.
+    pub(crate) fn block_last_doc(&self) -> DocId {
+        self.postings.block_last_doc()
+    }
 }
 
 impl DocSet for TermScorer {
@@ -1,11 +1,14 @@
 use super::term_scorer::TermScorer;
 use core::SegmentReader;
-use docset::DocSet;
-use postings::SegmentPostings;
+use docset::{DocSet, SkipResult};
+use postings::{Postings, SegmentPostings};
 use query::bm25::BM25Weight;
+use query::explanation::{does_not_match, term_description};
+use query::Explanation;
 use query::Scorer;
 use query::Weight;
 use schema::IndexRecordOption;
+use DocId;
 use Result;
 use Term;
 
@@ -50,6 +53,29 @@ impl Weight for TermWeight {
             Ok(self.scorer(reader)?.count())
         }
     }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> Result<Explanation> {
+        let field = self.term.field();
+        let mut postings = reader
+            .inverted_index(field)
+            .read_postings(&self.term, self.index_record_option)
+            .ok_or_else(|| does_not_match(doc))?;
+        if postings.skip_next(doc) != SkipResult::Reached {
+            return Err(does_not_match(doc));
+        }
+        let fieldnorm_id = reader.get_fieldnorms_reader(field).fieldnorm_id(doc);
+        let similarity_explanation = self.similarity_weight
+            .explain(fieldnorm_id, postings.term_freq() as f32);
+        let mut explanation = Explanation::new(
+            format!(
+                "TermQuery({}), result of:",
+                term_description(reader.schema(), &self.term)
+            ),
+            similarity_explanation.value(),
+        );
+        explanation.add_detail(similarity_explanation);
+        Ok(explanation)
+    }
 }
 
 impl TermWeight {
@@ -0,0 +1,206 @@
+use common::BitSet;
+use core::InvertedIndexReader;
+use core::Searcher;
+use core::SegmentReader;
+use query::query_dsl::{field_name, term_bytes_to_json};
+use query::BitSetDocSet;
+use query::ConstScorer;
+use query::{Query, QueryDsl, Scorer, Weight};
+use schema::{Field, IndexRecordOption, Schema, Term};
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use Result;
+
+/// `TermSetQuery` matches all of the documents containing
+/// at least one term of a given set of terms.
+///
+/// It is typically used to filter documents over a long
+/// list of ids (e.g. for access control).
+/// Matched documents all get a constant `Score` of one.
+///
+/// The terms may belong to different fields.
+///
+/// # Implementation
+///
+/// Unlike a `BooleanQuery` with one `TermQuery` per term, the
+/// `TermSetQuery` does not open one scorer per term.
+/// The terms are sorted, and the term dictionary of each field is
+/// walked once per segment, alongside the sorted terms.
+/// The documents of the matching terms are appended into a `BitSet`.
+#[derive(Debug)]
+pub struct TermSetQuery {
+    // sorted and deduplicated term values, per field.
+    terms_per_field: BTreeMap<Field, Vec<Vec<u8>>>,
+}
+
+impl TermSetQuery {
+    /// Creates a new `TermSetQuery` given a list of terms.
+    pub fn new(terms: Vec<Term>) -> TermSetQuery {
+        let mut terms_per_field: BTreeMap<Field, Vec<Vec<u8>>> = BTreeMap::new();
+        for term in &terms {
+            terms_per_field
+                .entry(term.field())
+                .or_insert_with(Vec::new)
+                .push(term.value_bytes().to_owned());
+        }
+        for field_terms in terms_per_field.values_mut() {
+            field_terms.sort();
+            field_terms.dedup();
+        }
+        TermSetQuery { terms_per_field }
+    }
+}
+
+impl Query for TermSetQuery {
+    fn weight(&self, _searcher: &Searcher, _scoring_enabled: bool) -> Result<Box<Weight>> {
+        Ok(Box::new(TermSetWeight {
+            terms_per_field: self.terms_per_field.clone(),
+        }))
+    }
+
+    fn query_terms(&self, term_set: &mut BTreeSet<Term>) {
+        for (&field, field_terms) in &self.terms_per_field {
+            for value_bytes in field_terms {
+                let mut term = Term::for_field(field);
+                term.set_bytes(value_bytes);
+                term_set.insert(term);
+            }
+        }
+    }
+
+    fn to_dsl(&self, schema: &Schema) -> Result<QueryDsl> {
+        let terms = self
+            .terms_per_field
+            .iter()
+            .map(|(&field, field_terms)| {
+                let values = field_terms
+                    .iter()
+                    .map(|value_bytes| term_bytes_to_json(schema, field, value_bytes))
+                    .collect();
+                (field_name(schema, field), values)
+            })
+            .collect();
+        Ok(QueryDsl::TermSet { terms })
+    }
+}
+
+struct TermSetWeight {
+    terms_per_field: BTreeMap<Field, Vec<Vec<u8>>>,
+}
+
+/// Appends the documents of all of the `sorted_terms` present in the
+/// inverted index into the `BitSet`.
+///
+/// The term dictionary is streamed over the range of the sorted terms,
+/// and merged with them.
+fn append_term_set_docs(
+    inverted_index: &InvertedIndexReader,
+    sorted_terms: &[Vec<u8>],
+    doc_bitset: &mut BitSet,
+) {
+    let (first_term, last_term) = match (sorted_terms.first(), sorted_terms.last()) {
+        (Some(first_term), Some(last_term)) => (first_term, last_term),
+        _ => {
+            return;
+        }
+    };
+    let mut term_stream = inverted_index
+        .terms()
+        .range()
+        .ge(first_term)
+        .le(last_term)
+        .into_stream();
+    let mut sorted_terms_it = sorted_terms.iter().peekable();
+    while term_stream.advance() {
+        let key = term_stream.key();
+        while sorted_terms_it
+            .peek()
+            .map(|term| &term[..] < key)
+            .unwrap_or(false)
+        {
+            sorted_terms_it.next();
+        }
+        match sorted_terms_it.peek() {
+            Some(term) if &term[..] == key => {}
+            Some(_) => {
+                continue;
+            }
+            None => {
+                return;
+            }
+        }
+        let mut block_segment_postings = inverted_index
+            .read_block_postings_from_terminfo(term_stream.value(), IndexRecordOption::Basic);
+        while block_segment_postings.advance() {
+            for &doc in block_segment_postings.docs() {
+                doc_bitset.insert(doc);
+            }
+        }
+    }
+}
+
+impl Weight for TermSetWeight {
+    fn scorer(&self, reader: &SegmentReader) -> Result<Box<Scorer>> {
+        let mut doc_bitset = BitSet::with_max_value(reader.max_doc());
+        for (&field, sorted_terms) in &self.terms_per_field {
+            let inverted_index = reader.inverted_index(field);
+            append_term_set_docs(&inverted_index, sorted_terms, &mut doc_bitset);
+        }
+        Ok(Box::new(ConstScorer::new(BitSetDocSet::from(doc_bitset))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::TermSetQuery;
+    use collector::tests::matching_docs;
+    use query::Query;
+    use schema::{SchemaBuilder, INT_INDEXED, STRING};
+    use Index;
+    use Term;
+
+    #[test]
+    fn test_term_set_query() {
+        let mut schema_builder = SchemaBuilder::default();
+        let id = schema_builder.add_u64_field("id", INT_INDEXED);
+        let tenant = schema_builder.add_text_field("tenant", STRING);
+        let index = Index::create_in_ram(schema_builder.build());
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 40_000_000).unwrap();
+            for i in 0u64..1_000u64 {
+                let tenant_name = format!("tenant{}", i % 7);
+                index_writer.add_document(doc!(id => i, tenant => tenant_name.as_str()));
+            }
+            index_writer.commit().unwrap();
+        }
+        index.load_searchers().unwrap();
+        let searcher = index.searcher();
+        {
+            let terms = (0u64..2_000u64)
+                .rev()
+                .filter(|i| i % 3 == 0)
+                .map(|i| Term::from_field_u64(id, i))
+                .collect();
+            let query = TermSetQuery::new(terms);
+            let expected: Vec<u32> = (0u32..1_000u32).filter(|i| i % 3 == 0).collect();
+            assert_eq!(matching_docs(&searcher, &query), expected);
+            assert_eq!(query.count(&*searcher).unwrap(), expected.len());
+        }
+        {
+            let query = TermSetQuery::new(vec![
+                Term::from_field_u64(id, 1),
+                Term::from_field_u64(id, 1),
+                Term::from_field_u64(id, 5_000),
+                Term::from_field_text(tenant, "tenant3"),
+                Term::from_field_text(tenant, "unknown"),
+            ]);
+            let expected: Vec<u32> = (0u32..1_000u32).filter(|&i| i == 1 || i % 7 == 3).collect();
+            assert_eq!(matching_docs(&searcher, &query), expected);
+        }
+        {
+            let query = TermSetQuery::new(vec![]);
+            assert!(matching_docs(&searcher, &query).is_empty());
+        }
+    }
+}
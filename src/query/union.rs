@@ -45,6 +45,20 @@ where
     TScorer: Scorer,
 {
     fn from(docsets: Vec<TScorer>) -> Union<TScorer, TScoreCombiner> {
+        Union::with_score_combiner(docsets, TScoreCombiner::default())
+    }
+}
+
+impl<TScorer: Scorer, TScoreCombiner: ScoreCombiner> Union<TScorer, TScoreCombiner> {
+    /// Creates a `Union` in which the scores of each document are
+    /// aggregated by copies of the given `score_combiner`.
+    ///
+    /// This makes it possible to use a `ScoreCombiner` carrying
+    /// some parameters.
+    pub(crate) fn with_score_combiner(
+        docsets: Vec<TScorer>,
+        score_combiner: TScoreCombiner,
+    ) -> Union<TScorer, TScoreCombiner> {
         let non_empty_docsets: Vec<TScorer> = docsets
             .into_iter()
             .flat_map(
@@ -60,7 +74,7 @@ where
         Union {
             docsets: non_empty_docsets,
             bitsets: Box::new([TinySet::empty(); HORIZON_NUM_TINYBITSETS]),
-            scores: Box::new([TScoreCombiner::default(); HORIZON as usize]),
+            scores: Box::new([score_combiner; HORIZON as usize]),
             cursor: HORIZON_NUM_TINYBITSETS,
             offset: 0,
             doc: 0,
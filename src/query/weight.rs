@@ -1,5 +1,9 @@
+use super::explanation::does_not_match;
+use super::Explanation;
 use super::Scorer;
 use core::SegmentReader;
+use docset::{DocSet, SkipResult};
+use DocId;
 use Result;
 
 /// A Weight is the specialization of a Query
@@ -11,8 +15,33 @@ pub trait Weight {
     /// See [`Query`](./trait.Query.html).
     fn scorer(&self, reader: &SegmentReader) -> Result<Box<Scorer>>;
 
+    /// Returns the scorer for the given segment, when only the
+    /// top documents are collected.
+    ///
+    /// The scorer may then skip the documents whose score cannot exceed the
+    /// [`score_threshold`](../collector/trait.Collector.html#method.score_threshold)
+    /// of the collector. By default, it is the regular scorer.
+    fn top_k_scorer(&self, reader: &SegmentReader) -> Result<Box<Scorer>> {
+        self.scorer(reader)
+    }
+
     /// Returns the number documents within the given `SegmentReader`.
     fn count(&self, reader: &SegmentReader) -> Result<u32> {
         Ok(self.scorer(reader)?.count())
     }
+
+    /// Returns an `Explanation` for the score of the document `doc`
+    /// within the given `SegmentReader`.
+    ///
+    /// Returns an error if the document does not match.
+    ///
+    /// The default implementation only reports the score
+    /// computed by the scorer, without any detail.
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> Result<Explanation> {
+        let mut scorer = self.scorer(reader)?;
+        if scorer.skip_next(doc) != SkipResult::Reached {
+            return Err(does_not_match(doc));
+        }
+        Ok(Explanation::new("score", scorer.score()))
+    }
 }
@@ -0,0 +1,189 @@
+use error::ErrorKind;
+use fst_regex::Regex;
+use query::automaton_weight::DEFAULT_MAX_EXPANSIONS;
+use query::query_dsl::{field_name, max_expansions_to_dsl};
+use query::{AutomatonWeight, Query, QueryDsl, Weight};
+use schema::{Field, Schema, Type};
+use Result;
+use Searcher;
+
+/// Translates a wildcard pattern into an equivalent regular expression.
+///
+/// `*` matches any sequence of characters, `?` matches exactly one character,
+/// and `\` escapes the next character.
+/// All of the other characters are matched literally.
+fn wildcard_to_regex(pattern: &str) -> String {
+    let mut regex = String::from("(?s)");
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' => regex.push_str(".*"),
+            '?' => regex.push('.'),
+            '\\' => {
+                if let Some(escaped) = chars.next() {
+                    push_literal(&mut regex, escaped);
+                } else {
+                    push_literal(&mut regex, '\\');
+                }
+            }
+            _ => push_literal(&mut regex, c),
+        }
+    }
+    regex
+}
+
+fn push_literal(regex: &mut String, c: char) {
+    match c {
+        '\\' | '.' | '+' | '*' | '?' | '(' | ')' | '|' | '[' | ']' | '{' | '}' | '^' | '$' => {
+            regex.push('\\');
+            regex.push(c);
+        }
+        _ => regex.push(c),
+    }
+}
+
+/// `WildcardQuery` matches all of the documents containing a term
+/// matching a wildcard pattern.
+///
+/// The pattern must match the entire term, and supports
+///
+/// * `*` matches any sequence of characters (possibly empty).
+/// * `?` matches exactly one character.
+/// * `\` escapes the next character, so that `\*` matches a literal `*`.
+///
+/// For instance, `f?o*bar` matches `foobar`, `fxobar` and `foo_and_bar`
+/// but does not match `fobar`.
+///
+/// Matched documents all get a constant `Score` of one.
+///
+/// # Implementation
+///
+/// The pattern is compiled into an automaton, which is intersected
+/// with the term dictionary of each segment.
+///
+/// The number of terms the pattern may expand to within a segment is capped
+/// (1,024 by default. See [`.set_max_expansions(...)`](#method.set_max_expansions).)
+/// If the cap is exceeded, the search fails with
+/// a `TooManyTerms` error.
+#[derive(Debug)]
+pub struct WildcardQuery {
+    field: Field,
+    pattern: String,
+    max_expansions: usize,
+}
+
+impl WildcardQuery {
+    /// Creates a new `WildcardQuery` for the given field and pattern.
+    pub fn new(field: Field, pattern: &str) -> WildcardQuery {
+        WildcardQuery {
+            field,
+            pattern: pattern.to_string(),
+            max_expansions: DEFAULT_MAX_EXPANSIONS,
+        }
+    }
+
+    /// Sets the maximum number of terms the pattern is allowed
+    /// to expand to within a segment.
+    pub fn set_max_expansions(&mut self, max_expansions: usize) {
+        self.max_expansions = max_expansions;
+    }
+
+    fn specialized_weight(&self, searcher: &Searcher) -> Result<AutomatonWeight<Regex>> {
+        let value_type = searcher
+            .schema()
+            .get_field_entry(self.field)
+            .field_type()
+            .value_type();
+        if value_type != Type::Str {
+            let err_msg = format!(
+                "Create a wildcard query on a field of type {:?}, \
+                 when only fields of type {:?} are supported",
+                value_type,
+                Type::Str
+            );
+            bail!(ErrorKind::SchemaError(err_msg))
+        }
+        let automaton = Regex::new(&wildcard_to_regex(&self.pattern)).map_err(|err| {
+            ErrorKind::InvalidArgument(format!("Invalid wildcard {:?}: {}", self.pattern, err))
+        })?;
+        let mut automaton_weight = AutomatonWeight::new(self.field, automaton);
+        automaton_weight.set_max_expansions(self.max_expansions);
+        Ok(automaton_weight)
+    }
+}
+
+impl Query for WildcardQuery {
+    fn weight(&self, searcher: &Searcher, _scoring_enabled: bool) -> Result<Box<Weight>> {
+        Ok(Box::new(self.specialized_weight(searcher)?))
+    }
+
+    fn rewrite(self: Box<Self>, searcher: &Searcher) -> Result<Box<Query>> {
+        let term_set_query = self.specialized_weight(searcher)?.term_set_query(searcher)?;
+        Ok(Box::new(term_set_query))
+    }
+
+    fn to_dsl(&self, schema: &Schema) -> Result<QueryDsl> {
+        Ok(QueryDsl::Wildcard {
+            field: field_name(schema, self.field),
+            pattern: self.pattern.clone(),
+            max_expansions: max_expansions_to_dsl(self.max_expansions),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{wildcard_to_regex, WildcardQuery};
+    use collector::tests::matching_docs;
+    use collector::CountCollector;
+    use error::ErrorKind;
+    use schema::{SchemaBuilder, STRING};
+    use Index;
+
+    #[test]
+    fn test_wildcard_to_regex() {
+        assert_eq!(wildcard_to_regex("f?o*bar"), "(?s)f.o.*bar");
+        assert_eq!(wildcard_to_regex("a.b"), "(?s)a\\.b");
+        assert_eq!(wildcard_to_regex("a\\*b\\?"), "(?s)a\\*b\\?");
+        assert_eq!(wildcard_to_regex("a\\"), "(?s)a\\\\");
+    }
+
+    #[test]
+    fn test_wildcard_query() {
+        let mut schema_builder = SchemaBuilder::new();
+        let field = schema_builder.add_text_field("name", STRING);
+        let index = Index::create_in_ram(schema_builder.build());
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 10_000_000).unwrap();
+            for &name in &["fooBar", "fxobar", "foo_and_bar", "fobar", "f*o", "föo"] {
+                index_writer.add_document(doc!(field => name));
+            }
+            index_writer.commit().unwrap();
+        }
+        index.load_searchers().unwrap();
+        let searcher = index.searcher();
+        assert_eq!(
+            matching_docs(&searcher, &WildcardQuery::new(field, "f?o*bar")),
+            vec![1, 2]
+        );
+        assert_eq!(
+            matching_docs(&searcher, &WildcardQuery::new(field, "f?o*")),
+            vec![0, 1, 2, 4, 5]
+        );
+        assert_eq!(
+            matching_docs(&searcher, &WildcardQuery::new(field, "f\\*o")),
+            vec![4]
+        );
+        assert_eq!(
+            matching_docs(&searcher, &WildcardQuery::new(field, "*")).len(),
+            6
+        );
+        {
+            let mut query = WildcardQuery::new(field, "f*");
+            query.set_max_expansions(3);
+            let mut count_collector = CountCollector::default();
+            let err = searcher.search(&query, &mut count_collector).unwrap_err();
+            assert_matches!(err.kind(), &ErrorKind::TooManyTerms(3));
+        }
+    }
+}
@@ -92,6 +92,11 @@ impl Document {
         self.add(FieldValue::new(field, Value::Bytes(value)))
     }
 
+    /// Add a geo point field
+    pub fn add_geo_point(&mut self, field: Field, value: GeoPoint) {
+        self.add(FieldValue::new(field, Value::GeoPoint(value)))
+    }
+
     /// Add a field value
     pub fn add(&mut self, field_value: FieldValue) {
         self.field_values.push(field_value);
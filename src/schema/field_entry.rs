@@ -64,6 +64,14 @@ impl FieldEntry {
         }
     }
 
+    /// Creates a field entry for a geo point field
+    pub fn new_geo_point(field_name: String) -> FieldEntry {
+        FieldEntry {
+            name: field_name,
+            field_type: FieldType::GeoPoint,
+        }
+    }
+
     /// Returns the name of the field
     pub fn name(&self) -> &str {
         &self.name
@@ -80,7 +88,7 @@ impl FieldEntry {
             FieldType::Str(ref options) => options.get_indexing_options().is_some(),
             FieldType::U64(ref options) | FieldType::I64(ref options) => options.is_indexed(),
             FieldType::HierarchicalFacet => true,
-            FieldType::Bytes => false,
+            FieldType::Bytes | FieldType::GeoPoint => false,
         }
     }
 
@@ -99,7 +107,7 @@ impl FieldEntry {
             FieldType::Str(ref options) => options.is_stored(),
             // TODO make stored hierarchical facet optional
             FieldType::HierarchicalFacet => true,
-            FieldType::Bytes => false,
+            FieldType::Bytes | FieldType::GeoPoint => false,
         }
     }
 }
@@ -131,6 +139,9 @@ impl Serialize for FieldEntry {
             FieldType::Bytes => {
                 s.serialize_field("type", "bytes")?;
             }
+            FieldType::GeoPoint => {
+                s.serialize_field("type", "geo_point")?;
+            }
         }
 
         s.end()
@@ -188,6 +199,9 @@ impl<'de> Deserialize<'de> for FieldEntry {
                                 "bytes" => {
                                     field_type = Some(FieldType::Bytes);
                                 }
+                                "geo_point" => {
+                                    field_type = Some(FieldType::GeoPoint);
+                                }
                                 "text" | "u64" | "i64" => {
                                     // These types require additional options to create a field_type
                                 }
@@ -3,6 +3,7 @@ use base64::decode;
 use schema::{IntOptions, TextOptions};
 
 use schema::Facet;
+use schema::GeoPoint;
 use schema::IndexRecordOption;
 use schema::Value;
 use serde_json::Value as JsonValue;
@@ -38,6 +39,8 @@ pub enum Type {
     HierarchicalFacet,
     /// `Vec<u8>`
     Bytes,
+    /// `tantivy::schema::GeoPoint`. Passed as a `{"lat": .., "lon": ..}` object in JSON.
+    GeoPoint,
 }
 
 /// A `FieldType` describes the type (text, u64) of a field as well as
@@ -54,6 +57,8 @@ pub enum FieldType {
     HierarchicalFacet,
     /// Bytes (one per document)
     Bytes,
+    /// Geo point (one per document)
+    GeoPoint,
 }
 
 impl FieldType {
@@ -65,6 +70,7 @@ impl FieldType {
             FieldType::I64(_) => Type::I64,
             FieldType::HierarchicalFacet => Type::HierarchicalFacet,
             FieldType::Bytes => Type::Bytes,
+            FieldType::GeoPoint => Type::GeoPoint,
         }
     }
 
@@ -76,7 +82,7 @@ impl FieldType {
                 int_options.is_indexed()
             }
             FieldType::HierarchicalFacet => true,
-            FieldType::Bytes => false,
+            FieldType::Bytes | FieldType::GeoPoint => false,
         }
     }
 
@@ -97,7 +103,7 @@ impl FieldType {
                 }
             }
             FieldType::HierarchicalFacet => Some(IndexRecordOption::Basic),
-            FieldType::Bytes => None,
+            FieldType::Bytes | FieldType::GeoPoint => None,
         }
     }
 
@@ -113,6 +119,10 @@ impl FieldType {
                 FieldType::U64(_) | FieldType::I64(_) => Err(ValueParsingError::TypeError(
                     format!("Expected an integer, got {:?}", json),
                 )),
+                FieldType::GeoPoint => Err(ValueParsingError::TypeError(format!(
+                    "Expected a geo point, got {:?}",
+                    json
+                ))),
                 FieldType::HierarchicalFacet => Ok(Value::Facet(Facet::from(field_text))),
                 FieldType::Bytes => decode(field_text).map(Value::Bytes).map_err(|_| {
                     ValueParsingError::InvalidBase64(format!(
@@ -142,7 +152,27 @@ impl FieldType {
                     let msg = format!("Expected a string, got {:?}", json);
                     Err(ValueParsingError::TypeError(msg))
                 }
+                FieldType::GeoPoint => {
+                    let msg = format!("Expected a geo point, got {:?}", json);
+                    Err(ValueParsingError::TypeError(msg))
+                }
             },
+            JsonValue::Object(ref json_obj) if *self == FieldType::GeoPoint => {
+                let coordinate = |name: &str| json_obj.get(name).and_then(|val| val.as_f64());
+                match (coordinate("lat"), coordinate("lon")) {
+                    (Some(lat), Some(lon)) if GeoPoint::is_valid(lat, lon) => {
+                        Ok(Value::GeoPoint(GeoPoint::new(lat, lon)))
+                    }
+                    _ => {
+                        let msg = format!(
+                            "Expected a geo point with a `lat` within [-90, 90] \
+                             and a `lon` within [-180, 180], got {:?}",
+                            json
+                        );
+                        Err(ValueParsingError::TypeError(msg))
+                    }
+                }
+            }
             _ => {
                 let msg = format!(
                     "Json value not supported error {:?}. Expected {:?}",
@@ -158,7 +188,7 @@ impl FieldType {
 mod tests {
     use super::FieldType;
     use schema::field_type::ValueParsingError;
-    use schema::Value;
+    use schema::{GeoPoint, Value};
 
     #[test]
     fn test_bytes_value_from_json() {
@@ -179,4 +209,24 @@ mod tests {
             _ => panic!("Expected parse failure for invalid base64"),
         }
     }
+
+    #[test]
+    fn test_geo_point_value_from_json() {
+        let result = FieldType::GeoPoint
+            .value_from_json(&json!({"lat": 48.8566, "lon": 2.3522}))
+            .unwrap();
+        assert_eq!(result, Value::GeoPoint(GeoPoint::new(48.8566, 2.3522)));
+
+        for json in &[
+            json!({"lat": 148.8566, "lon": 2.3522}),
+            json!({"lat": 48.8566}),
+            json!("48.8566,2.3522"),
+            json!(48),
+        ] {
+            match FieldType::GeoPoint.value_from_json(json) {
+                Err(ValueParsingError::TypeError(_)) => {}
+                _ => panic!("Expected parse failure for {:?}", json),
+            }
+        }
+    }
 }
@@ -0,0 +1,186 @@
+use serde::de;
+use serde::{Deserialize, Deserializer};
+use std::cmp::Ordering;
+
+/// Mean radius of the earth, in meters.
+const EARTH_RADIUS_IN_METERS: f64 = 6_371_008.8;
+
+/// Largest quantized coordinate.
+///
+/// Quantized coordinates are within `[1, MAX_QUANTIZED]`, so that
+/// the fast field value `0` can be used for documents without any geo point.
+const MAX_QUANTIZED: u64 = (1u64 << 32) - 1;
+
+/// A point on the earth, given by its latitude and
+/// longitude in degrees.
+///
+/// Geo points are the values of the `GeoPoint` fields.
+/// They are stored in a fast field, with a precision
+/// of about a centimeter.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct GeoPoint {
+    lat: f64,
+    lon: f64,
+}
+
+impl<'de> Deserialize<'de> for GeoPoint {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(deny_unknown_fields)]
+        struct RawGeoPoint {
+            lat: f64,
+            lon: f64,
+        }
+        let RawGeoPoint { lat, lon } = RawGeoPoint::deserialize(deserializer)?;
+        if !GeoPoint::is_valid(lat, lon) {
+            return Err(de::Error::custom(format!(
+                "Invalid geo point (lat: {}, lon: {})",
+                lat, lon
+            )));
+        }
+        Ok(GeoPoint { lat, lon })
+    }
+}
+
+impl GeoPoint {
+    /// Creates a new `GeoPoint`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the latitude is not within `[-90, 90]`,
+    /// or if the longitude is not within `[-180, 180]`.
+    pub fn new(lat: f64, lon: f64) -> GeoPoint {
+        assert!(
+            GeoPoint::is_valid(lat, lon),
+            "Invalid geo point (lat: {}, lon: {})",
+            lat,
+            lon
+        );
+        GeoPoint { lat, lon }
+    }
+
+    /// Returns true iff the latitude and the longitude are
+    /// within their respective range.
+    pub fn is_valid(lat: f64, lon: f64) -> bool {
+        lat >= -90f64 && lat <= 90f64 && lon >= -180f64 && lon <= 180f64
+    }
+
+    /// Returns the latitude, in degrees.
+    pub fn lat(&self) -> f64 {
+        self.lat
+    }
+
+    /// Returns the longitude, in degrees.
+    pub fn lon(&self) -> f64 {
+        self.lon
+    }
+
+    /// Returns the great-circle distance to another point, in meters,
+    /// computed with the haversine formula.
+    pub fn distance(&self, other: &GeoPoint) -> f64 {
+        let (lat1, lat2) = (self.lat.to_radians(), other.lat.to_radians());
+        let half_delta_lat = (lat2 - lat1) / 2f64;
+        let half_delta_lon = (other.lon - self.lon).to_radians() / 2f64;
+        let haversine =
+            half_delta_lat.sin().powi(2) + lat1.cos() * lat2.cos() * half_delta_lon.sin().powi(2);
+        2f64 * EARTH_RADIUS_IN_METERS * haversine.sqrt().min(1f64).asin()
+    }
+
+    /// Encodes the point as a fast field value,
+    /// the quantized latitude taking the 32 higher bits.
+    ///
+    /// The resulting value is never `0`.
+    pub(crate) fn to_u64(&self) -> u64 {
+        let lat_quantized = quantize(self.lat, 90f64);
+        let lon_quantized = quantize(self.lon, 180f64);
+        (lat_quantized << 32) | lon_quantized
+    }
+
+    /// Decodes a point encoded with `.to_u64()`.
+    ///
+    /// Returns `None` if the value is `0`, that is for documents
+    /// without any geo point.
+    pub(crate) fn from_u64(val: u64) -> Option<GeoPoint> {
+        if val == 0u64 {
+            return None;
+        }
+        Some(GeoPoint {
+            lat: unquantize(val >> 32, 90f64),
+            lon: unquantize(val & MAX_QUANTIZED, 180f64),
+        })
+    }
+}
+
+fn quantize(coordinate: f64, max_abs: f64) -> u64 {
+    let ratio = (coordinate + max_abs) / (2f64 * max_abs);
+    1u64 + (ratio * (MAX_QUANTIZED - 1) as f64).round() as u64
+}
+
+fn unquantize(quantized: u64, max_abs: f64) -> f64 {
+    let ratio = (quantized - 1) as f64 / (MAX_QUANTIZED - 1) as f64;
+    ratio * 2f64 * max_abs - max_abs
+}
+
+// Coordinates are checked at construction, so that they are never `NaN`.
+impl Eq for GeoPoint {}
+
+impl Ord for GeoPoint {
+    fn cmp(&self, other: &GeoPoint) -> Ordering {
+        (self.lat, self.lon)
+            .partial_cmp(&(other.lat, other.lon))
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
+impl PartialOrd for GeoPoint {
+    fn partial_cmp(&self, other: &GeoPoint) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::GeoPoint;
+
+    #[test]
+    fn test_geo_point_encoding() {
+        for &(lat, lon) in &[
+            (0f64, 0f64),
+            (-90f64, -180f64),
+            (90f64, 180f64),
+            (48.8566f64, 2.3522f64),
+            (-33.8688f64, 151.2093f64),
+        ] {
+            let point = GeoPoint::new(lat, lon);
+            let val = point.to_u64();
+            assert!(val != 0u64);
+            let decoded = GeoPoint::from_u64(val).unwrap();
+            assert!((decoded.lat() - lat).abs() < 1e-7);
+            assert!((decoded.lon() - lon).abs() < 1e-7);
+            assert!(point.distance(&decoded) < 0.01f64);
+        }
+        assert_eq!(GeoPoint::from_u64(0u64), None);
+    }
+
+    #[test]
+    fn test_geo_point_distance() {
+        let paris = GeoPoint::new(48.8566, 2.3522);
+        let london = GeoPoint::new(51.5074, -0.1278);
+        let distance = paris.distance(&london);
+        assert!((distance - 343_500f64).abs() < 1_000f64);
+        assert_eq!(london.distance(&paris), distance);
+        assert_eq!(paris.distance(&paris), 0f64);
+        let antipode = GeoPoint::new(-48.8566, -177.6478);
+        assert!((paris.distance(&antipode) - 20_015_000f64).abs() < 1_000f64);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_geo_point_invalid() {
+        GeoPoint::new(91f64, 0f64);
+    }
+}
@@ -102,6 +102,7 @@ the field is required during scoring or collection for instance.
 
 mod document;
 mod facet;
+mod geo_point;
 mod schema;
 mod term;
 
@@ -124,6 +125,8 @@ pub use self::value::Value;
 pub use self::facet::Facet;
 pub use self::facet::FACET_SEP_BYTE;
 
+pub use self::geo_point::GeoPoint;
+
 pub use self::document::Document;
 pub use self::field::Field;
 pub use self::term::Term;
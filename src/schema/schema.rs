@@ -101,6 +101,12 @@ impl SchemaBuilder {
         self.add_field(field_entry)
     }
 
+    /// Adds a fast geo point field to the schema
+    pub fn add_geo_point_field(&mut self, field_name: &str) -> Field {
+        let field_entry = FieldEntry::new_geo_point(field_name.to_string());
+        self.add_field(field_entry)
+    }
+
     /// Adds a field entry to the schema in build.
     fn add_field(&mut self, field_entry: FieldEntry) -> Field {
         let field = Field(self.fields.len() as u32);
@@ -104,7 +104,7 @@ impl Term {
         self.set_u64(common::i64_to_u64(val));
     }
 
-    fn set_bytes(&mut self, bytes: &[u8]) {
+    pub(crate) fn set_bytes(&mut self, bytes: &[u8]) {
         self.0.resize(4, 0u8);
         self.0.extend(bytes);
     }
@@ -1,4 +1,4 @@
-use schema::Facet;
+use schema::{Facet, GeoPoint};
 use serde::de::Visitor;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::fmt;
@@ -17,6 +17,8 @@ pub enum Value {
     Facet(Facet),
     /// Arbitrarily sized byte array
     Bytes(Vec<u8>),
+    /// Geo point, given by its latitude and longitude
+    GeoPoint(GeoPoint),
 }
 
 impl Serialize for Value {
@@ -30,6 +32,7 @@ impl Serialize for Value {
             Value::I64(u) => serializer.serialize_i64(u),
             Value::Facet(ref facet) => facet.serialize(serializer),
             Value::Bytes(ref bytes) => serializer.serialize_bytes(bytes),
+            Value::GeoPoint(ref geo_point) => geo_point.serialize(serializer),
         }
     }
 }
@@ -102,6 +105,17 @@ impl Value {
             _ => panic!("This is not a text field."),
         }
     }
+
+    /// Returns the geo point, provided the value is of the `GeoPoint` type.
+    ///
+    /// # Panics
+    /// If the value is not of type `GeoPoint`
+    pub fn geo_point_value(&self) -> GeoPoint {
+        match *self {
+            Value::GeoPoint(ref value) => *value,
+            _ => panic!("This is not a geo point field."),
+        }
+    }
 }
 
 impl From<String> for Value {
@@ -140,10 +154,16 @@ impl From<Vec<u8>> for Value {
     }
 }
 
+impl From<GeoPoint> for Value {
+    fn from(geo_point: GeoPoint) -> Value {
+        Value::GeoPoint(geo_point)
+    }
+}
+
 mod binary_serialize {
     use super::Value;
     use common::BinarySerializable;
-    use schema::Facet;
+    use schema::{Facet, GeoPoint};
     use std::io::{self, Read, Write};
 
     const TEXT_CODE: u8 = 0;
@@ -151,6 +171,7 @@ mod binary_serialize {
     const I64_CODE: u8 = 2;
     const HIERARCHICAL_FACET_CODE: u8 = 3;
     const BYTES_CODE: u8 = 4;
+    const GEO_POINT_CODE: u8 = 5;
 
     impl BinarySerializable for Value {
         fn serialize<W: Write>(&self, writer: &mut W) -> io::Result<()> {
@@ -175,6 +196,11 @@ mod binary_serialize {
                     BYTES_CODE.serialize(writer)?;
                     bytes.serialize(writer)
                 }
+                Value::GeoPoint(ref geo_point) => {
+                    GEO_POINT_CODE.serialize(writer)?;
+                    geo_point.lat().to_bits().serialize(writer)?;
+                    geo_point.lon().to_bits().serialize(writer)
+                }
             }
         }
         fn deserialize<R: Read>(reader: &mut R) -> io::Result<Self> {
@@ -194,6 +220,18 @@ mod binary_serialize {
                 }
                 HIERARCHICAL_FACET_CODE => Ok(Value::Facet(Facet::deserialize(reader)?)),
                 BYTES_CODE => Ok(Value::Bytes(Vec::<u8>::deserialize(reader)?)),
+                GEO_POINT_CODE => {
+                    let lat = f64::from_bits(u64::deserialize(reader)?);
+                    let lon = f64::from_bits(u64::deserialize(reader)?);
+                    if GeoPoint::is_valid(lat, lon) {
+                        Ok(Value::GeoPoint(GeoPoint::new(lat, lon)))
+                    } else {
+                        Err(io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            format!("Invalid geo point (lat: {}, lon: {})", lat, lon),
+                        ))
+                    }
+                }
                 _ => Err(io::Error::new(
                     io::ErrorKind::InvalidData,
                     format!("No field type is associated with code {:?}", type_code),
@@ -5,6 +5,7 @@ use common::CountingWriter;
 use directory::ReadOnlySource;
 use fst;
 use fst::raw::Fst;
+use fst::Automaton;
 use postings::TermInfo;
 use schema::FieldType;
 use std::io::{self, Write};
@@ -199,4 +200,14 @@ impl TermDictionary {
     pub fn stream<'a>(&'a self) -> TermStreamer<'a> {
         self.range().into_stream()
     }
+
+    /// Returns a search builder, to stream all of the terms
+    /// accepted by the given `Automaton`.
+    ///
+    /// The automaton is intersected with the underlying `fst`,
+    /// so that only the relevant part of the dictionary is visited.
+    pub fn search<'a, A: Automaton>(&'a self, automaton: A) -> TermStreamerBuilder<'a, A> {
+        let stream_builder = self.fst_index.search(automaton);
+        TermStreamerBuilder::new(self, stream_builder)
+    }
 }